@@ -0,0 +1,52 @@
+use std::{env::args, io};
+
+use lux_types::key;
+
+/// softhsm2-util --init-token --free --label test --so-pin 1234 --pin 1234
+/// cargo run --example key_secp256k1_kms_pkcs11 --features="kms_pkcs11" -- /usr/lib/softhsm/libsofthsm2.so [SLOT_ID] 1234
+#[tokio::main]
+async fn main() -> io::Result<()> {
+    // ref. <https://github.com/env-logger-rs/env_logger/issues/47>
+    env_logger::init_from_env(
+        env_logger::Env::default().filter_or(env_logger::DEFAULT_FILTER_ENV, "debug"),
+    );
+
+    let module_path = args().nth(1).expect("no module path given");
+    let slot_id: u64 = args()
+        .nth(2)
+        .expect("no slot id given")
+        .parse()
+        .expect("invalid slot id");
+    let pin = args().nth(3).expect("no pin given");
+
+    log::info!("creating PKCS#11 key pair!");
+    let label = id_manager::time::with_prefix("test");
+    let key = key::secp256k1::kms::pkcs11::Key::create(&module_path, slot_id, &pin, &label)
+        .await
+        .unwrap();
+
+    let key_info = key.to_info(1).unwrap();
+    println!("key_info:\n{}", key_info);
+
+    let key2 = key::secp256k1::kms::pkcs11::Key::from_label(&module_path, slot_id, &pin, &label)
+        .await
+        .unwrap();
+    let key_info2 = key2.to_info(1).unwrap();
+    println!("key_info2:\n{}", key_info2);
+    assert_eq!(key_info.eth_address, key_info2.eth_address);
+
+    let digest = [0u8; ring::digest::SHA256_OUTPUT_LEN];
+    match key.sign_digest(&digest).await {
+        Ok(sig) => {
+            log::info!(
+                "successfully signed with signature output {} bytes",
+                sig.to_vec().len()
+            );
+        }
+        Err(e) => {
+            log::warn!("failed to sign, error: {:?}", e);
+        }
+    }
+
+    Ok(())
+}
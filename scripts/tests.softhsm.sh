@@ -0,0 +1,31 @@
+#!/usr/bin/env bash
+set -xue
+
+if ! [[ "$0" =~ scripts/tests.softhsm.sh ]]; then
+  echo "must be run from repository root"
+  exit 255
+fi
+
+# Runs the ignored PKCS#11 tests against a fresh SoftHSM token.
+# e.g., "sudo apt-get install -y softhsm2" on Ubuntu
+PKCS11_MODULE=${PKCS11_MODULE:-/usr/lib/softhsm/libsofthsm2.so}
+PKCS11_PIN=${PKCS11_PIN:-1234}
+
+# keeps the test token out of the system token directory
+SOFTHSM2_DIR=$(mktemp -d)
+trap 'rm -rf "${SOFTHSM2_DIR}"' EXIT
+mkdir -p "${SOFTHSM2_DIR}/tokens"
+echo "directories.tokendir = ${SOFTHSM2_DIR}/tokens" > "${SOFTHSM2_DIR}/softhsm2.conf"
+export SOFTHSM2_CONF="${SOFTHSM2_DIR}/softhsm2.conf"
+
+softhsm2-util --init-token --free --label lux-types-test --so-pin "${PKCS11_PIN}" --pin "${PKCS11_PIN}"
+PKCS11_SLOT_ID=$(softhsm2-util --show-slots | awk '/^Slot [0-9]+/ { slot = $2 } /Label: +lux-types-test/ { print slot; exit }')
+
+PKCS11_MODULE=${PKCS11_MODULE} \
+PKCS11_SLOT_ID=${PKCS11_SLOT_ID} \
+PKCS11_PIN=${PKCS11_PIN} \
+RUST_LOG=debug cargo test --package lux-types --lib \
+--features kms_pkcs11 \
+-- key::secp256k1::kms::pkcs11 --ignored --show-output
+
+echo "ALL SUCCESS!"
@@ -13,10 +13,14 @@ RUST_LOG=debug cargo test --workspace \
 --features lux-types/codec_big_int \
 --features lux-types/evm \
 --features lux-types/jsonrpc_client \
+--features lux-types/keystore \
 --features lux-types/kms_aws \
+--features lux-types/kms_grpc \
+--features lux-types/kms_pkcs11 \
 --features lux-types/libsecp256k1 \
 --features lux-types/message \
 --features lux-types/mnemonic \
+--features lux-types/staking \
 --features lux-types/subnet \
 --features lux-types/subnet_evm \
 --features lux-types/wallet \
@@ -264,6 +264,16 @@ impl Tx {
     }
 }
 
+impl txs::credentials::Signed for Tx {
+    fn base_tx(&self) -> &txs::Tx {
+        &self.base_tx
+    }
+
+    fn credentials(&self) -> Vec<&key::secp256k1::txs::Credential> {
+        self.fx_creds.iter().map(|c| &c.cred).collect()
+    }
+}
+
 /// RUST_LOG=debug cargo test --package lux-types --lib -- avm::txs::export::test_export_tx_serialization_with_two_signers --exact --show-output
 /// ref. "node/vms/avm.TestExportTxSerialization"
 #[test]
@@ -254,6 +254,29 @@ impl Tx {
     }
 }
 
+impl txs::credentials::Signed for Tx {
+    fn base_tx(&self) -> &txs::Tx {
+        &self.base_tx
+    }
+
+    fn credentials(&self) -> Vec<&key::secp256k1::txs::Credential> {
+        self.fx_creds.iter().map(|c| &c.cred).collect()
+    }
+
+    /// Base inputs followed by the imported inputs.
+    /// ref. "vms/avm/txs/executor.SemanticVerifier.ImportTx"
+    fn spent_inputs(&self) -> Vec<&txs::transferable::Input> {
+        let mut ins: Vec<&txs::transferable::Input> = Vec::new();
+        if let Some(base_ins) = &self.base_tx.transferable_inputs {
+            ins.extend(base_ins.iter());
+        }
+        if let Some(imported_ins) = &self.source_chain_transferable_inputs {
+            ins.extend(imported_ins.iter());
+        }
+        ins
+    }
+}
+
 /// RUST_LOG=debug cargo test --package lux-types --lib -- avm::txs::import::test_import_tx_serialization_with_two_signers --exact --show-output
 /// ref. "node/vms/avm.TestImportTxSerialization"
 #[test]
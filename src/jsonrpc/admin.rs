@@ -1,4 +1,7 @@
-use std::io::{self, Error as ioError, ErrorKind};
+use std::{
+    collections::HashMap,
+    io::{self, Error as ioError, ErrorKind},
+};
 
 use serde::{Deserialize, Serialize};
 
@@ -55,9 +58,113 @@ pub struct ChainAliasResponse {
     pub id: u32,
 }
 
+/// Response for the admin calls that reply with no result
+/// (e.g., "admin.memoryProfile", "admin.lockProfile").
+/// ref. <https://docs.lux.network/apis/node/apis/admin#adminmemoryprofile>
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct EmptyResponse {
+    /// Jsonrpc version
+    pub jsonrpc: String,
+    /// Id of request
+    pub id: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<super::ResponseError>,
+}
+
+/// Response for "admin.getChainAliases".
+/// ref. <https://docs.lux.network/apis/node/apis/admin#admingetchainaliases>
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct GetChainAliasesResponse {
+    /// Jsonrpc version
+    pub jsonrpc: String,
+    /// Id of request
+    pub id: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<GetChainAliasesResult>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<super::ResponseError>,
+}
+
+/// Result for "admin.getChainAliases".
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct GetChainAliasesResult {
+    /// All aliases of the chain
+    pub aliases: Vec<String>,
+}
+
+/// Response for "admin.getLoggerLevel" and "admin.setLoggerLevel".
+/// ref. <https://docs.lux.network/apis/node/apis/admin#admingetloggerlevel>
+/// ref. <https://docs.lux.network/apis/node/apis/admin#adminsetloggerlevel>
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct LoggerLevelResponse {
+    /// Jsonrpc version
+    pub jsonrpc: String,
+    /// Id of request
+    pub id: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<LoggerLevelResult>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<super::ResponseError>,
+}
+
+/// Result for "admin.getLoggerLevel" and "admin.setLoggerLevel".
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LoggerLevelResult {
+    /// Levels of each logger, keyed by the logger name
+    pub logger_levels: HashMap<String, LogAndDisplayLevels>,
+}
+
+/// Log levels of a logger.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LogAndDisplayLevels {
+    /// Level of the logs written to the file
+    pub log_level: String,
+    /// Level of the logs displayed on the console
+    pub display_level: String,
+}
+
+/// Response for "admin.loadVMs".
+/// ref. <https://docs.lux.network/apis/node/apis/admin#adminloadvms>
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct LoadVmsResponse {
+    /// Jsonrpc version
+    pub jsonrpc: String,
+    /// Id of request
+    pub id: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<LoadVmsResult>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<super::ResponseError>,
+}
+
+/// Result for "admin.loadVMs".
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct LoadVmsResult {
+    /// Aliases of the newly installed VMs, keyed by the VM ID
+    #[serde(rename = "newVMs")]
+    pub new_vms: HashMap<String, Vec<String>>,
+    /// Errors of the VMs that failed to load, keyed by the VM ID
+    #[serde(rename = "failedVMs", default, skip_serializing_if = "Option::is_none")]
+    pub failed_vms: Option<HashMap<String, String>>,
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::jsonrpc::admin::{ChainAliasParams, ChainAliasRequest, ChainAliasResponse};
+    use std::collections::HashMap;
+
+    use crate::jsonrpc::admin::{
+        ChainAliasParams, ChainAliasRequest, ChainAliasResponse, GetChainAliasesResponse,
+        GetChainAliasesResult, LoadVmsResponse, LogAndDisplayLevels, LoggerLevelResponse,
+    };
     use crate::jsonrpc::{DEFAULT_ID, DEFAULT_VERSION};
 
     #[test]
@@ -89,4 +196,57 @@ mod tests {
 
         assert_eq!(expected, deserialized);
     }
+
+    #[test]
+    fn test_get_chain_aliases() {
+        let response = r#"{"jsonrpc":"2.0","result":{"aliases":["X","avm","2eNy1mUFdmaxXNj1eQHUe7Np4gju9sJsEtWQ4MX3ToiNKuADed"]},"id":1}"#;
+        let deserialized: GetChainAliasesResponse =
+            serde_json::from_str(response).expect("failed deserialization");
+
+        let expected = GetChainAliasesResponse {
+            jsonrpc: String::from(DEFAULT_VERSION),
+            id: DEFAULT_ID,
+            result: Some(GetChainAliasesResult {
+                aliases: vec![
+                    String::from("X"),
+                    String::from("avm"),
+                    String::from("2eNy1mUFdmaxXNj1eQHUe7Np4gju9sJsEtWQ4MX3ToiNKuADed"),
+                ],
+            }),
+            error: None,
+        };
+        assert_eq!(expected, deserialized);
+    }
+
+    #[test]
+    fn test_logger_level() {
+        let response = r#"{"jsonrpc":"2.0","result":{"loggerLevels":{"C":{"logLevel":"DEBUG","displayLevel":"INFO"}}},"id":1}"#;
+        let deserialized: LoggerLevelResponse =
+            serde_json::from_str(response).expect("failed deserialization");
+
+        let logger_levels = deserialized.result.expect("no result").logger_levels;
+        assert_eq!(
+            logger_levels.get("C"),
+            Some(&LogAndDisplayLevels {
+                log_level: String::from("DEBUG"),
+                display_level: String::from("INFO"),
+            })
+        );
+    }
+
+    #[test]
+    fn test_load_vms() {
+        let response = r#"{"jsonrpc":"2.0","result":{"newVMs":{"tGas3T58KzdjLHhBDMnH2TvrddhqTji5iZAMZ3RXs2NLpSnhH":["foovm"]},"failedVMs":{"rXJsCSEYXg2TehWxCEEGj6JU2PWKTkd6cBdNLjoe2SpsKD9cy":"error message"}},"id":1}"#;
+        let deserialized: LoadVmsResponse =
+            serde_json::from_str(response).expect("failed deserialization");
+
+        let result = deserialized.result.expect("no result");
+        let mut new_vms = HashMap::new();
+        new_vms.insert(
+            String::from("tGas3T58KzdjLHhBDMnH2TvrddhqTji5iZAMZ3RXs2NLpSnhH"),
+            vec![String::from("foovm")],
+        );
+        assert_eq!(result.new_vms, new_vms);
+        assert_eq!(result.failed_vms.expect("no failed VMs").len(), 1);
+    }
 }
@@ -0,0 +1,250 @@
+use std::{
+    collections::HashMap,
+    io::{self, Error, ErrorKind},
+    marker::PhantomData,
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// Represents a single call in a JSON-RPC 2.0 batch request.
+/// ref. <https://www.jsonrpc.org/specification#batch>
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct Call {
+    pub jsonrpc: String,
+    pub id: u32,
+
+    pub method: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub params: Option<serde_json::Value>,
+}
+
+/// Refers to the result of a call in the batch, decoded as "T".
+/// Returned when the call is added, and used to look up its result
+/// in the responses.
+#[derive(Debug)]
+pub struct Handle<T> {
+    pub id: u32,
+    _result: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Handle<T> {}
+
+/// Builds a JSON-RPC 2.0 batch of heterogeneous calls that are sent
+/// in a single HTTP round trip. Each call is assigned a unique request Id
+/// in the order it is added, starting at "DEFAULT_ID".
+/// ref. <https://www.jsonrpc.org/specification#batch>
+#[derive(Debug, PartialEq, Clone)]
+pub struct Batch {
+    pub calls: Vec<Call>,
+}
+
+impl Default for Batch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Batch {
+    pub fn new() -> Self {
+        Self { calls: Vec::new() }
+    }
+
+    /// Adds a call with the params, which are serialized as is
+    /// (e.g., a struct or a map for the node APIs, an array for the EVM APIs).
+    pub fn add<P: Serialize, T>(
+        &mut self,
+        method: &str,
+        params: Option<P>,
+    ) -> io::Result<Handle<T>> {
+        let params = match params {
+            Some(p) => Some(serde_json::to_value(p).map_err(|e| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("failed to serialize params {}", e),
+                )
+            })?),
+            None => None,
+        };
+
+        let id = super::DEFAULT_ID + self.calls.len() as u32;
+        self.calls.push(Call {
+            jsonrpc: String::from(super::DEFAULT_VERSION),
+            id,
+            method: method.to_string(),
+            params,
+        });
+
+        Ok(Handle {
+            id,
+            _result: PhantomData,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    pub fn encode_json(&self) -> io::Result<String> {
+        serde_json::to_string(&self.calls)
+            .map_err(|e| Error::new(ErrorKind::Other, format!("failed to serialize JSON {}", e)))
+    }
+}
+
+/// Represents a single response in a JSON-RPC 2.0 batch response,
+/// with the result left undecoded until its type is known.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct Response {
+    pub jsonrpc: String,
+    pub id: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<super::ResponseError>,
+}
+
+/// Responses to a batch request, keyed by the request Id.
+/// The server may return the responses in any order.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Responses {
+    pub responses: HashMap<u32, Response>,
+}
+
+impl Responses {
+    /// Decodes the batch response body.
+    /// A server that rejects the whole batch replies with a single error
+    /// object instead of an array, which is returned as an error.
+    pub fn decode_json(d: &[u8]) -> io::Result<Self> {
+        let resps: Vec<Response> = match serde_json::from_slice(d) {
+            Ok(v) => v,
+            Err(e) => {
+                if let Ok(resp) = serde_json::from_slice::<Response>(d) {
+                    if let Some(err) = resp.error {
+                        return Err(Error::new(
+                            ErrorKind::Other,
+                            format!(
+                                "batch request failed (code {}, message {})",
+                                err.code, err.message
+                            ),
+                        ));
+                    }
+                }
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("failed to deserialize JSON {}", e),
+                ));
+            }
+        };
+
+        let mut responses = HashMap::new();
+        for resp in resps {
+            // responses without an Id cannot be correlated (e.g., parse errors)
+            if let Some(id) = resp.id {
+                responses.insert(id, resp);
+            }
+        }
+        Ok(Self { responses })
+    }
+
+    /// Returns the raw response of the call, if any.
+    pub fn response(&self, id: u32) -> Option<&Response> {
+        self.responses.get(&id)
+    }
+
+    /// Returns the decoded result of the call.
+    /// Fails if the server returned no response or an error for the call.
+    pub fn get<T: DeserializeOwned>(&self, handle: &Handle<T>) -> io::Result<T> {
+        let resp = self.responses.get(&handle.id).ok_or_else(|| {
+            Error::new(
+                ErrorKind::NotFound,
+                format!("no response for request Id {}", handle.id),
+            )
+        })?;
+        if let Some(err) = &resp.error {
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!(
+                    "request Id {} failed (code {}, message {})",
+                    handle.id, err.code, err.message
+                ),
+            ));
+        }
+
+        let result = resp.result.clone().unwrap_or(serde_json::Value::Null);
+        serde_json::from_value(result).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "failed to deserialize result for request Id {} {}",
+                    handle.id, e
+                ),
+            )
+        })
+    }
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib -- jsonrpc::batch::test_batch --exact --show-output
+#[test]
+fn test_batch() {
+    use crate::{choices, jsonrpc::avm};
+
+    let mut batch = Batch::new();
+
+    let mut params = HashMap::new();
+    params.insert("txID", "2QouvFWUbjuySRxeX5xMbNCuAaKWfbk5FeEa2JmoF85RKLk2dD");
+    let h1: Handle<avm::GetTxStatusResult> = batch.add("avm.getTxStatus", Some(params)).unwrap();
+
+    let h2: Handle<String> = batch
+        .add(
+            "eth_getBalance",
+            Some(vec!["0x0000000000000000000000000000000000000000", "latest"]),
+        )
+        .unwrap();
+
+    let h3: Handle<String> = batch.add::<(), _>("eth_chainId", None).unwrap();
+    assert_eq!(batch.len(), 3);
+
+    assert_eq!(
+        batch.encode_json().unwrap(),
+        r#"[{"jsonrpc":"2.0","id":1,"method":"avm.getTxStatus","params":{"txID":"2QouvFWUbjuySRxeX5xMbNCuAaKWfbk5FeEa2JmoF85RKLk2dD"}},{"jsonrpc":"2.0","id":2,"method":"eth_getBalance","params":["0x0000000000000000000000000000000000000000","latest"]},{"jsonrpc":"2.0","id":3,"method":"eth_chainId"}]"#
+    );
+
+    // responses may come back in any order
+    let resps = Responses::decode_json(
+        br#"[
+    {"jsonrpc":"2.0","id":3,"error":{"code":-32601,"message":"the method eth_chainId does not exist"}},
+    {"jsonrpc":"2.0","id":2,"result":"0x1"},
+    {"jsonrpc":"2.0","id":1,"result":{"status":"Accepted"}}
+]"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        resps.get(&h1).unwrap().status,
+        choices::status::Status::Accepted
+    );
+    assert_eq!(resps.get(&h2).unwrap(), "0x1");
+    assert!(resps.get(&h3).is_err());
+    assert_eq!(
+        resps.response(h3.id).unwrap().error.as_ref().unwrap().code,
+        -32601
+    );
+
+    let err = Responses::decode_json(
+        br#"{"jsonrpc":"2.0","id":null,"error":{"code":-32600,"message":"empty batch"}}"#,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("empty batch"));
+}
@@ -1,10 +1,16 @@
-use std::time::Duration;
+use std::{collections::HashMap, time::Duration};
 
 use reqwest::{header::CONTENT_TYPE, ClientBuilder};
 
 use crate::{
     errors::{Error, Result},
-    jsonrpc::admin::{ChainAliasParams, ChainAliasRequest, ChainAliasResponse},
+    jsonrpc::{
+        self,
+        admin::{
+            ChainAliasParams, ChainAliasRequest, ChainAliasResponse, EmptyResponse,
+            GetChainAliasesResponse, LoadVmsResponse, LoggerLevelResponse,
+        },
+    },
     utils,
 };
 
@@ -83,3 +89,90 @@ pub async fn alias_chain(
 
     Ok(response)
 }
+
+/// e.g., "admin.getChainAliases".
+/// ref. <https://docs.lux.network/apis/node/apis/admin#admingetchainaliases>
+pub async fn get_chain_aliases(http_rpc: &str, chain: &str) -> Result<GetChainAliasesResponse> {
+    let mut params = HashMap::new();
+    params.insert(String::from("chain"), String::from(chain));
+
+    call(http_rpc, "admin.getChainAliases", params).await
+}
+
+/// e.g., "admin.setLoggerLevel".
+/// Sets the levels of all loggers if "logger_name" is None.
+/// A level that is None is left unchanged, but at least one level must be set.
+/// ref. <https://docs.lux.network/apis/node/apis/admin#adminsetloggerlevel>
+pub async fn set_logger_level(
+    http_rpc: &str,
+    logger_name: Option<&str>,
+    log_level: Option<&str>,
+    display_level: Option<&str>,
+) -> Result<LoggerLevelResponse> {
+    let mut params = HashMap::new();
+    if let Some(v) = logger_name {
+        params.insert(String::from("loggerName"), String::from(v));
+    }
+    if let Some(v) = log_level {
+        params.insert(String::from("logLevel"), String::from(v));
+    }
+    if let Some(v) = display_level {
+        params.insert(String::from("displayLevel"), String::from(v));
+    }
+
+    call(http_rpc, "admin.setLoggerLevel", params).await
+}
+
+/// e.g., "admin.getLoggerLevel".
+/// Returns the levels of all loggers if "logger_name" is None.
+/// ref. <https://docs.lux.network/apis/node/apis/admin#admingetloggerlevel>
+pub async fn get_logger_level(
+    http_rpc: &str,
+    logger_name: Option<&str>,
+) -> Result<LoggerLevelResponse> {
+    let mut params = HashMap::new();
+    if let Some(v) = logger_name {
+        params.insert(String::from("loggerName"), String::from(v));
+    }
+
+    call(http_rpc, "admin.getLoggerLevel", params).await
+}
+
+/// e.g., "admin.loadVMs".
+/// ref. <https://docs.lux.network/apis/node/apis/admin#adminloadvms>
+pub async fn load_vms(http_rpc: &str) -> Result<LoadVmsResponse> {
+    call(http_rpc, "admin.loadVMs", HashMap::new()).await
+}
+
+/// e.g., "admin.memoryProfile".
+/// Writes the memory profile to "mem.profile" in the node's profile directory.
+/// ref. <https://docs.lux.network/apis/node/apis/admin#adminmemoryprofile>
+pub async fn memory_profile(http_rpc: &str) -> Result<EmptyResponse> {
+    call(http_rpc, "admin.memoryProfile", HashMap::new()).await
+}
+
+/// e.g., "admin.lockProfile".
+/// Writes the mutex statistics to "lock.profile" in the node's profile directory.
+/// ref. <https://docs.lux.network/apis/node/apis/admin#adminlockprofile>
+pub async fn lock_profile(http_rpc: &str) -> Result<EmptyResponse> {
+    call(http_rpc, "admin.lockProfile", HashMap::new()).await
+}
+
+async fn call<T: serde::de::DeserializeOwned>(
+    http_rpc: &str,
+    method: &str,
+    params: HashMap<String, String>,
+) -> Result<T> {
+    let u = super::url(http_rpc, "/ext/admin")?;
+    log::info!("calling {method} via {u}");
+
+    let mut data = jsonrpc::Request::default();
+    data.method = String::from(method);
+    data.params = Some(params);
+    let d = data.encode_json().map_err(|e| Error::Other {
+        message: format!("failed encode_json '{}'", e),
+        retryable: false,
+    })?;
+
+    super::post(&u, d).await
+}
@@ -0,0 +1,31 @@
+use crate::{
+    errors::{Error, Result},
+    jsonrpc::batch,
+};
+
+/// Sends all calls in the batch to the API path (e.g., "/ext/bc/C/rpc")
+/// in a single HTTP round trip, and returns the responses keyed by request Id.
+/// The API must support JSON-RPC 2.0 batch requests.
+/// ref. <https://www.jsonrpc.org/specification#batch>
+pub async fn send(http_rpc: &str, path: &str, b: &batch::Batch) -> Result<batch::Responses> {
+    if b.is_empty() {
+        return Err(Error::Other {
+            message: String::from("empty batch"),
+            retryable: false,
+        });
+    }
+
+    let u = super::url(http_rpc, path)?;
+    log::info!("sending a batch of {} calls via {u}", b.len());
+
+    let d = b.encode_json().map_err(|e| Error::Other {
+        message: format!("failed encode_json '{}'", e),
+        retryable: false,
+    })?;
+
+    let out = super::post_bytes(&u, d).await?;
+    batch::Responses::decode_json(&out).map_err(|e| Error::API {
+        message: format!("failed batch::Responses::decode_json '{}'", e),
+        retryable: false,
+    })
+}
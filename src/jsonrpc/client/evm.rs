@@ -1,50 +1,235 @@
-use std::time::Duration;
+use std::collections::HashMap;
 
-use crate::errors::{Error, Result};
-use ethers_providers::{Http, Middleware, Provider};
-use primitive_types::{H160, U256};
+use crate::{
+    errors::{Error, Result},
+    ids,
+    jsonrpc::{self, evm},
+};
+use primitive_types::{H160, H256, U256};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
 
 /// Fetches the chain Id from "{http_rpc}/ext/bc/{chain_id_alias}/rpc".
 /// "chain_id_alias" is "C" for C-chain, and blockchain Id for subnet-evm.
 pub async fn chain_id(rpc_ep: &str) -> Result<U256> {
-    let provider = Provider::<Http>::try_from(rpc_ep)
-        .map_err(|e| {
-            // TODO: check retryable
-            Error::API {
-                message: format!("failed to create provider '{}'", e),
-                retryable: false,
-            }
-        })?
-        .interval(Duration::from_millis(2000u64));
-
     log::info!("getting chain id via {rpc_ep}");
-    provider.get_chainid().await.map_err(|e|
-            // TODO: check retryable
-            Error::API {
-                message: format!("failed to get_chainid '{}'", e),
-                retryable: false,
-            })
+    let resp: evm::ChainIdResponse = call(rpc_ep, "eth_chainId", vec![]).await?;
+    Ok(resp.result)
 }
 
 /// Fetches the balance from "{http_rpc}/ext/bc/{chain_id_alias}/rpc".
 /// "chain_id_alias" is "C" for C-chain, and blockchain Id for subnet-evm.
 /// ref. <https://docs.lux.network/build/node-apis/c-chain#eth_getassetbalance>
 pub async fn get_balance(rpc_ep: &str, eth_addr: H160) -> Result<U256> {
-    let provider = Provider::<Http>::try_from(rpc_ep)
-        .map_err(|e| {
-            // TODO: check retryable
-            Error::API {
-                message: format!("failed to create provider '{}'", e),
-                retryable: false,
-            }
-        })?
-        .interval(Duration::from_millis(2000u64));
-
     log::info!("getting balances for {} via {rpc_ep}", eth_addr);
-    provider.get_balance(eth_addr, None).await.map_err(|e|
-            // TODO: check retryable
-            Error::API {
-                message: format!("failed get_balance '{}'", e),
-                retryable: false,
-            })
+    let resp: evm::GetBalanceResponse = call(
+        rpc_ep,
+        "eth_getBalance",
+        vec![json!(eth_addr), json!("latest")],
+    )
+    .await?;
+    Ok(resp.result)
+}
+
+/// Fetches the balance of the X-chain asset (e.g., an ANT imported to the C-chain)
+/// at the block (e.g., "latest", "0x1").
+/// ref. <https://docs.lux.network/build/node-apis/c-chain#eth_getassetbalance>
+pub async fn get_asset_balance(
+    rpc_ep: &str,
+    eth_addr: H160,
+    block: &str,
+    asset_id: &ids::Id,
+) -> Result<U256> {
+    log::info!(
+        "getting asset {} balances for {} via {rpc_ep}",
+        asset_id,
+        eth_addr
+    );
+    let resp: evm::GetBalanceResponse = call(
+        rpc_ep,
+        "eth_getAssetBalance",
+        vec![json!(eth_addr), json!(block), json!(asset_id.to_string())],
+    )
+    .await?;
+    Ok(resp.result)
+}
+
+/// Fetches the latest block number.
+/// ref. <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_blocknumber>
+pub async fn block_number(rpc_ep: &str) -> Result<U256> {
+    log::info!("getting block number via {rpc_ep}");
+    let resp: evm::BlockNumberResponse = call(rpc_ep, "eth_blockNumber", vec![]).await?;
+    Ok(resp.result)
+}
+
+/// Fetches the current gas price.
+/// ref. <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_gasprice>
+pub async fn gas_price(rpc_ep: &str) -> Result<U256> {
+    log::info!("getting gas price via {rpc_ep}");
+    let resp: evm::GasPriceResponse = call(rpc_ep, "eth_gasPrice", vec![]).await?;
+    Ok(resp.result)
+}
+
+/// Fetches the base fee for the next block.
+/// ref. <https://docs.lux.network/apis/node/apis/c-chain#eth_basefee>
+pub async fn base_fee(rpc_ep: &str) -> Result<U256> {
+    log::info!("getting base fee via {rpc_ep}");
+    let resp: evm::BaseFeeResponse = call(rpc_ep, "eth_baseFee", vec![]).await?;
+    Ok(resp.result)
+}
+
+/// Fetches the suggested priority fee (tip) for the next block.
+/// ref. <https://docs.lux.network/apis/node/apis/c-chain#eth_maxpriorityfeepergas>
+pub async fn max_priority_fee_per_gas(rpc_ep: &str) -> Result<U256> {
+    log::info!("getting max priority fee per gas via {rpc_ep}");
+    let resp: evm::MaxPriorityFeePerGasResponse =
+        call(rpc_ep, "eth_maxPriorityFeePerGas", vec![]).await?;
+    Ok(resp.result)
+}
+
+/// Fetches the fee history of "block_count" blocks up to "newest_block"
+/// (e.g., "latest"), with the priority fees at the "reward_percentiles".
+/// ref. <https://ethereum.github.io/execution-apis/api-documentation>
+pub async fn fee_history(
+    rpc_ep: &str,
+    block_count: u64,
+    newest_block: &str,
+    reward_percentiles: &[f64],
+) -> Result<evm::FeeHistoryResult> {
+    log::info!("getting fee history via {rpc_ep}");
+    let resp: evm::FeeHistoryResponse = call(
+        rpc_ep,
+        "eth_feeHistory",
+        vec![
+            json!(format!("0x{:x}", block_count)),
+            json!(newest_block),
+            json!(reward_percentiles),
+        ],
+    )
+    .await?;
+    Ok(resp.result)
+}
+
+/// Fetches the number of transactions sent from the address
+/// at the block (e.g., "latest", "pending"), which is the next nonce.
+/// ref. <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_gettransactioncount>
+pub async fn get_transaction_count(rpc_ep: &str, eth_addr: H160, block: &str) -> Result<U256> {
+    log::info!("getting transaction count for {} via {rpc_ep}", eth_addr);
+    let resp: evm::GetTransactionCountResponse = call(
+        rpc_ep,
+        "eth_getTransactionCount",
+        vec![json!(eth_addr), json!(block)],
+    )
+    .await?;
+    Ok(resp.result)
+}
+
+/// Fetches the receipt of the transaction.
+/// Returns None if the transaction is pending or unknown.
+/// ref. <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_gettransactionreceipt>
+pub async fn get_transaction_receipt(
+    rpc_ep: &str,
+    tx_hash: H256,
+) -> Result<Option<evm::GetTransactionReceiptResult>> {
+    log::info!(
+        "getting transaction receipt for 0x{:x} via {rpc_ep}",
+        tx_hash
+    );
+    let resp: evm::GetTransactionReceiptResponse =
+        call(rpc_ep, "eth_getTransactionReceipt", vec![json!(tx_hash)]).await?;
+    Ok(resp.result)
+}
+
+/// Submits the signed and RLP-encoded transaction, and returns its hash.
+/// ref. <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_sendrawtransaction>
+pub async fn send_raw_transaction(rpc_ep: &str, signed_tx: &[u8]) -> Result<H256> {
+    log::info!("sending raw transaction via {rpc_ep}");
+    let resp: evm::SendRawTransactionResponse = call(
+        rpc_ep,
+        "eth_sendRawTransaction",
+        vec![json!(format!("0x{}", hex::encode(signed_tx)))],
+    )
+    .await?;
+    resp.result.ok_or_else(|| Error::API {
+        message: String::from("eth_sendRawTransaction returned no transaction hash"),
+        retryable: false,
+    })
+}
+
+/// Executes the call at the block (e.g., "latest") without creating a transaction,
+/// and returns the return value of the executed contract.
+/// ref. <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_call>
+pub async fn call_contract(rpc_ep: &str, req: &evm::CallRequest, block: &str) -> Result<Vec<u8>> {
+    log::info!("calling {:?} via {rpc_ep}", req.to);
+    let resp: evm::CallResponse = call(rpc_ep, "eth_call", vec![json!(req), json!(block)]).await?;
+    Ok(resp.result)
+}
+
+/// Estimates the gas needed for the transaction to complete.
+/// ref. <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_estimategas>
+pub async fn estimate_gas(rpc_ep: &str, req: &evm::CallRequest) -> Result<U256> {
+    log::info!("estimating gas for {:?} via {rpc_ep}", req.to);
+    let resp: evm::EstimateGasResponse = call(rpc_ep, "eth_estimateGas", vec![json!(req)]).await?;
+    Ok(resp.result)
+}
+
+/// Fetches the logs matching the filter.
+/// ref. <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_getlogs>
+pub async fn get_logs(rpc_ep: &str, filter: &evm::LogFilter) -> Result<Vec<evm::Log>> {
+    log::info!("getting logs via {rpc_ep}");
+    let resp: evm::GetLogsResponse = call(rpc_ep, "eth_getLogs", vec![json!(filter)]).await?;
+    Ok(resp.result)
+}
+
+/// e.g., "avax.getAtomicTxStatus" on "http://[ADDR]:9650" and "/ext/bc/C/avax" path.
+/// ref. <https://docs.lux.network/apis/node/apis/c-chain#avaxgetatomictxstatus>
+pub async fn get_atomic_tx_status(
+    http_rpc: &str,
+    tx_id: &ids::Id,
+) -> Result<evm::GetAtomicTxStatusResponse> {
+    let u = super::url(http_rpc, "/ext/bc/C/avax")?;
+    log::info!("getting atomic tx status via {u}");
+
+    let mut data = jsonrpc::Request::default();
+    data.method = String::from("avax.getAtomicTxStatus");
+    let mut params = HashMap::new();
+    params.insert(String::from("txID"), tx_id.to_string());
+    data.params = Some(params);
+    let d = data.encode_json().map_err(|e| Error::Other {
+        message: format!("failed encode_json '{}'", e),
+        retryable: false,
+    })?;
+
+    super::post(&u, d).await
+}
+
+/// Error part of the response, checked before decoding the result
+/// since the EVM response types do not carry the error.
+#[derive(Deserialize)]
+struct ErrorResponse {
+    error: Option<jsonrpc::ResponseError>,
+}
+
+/// Posts the "method" with the positional "params" to the RPC endpoint
+/// (e.g., "http://[ADDR]:9650/ext/bc/C/rpc"), and decodes the response.
+async fn call<T: DeserializeOwned>(rpc_ep: &str, method: &str, params: Vec<Value>) -> Result<T> {
+    let d = json!({
+        "jsonrpc": jsonrpc::DEFAULT_VERSION,
+        "id": jsonrpc::DEFAULT_ID,
+        "method": method,
+        "params": params,
+    })
+    .to_string();
+
+    let out = super::post_bytes(rpc_ep, d).await?;
+    if let Ok(ErrorResponse { error: Some(e) }) = serde_json::from_slice(&out) {
+        return Err(Error::API {
+            message: format!("failed {method} (code {}, message '{}')", e.code, e.message),
+            retryable: false,
+        });
+    }
+    serde_json::from_slice(&out).map_err(|e| Error::Other {
+        message: format!("failed serde_json::from_slice '{}'", e),
+        retryable: false,
+    })
 }
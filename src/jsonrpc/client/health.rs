@@ -1,8 +1,8 @@
-use std::{sync::Arc, time::Duration};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use crate::{
     errors::{Error, Result},
-    jsonrpc::health,
+    jsonrpc::{self, health},
 };
 use reqwest::ClientBuilder;
 
@@ -62,3 +62,36 @@ pub async fn spawn_check(http_rpc: &str, liveness: bool) -> Result<health::Respo
         .await
         .expect("failed spawn await")
 }
+
+/// e.g., "health.health".
+/// Only reports the checks that are registered with any of the tags
+/// (e.g., subnet Ids), if "tags" is non-empty.
+/// ref. <https://docs.lux.network/apis/node/apis/health#healthhealth>
+pub async fn health(http_rpc: &str, tags: &[String]) -> Result<health::HealthResponse> {
+    call(http_rpc, "health.health", tags).await
+}
+
+/// e.g., "health.readiness".
+/// Returns healthy once the node finished bootstrapping the chains
+/// that are registered with any of the tags, or all chains if "tags" is empty.
+/// ref. <https://docs.lux.network/apis/node/apis/health#healthreadiness>
+pub async fn readiness(http_rpc: &str, tags: &[String]) -> Result<health::HealthResponse> {
+    call(http_rpc, "health.readiness", tags).await
+}
+
+async fn call(http_rpc: &str, method: &str, tags: &[String]) -> Result<health::HealthResponse> {
+    let u = super::url(http_rpc, "/ext/health")?;
+    log::info!("calling {method} via {u}");
+
+    let mut data = jsonrpc::RequestWithParamsHashMapToArray::default();
+    data.method = String::from(method);
+    let mut params = HashMap::new();
+    params.insert(String::from("tags"), tags.to_vec());
+    data.params = Some(params);
+    let d = data.encode_json().map_err(|e| Error::Other {
+        message: format!("failed encode_json '{}'", e),
+        retryable: false,
+    })?;
+
+    super::post(&u, d).await
+}
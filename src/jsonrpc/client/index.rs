@@ -0,0 +1,235 @@
+use std::{collections::HashMap, time::Duration};
+
+use crate::{
+    errors::{Error, Result},
+    ids,
+    jsonrpc::{self, index},
+};
+use futures::stream::{self, Stream};
+use serde::de::DeserializeOwned;
+
+/// e.g., "index.getLastAccepted" on "http://[ADDR]:9650" and "/ext/index/X/tx" path.
+/// "index_path" is the chain alias and the index name (e.g., "X/tx", "X/vtx",
+/// "X/block", "P/block", "C/block").
+/// ref. <https://docs.lux.network/apis/node/apis/index-api#indexgetlastaccepted>
+pub async fn get_last_accepted(
+    http_rpc: &str,
+    index_path: &str,
+) -> Result<index::GetContainerResponse> {
+    let mut params = HashMap::new();
+    params.insert(String::from("encoding"), String::from("hex"));
+
+    call(http_rpc, index_path, "index.getLastAccepted", params).await
+}
+
+/// e.g., "index.getContainerByIndex" on "http://[ADDR]:9650" and "/ext/index/X/tx" path.
+/// ref. <https://docs.lux.network/apis/node/apis/index-api#indexgetcontainerbyindex>
+pub async fn get_container_by_index(
+    http_rpc: &str,
+    index_path: &str,
+    idx: u64,
+) -> Result<index::GetContainerResponse> {
+    let mut params = HashMap::new();
+    params.insert(String::from("index"), idx.to_string());
+    params.insert(String::from("encoding"), String::from("hex"));
+
+    call(http_rpc, index_path, "index.getContainerByIndex", params).await
+}
+
+/// e.g., "index.getContainerRange" on "http://[ADDR]:9650" and "/ext/index/X/tx" path.
+/// Fetches at most "index::MAX_FETCHED_BY_RANGE" containers starting at "start_index".
+/// ref. <https://docs.lux.network/apis/node/apis/index-api#indexgetcontainerrange>
+pub async fn get_container_range(
+    http_rpc: &str,
+    index_path: &str,
+    start_index: u64,
+    num_to_fetch: u64,
+) -> Result<index::GetContainerRangeResponse> {
+    let mut params = HashMap::new();
+    params.insert(String::from("startIndex"), start_index.to_string());
+    params.insert(String::from("numToFetch"), num_to_fetch.to_string());
+    params.insert(String::from("encoding"), String::from("hex"));
+
+    call(http_rpc, index_path, "index.getContainerRange", params).await
+}
+
+/// e.g., "index.getIndex" on "http://[ADDR]:9650" and "/ext/index/X/tx" path.
+/// ref. <https://docs.lux.network/apis/node/apis/index-api#indexgetindex>
+pub async fn get_index(
+    http_rpc: &str,
+    index_path: &str,
+    container_id: &ids::Id,
+) -> Result<index::GetIndexResponse> {
+    let mut params = HashMap::new();
+    params.insert(String::from("id"), container_id.to_string());
+
+    call(http_rpc, index_path, "index.getIndex", params).await
+}
+
+/// e.g., "index.isAccepted" on "http://[ADDR]:9650" and "/ext/index/X/tx" path.
+/// ref. <https://docs.lux.network/apis/node/apis/index-api#indexisaccepted>
+pub async fn is_accepted(
+    http_rpc: &str,
+    index_path: &str,
+    container_id: &ids::Id,
+) -> Result<index::IsAcceptedResponse> {
+    let mut params = HashMap::new();
+    params.insert(String::from("id"), container_id.to_string());
+
+    call(http_rpc, index_path, "index.isAccepted", params).await
+}
+
+/// Returns a stream that yields every accepted container in the index
+/// in order, starting from "start_index" and following the tip forever.
+/// When the stream catches up with the last accepted container, it polls
+/// "index.getLastAccepted" every "poll_interval" for new containers.
+/// Failed calls are yielded as errors and retried on the next poll,
+/// so the caller decides when to stop consuming.
+pub fn follow(
+    http_rpc: &str,
+    index_path: &str,
+    start_index: u64,
+    poll_interval: Duration,
+) -> impl Stream<Item = Result<index::Container>> {
+    let state = Follower {
+        http_rpc: http_rpc.to_string(),
+        index_path: index_path.to_string(),
+        next_index: start_index,
+        poll_interval,
+        buffered: Vec::new(),
+        polled: false,
+    };
+
+    stream::unfold(state, |mut state| async move {
+        let item = state.next().await;
+        Some((item, state))
+    })
+}
+
+struct Follower {
+    http_rpc: String,
+    index_path: String,
+    next_index: u64,
+    poll_interval: Duration,
+
+    /// Fetched containers yet to be yielded, in reverse order.
+    buffered: Vec<index::Container>,
+    /// Whether the last accepted index has been checked at least once.
+    polled: bool,
+}
+
+impl Follower {
+    async fn next(&mut self) -> Result<index::Container> {
+        loop {
+            if let Some(container) = self.buffered.pop() {
+                self.next_index = container.index + 1;
+                return Ok(container);
+            }
+
+            if self.polled {
+                tokio::time::sleep(self.poll_interval).await;
+            }
+            self.polled = true;
+
+            let last_index = match self.last_accepted_index().await? {
+                Some(last_index) if last_index >= self.next_index => last_index,
+                _ => continue,
+            };
+
+            let num_to_fetch = (last_index - self.next_index + 1).min(index::MAX_FETCHED_BY_RANGE);
+            let resp = get_container_range(
+                &self.http_rpc,
+                &self.index_path,
+                self.next_index,
+                num_to_fetch,
+            )
+            .await?;
+            let containers = match (resp.result, resp.error) {
+                (Some(result), _) => result.containers,
+                (None, Some(e)) => {
+                    return Err(Error::API {
+                        message: format!("failed index.getContainerRange '{}'", e.message),
+                        retryable: true,
+                    })
+                }
+                (None, None) => Vec::new(),
+            };
+
+            // skip anything already yielded, so a re-fetch never rewinds the stream
+            self.buffered = containers
+                .into_iter()
+                .filter(|c| c.index >= self.next_index)
+                .rev()
+                .collect();
+
+            // fetch the rest right away if we are still behind the tip
+            self.polled = self.buffered.is_empty();
+        }
+    }
+
+    /// Returns the index of the last accepted container, or None if the index
+    /// has no accepted container yet. The indexer reports the empty index as a
+    /// JSON-RPC error, which is told apart from the other errors (e.g., wrong
+    /// index path, indexer disabled) by its message.
+    async fn last_accepted_index(&self) -> Result<Option<u64>> {
+        let resp = get_last_accepted(&self.http_rpc, &self.index_path).await?;
+        match (resp.result, resp.error) {
+            (Some(container), _) => Ok(Some(container.index)),
+            (None, Some(e)) if is_none_accepted(&e) => {
+                log::debug!("no accepted container in {}", self.index_path);
+                Ok(None)
+            }
+            (None, Some(e)) => Err(Error::API {
+                message: format!("failed index.getLastAccepted '{}'", e.message),
+                retryable: false,
+            }),
+            (None, None) => Ok(None),
+        }
+    }
+}
+
+/// Error message of the indexer when no container has been accepted yet.
+/// ref. "indexer.errNoneAccepted"
+const NONE_ACCEPTED: &str = "no containers have been accepted";
+
+fn is_none_accepted(e: &jsonrpc::ResponseError) -> bool {
+    e.message.contains(NONE_ACCEPTED)
+}
+
+async fn call<T: DeserializeOwned>(
+    http_rpc: &str,
+    index_path: &str,
+    method: &str,
+    params: HashMap<String, String>,
+) -> Result<T> {
+    let u = super::url(http_rpc, &format!("/ext/index/{index_path}"))?;
+    log::debug!("calling {method} via {u}");
+
+    let mut data = jsonrpc::Request::default();
+    data.method = String::from(method);
+    data.params = Some(params);
+    let d = data.encode_json().map_err(|e| Error::Other {
+        message: format!("failed encode_json '{}'", e),
+        retryable: false,
+    })?;
+
+    super::post(&u, d).await
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features jsonrpc_client -- jsonrpc::client::index::test_is_none_accepted --exact --show-output
+#[test]
+fn test_is_none_accepted() {
+    let e = jsonrpc::ResponseError {
+        code: -32000,
+        message: String::from("no containers have been accepted"),
+        data: None,
+    };
+    assert!(is_none_accepted(&e));
+
+    let e = jsonrpc::ResponseError {
+        code: -32601,
+        message: String::from("the method index.getLastAccepted does not exist/is not available"),
+        data: None,
+    };
+    assert!(!is_none_accepted(&e));
+}
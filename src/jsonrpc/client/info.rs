@@ -670,3 +670,41 @@ pub async fn peers(
         retryable: false,
     })
 }
+
+/// e.g., "info.getNodeIP".
+/// ref. <https://docs.lux.network/build/node-apis/info/#infogetnodeip>
+pub async fn get_node_ip(http_rpc: &str) -> Result<info::GetNodeIpResponse> {
+    let u = super::url(http_rpc, "/ext/info")?;
+    log::info!("getting node IP for {u}");
+
+    let mut data = jsonrpc::RequestWithParamsArray::default();
+    data.method = String::from("info.getNodeIP");
+    let d = data.encode_json().map_err(|e| Error::Other {
+        message: format!("failed encode_json '{}'", e),
+        retryable: false,
+    })?;
+
+    super::post(&u, d).await
+}
+
+/// e.g., "info.uptime".
+/// Returns the uptime for the primary network if "subnet_id" is None.
+/// ref. <https://docs.lux.network/apis/node/apis/info#infouptime>
+pub async fn uptime(http_rpc: &str, subnet_id: Option<ids::Id>) -> Result<info::UptimeResponse> {
+    let u = super::url(http_rpc, "/ext/info")?;
+    log::info!("getting uptime for {u}");
+
+    let mut data = jsonrpc::Request::default();
+    data.method = String::from("info.uptime");
+    let mut params = HashMap::new();
+    if let Some(subnet_id) = subnet_id {
+        params.insert(String::from("subnetID"), subnet_id.to_string());
+    }
+    data.params = Some(params);
+    let d = data.encode_json().map_err(|e| Error::Other {
+        message: format!("failed encode_json '{}'", e),
+        retryable: false,
+    })?;
+
+    super::post(&u, d).await
+}
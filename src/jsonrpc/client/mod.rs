@@ -1,6 +1,84 @@
 pub mod admin;
+pub mod batch;
 pub mod evm;
 pub mod health;
+pub mod index;
 pub mod info;
 pub mod p;
 pub mod x;
+
+use std::time::Duration;
+
+use crate::{
+    errors::{Error, Result},
+    utils,
+};
+use reqwest::{header::CONTENT_TYPE, ClientBuilder};
+use serde::de::DeserializeOwned;
+
+/// Returns the URL for the API path (e.g., "/ext/admin") on the node
+/// that serves "http_rpc".
+pub(crate) fn url(http_rpc: &str, path: &str) -> Result<String> {
+    let (scheme, host, port, _, _) =
+        utils::urls::extract_scheme_host_port_path_chain_alias(http_rpc).map_err(|e| {
+            Error::Other {
+                message: format!("failed extract_scheme_host_port_path_chain_alias '{}'", e),
+                retryable: false,
+            }
+        })?;
+    let u = if let Some(scheme) = scheme {
+        if let Some(port) = port {
+            format!("{scheme}://{host}:{port}{path}")
+        } else {
+            format!("{scheme}://{host}{path}")
+        }
+    } else {
+        format!("http://{host}{path}")
+    };
+    Ok(u)
+}
+
+/// Posts the encoded JSON-RPC request to the URL and decodes the response.
+pub(crate) async fn post<T: DeserializeOwned>(u: &str, d: String) -> Result<T> {
+    let out = post_bytes(u, d).await?;
+    serde_json::from_slice(&out).map_err(|e| Error::Other {
+        message: format!("failed serde_json::from_slice '{}'", e),
+        retryable: false,
+    })
+}
+
+/// Posts the encoded JSON-RPC request to the URL and returns the raw response body.
+pub(crate) async fn post_bytes(u: &str, d: String) -> Result<Vec<u8>> {
+    let req_cli_builder = ClientBuilder::new()
+        .user_agent(env!("CARGO_PKG_NAME"))
+        .danger_accept_invalid_certs(true)
+        .timeout(Duration::from_secs(15))
+        .connection_verbose(true)
+        .build()
+        .map_err(|e| {
+            // TODO: check retryable
+            Error::Other {
+                message: format!("failed reqwest::ClientBuilder.build '{}'", e),
+                retryable: false,
+            }
+        })?;
+    let resp = req_cli_builder
+        .post(u)
+        .header(CONTENT_TYPE, "application/json")
+        .body(d)
+        .send()
+        .await
+        .map_err(|e| Error::API {
+            message: format!("failed reqwest::Client.send '{}'", e),
+            // connection failures and timeouts are transient
+            retryable: e.is_connect() || e.is_timeout(),
+        })?;
+    let out = resp.bytes().await.map_err(|e| {
+        // TODO: check retryable
+        Error::Other {
+            message: format!("failed reqwest response bytes '{}'", e),
+            retryable: false,
+        }
+    })?;
+    Ok(out.into())
+}
@@ -203,12 +203,11 @@ pub async fn get_tx_status(http_rpc: &str, tx_id: &str) -> Result<platformvm::Ge
         .body(d)
         .send()
         .await
-        .map_err(|e|
-            // TODO: check retryable
-            Error::API {
-                message: format!("failed reqwest::Client.send '{}'", e),
-                retryable: false,
-            })?;
+        .map_err(|e| Error::API {
+            message: format!("failed reqwest::Client.send '{}'", e),
+            // connection failures and timeouts are transient
+            retryable: e.is_connect() || e.is_timeout(),
+        })?;
     let out = resp.bytes().await.map_err(|e| {
         // TODO: check retryable
         Error::Other {
@@ -1,4 +1,8 @@
-use crate::codec::serde::{hex_0x_bytes::Hex0xBytes, hex_0x_primitive_types_h256::Hex0xH256};
+use crate::codec::serde::{
+    hex_0x_bytes::Hex0xBytes, hex_0x_primitive_types_h160::Hex0xH160,
+    hex_0x_primitive_types_h256::Hex0xH256, hex_0x_primitive_types_u256::Hex0xU256,
+    hex_0x_u64::Hex0xU64,
+};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
@@ -340,3 +344,428 @@ fn test_send_raw_transaction() {
     };
     assert_eq!(resp, expected);
 }
+
+/// Response for "avax.getAtomicTxStatus" on the C-chain "/ext/bc/C/avax" path.
+/// ref. <https://docs.lux.network/apis/node/apis/c-chain#avaxgetatomictxstatus>
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct GetAtomicTxStatusResponse {
+    pub jsonrpc: String,
+    pub id: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<GetAtomicTxStatusResult>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<super::ResponseError>,
+}
+
+/// ref. <https://docs.lux.network/apis/node/apis/c-chain#avaxgetatomictxstatus>
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GetAtomicTxStatusResult {
+    /// One of "Accepted", "Processing", "Dropped", or "Unknown".
+    pub status: String,
+    /// Height of the block that accepted the transaction, if accepted.
+    #[serde_as(as = "Option<serde_with::DisplayFromStr>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_height: Option<u64>,
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib -- jsonrpc::evm::test_get_atomic_tx_status --exact --show-output
+#[test]
+fn test_get_atomic_tx_status() {
+    let resp: GetAtomicTxStatusResponse = serde_json::from_str(
+        "
+
+{
+    \"jsonrpc\": \"2.0\",
+    \"result\": {
+        \"status\": \"Accepted\",
+        \"blockHeight\": \"1\"
+    },
+    \"id\": 1
+}
+
+",
+    )
+    .unwrap();
+    let expected = GetAtomicTxStatusResponse {
+        jsonrpc: "2.0".to_string(),
+        id: 1,
+        result: Some(GetAtomicTxStatusResult {
+            status: String::from("Accepted"),
+            block_height: Some(1),
+        }),
+        error: None,
+    };
+    assert_eq!(resp, expected);
+}
+
+/// Transaction call object for "eth_call" and "eth_estimateGas".
+/// ref. <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_call>
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CallRequest {
+    #[serde_as(as = "Option<Hex0xH160>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<primitive_types::H160>,
+    /// None to estimate a contract creation.
+    #[serde_as(as = "Option<Hex0xH160>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<primitive_types::H160>,
+
+    #[serde_as(as = "Option<Hex0xU256>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas: Option<primitive_types::U256>,
+    #[serde_as(as = "Option<Hex0xU256>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_price: Option<primitive_types::U256>,
+    #[serde_as(as = "Option<Hex0xU256>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fee_per_gas: Option<primitive_types::U256>,
+    #[serde_as(as = "Option<Hex0xU256>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_priority_fee_per_gas: Option<primitive_types::U256>,
+
+    #[serde_as(as = "Option<Hex0xU256>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<primitive_types::U256>,
+    /// ABI-encoded call data.
+    #[serde_as(as = "Option<Hex0xBytes>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Vec<u8>>,
+}
+
+/// Response for "eth_call".
+/// ref. <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_call>
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct CallResponse {
+    pub jsonrpc: String,
+    pub id: u32,
+
+    /// Return value of the executed contract.
+    #[serde_as(as = "Hex0xBytes")]
+    pub result: Vec<u8>,
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib -- jsonrpc::evm::test_call --exact --show-output
+#[test]
+fn test_call() {
+    use std::str::FromStr;
+
+    let req = CallRequest {
+        to: Some(
+            primitive_types::H160::from_str("0x3c42649799074b438889b80312ea9f62bc798aa8").unwrap(),
+        ),
+        gas: Some(primitive_types::U256::from(30000)),
+        data: Some(vec![0x70, 0xa0, 0x82, 0x31]),
+        ..Default::default()
+    };
+    assert_eq!(
+        serde_json::to_string(&req).unwrap(),
+        r#"{"to":"0x3c42649799074b438889b80312ea9f62bc798aa8","gas":"0x7530","data":"0x70a08231"}"#
+    );
+
+    let resp: CallResponse = serde_json::from_str(
+        "
+
+{
+    \"jsonrpc\": \"2.0\",
+    \"result\": \"0x000000000000000000000000000000000000000000000000000000000000002a\",
+    \"id\": 1
+}
+
+",
+    )
+    .unwrap();
+    let mut expected = vec![0u8; 32];
+    expected[31] = 42;
+    assert_eq!(resp.result, expected);
+}
+
+/// Response for "eth_estimateGas".
+/// ref. <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_estimategas>
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct EstimateGasResponse {
+    pub jsonrpc: String,
+    pub id: u32,
+
+    #[serde(with = "crate::codec::serde::hex_0x_primitive_types_u256")]
+    pub result: primitive_types::U256,
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib -- jsonrpc::evm::test_estimate_gas --exact --show-output
+#[test]
+fn test_estimate_gas() {
+    let resp: EstimateGasResponse = serde_json::from_str(
+        "
+
+{
+    \"jsonrpc\": \"2.0\",
+    \"result\": \"0x5208\",
+    \"id\": 1
+}
+
+",
+    )
+    .unwrap();
+    let expected = EstimateGasResponse {
+        jsonrpc: "2.0".to_string(),
+        id: 1,
+        result: primitive_types::U256::from(21000),
+    };
+    assert_eq!(resp, expected);
+}
+
+/// Filter object for "eth_getLogs".
+/// Either "block_hash" or the block range can be set, but not both.
+/// ref. <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_getlogs>
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LogFilter {
+    /// Block number in hex (e.g., "0x1"), or a tag (e.g., "latest", "earliest").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_block: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_block: Option<String>,
+    #[serde_as(as = "Option<Hex0xH256>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_hash: Option<primitive_types::H256>,
+
+    /// Contract addresses that the logs must originate from.
+    #[serde_as(as = "Option<Vec<Hex0xH160>>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<Vec<primitive_types::H160>>,
+    /// Topics by position, where None matches any topic and
+    /// multiple topics in a position match any of them.
+    #[serde_as(as = "Option<Vec<Option<Vec<Hex0xH256>>>>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topics: Option<Vec<Option<Vec<primitive_types::H256>>>>,
+}
+
+/// Response for "eth_getLogs".
+/// ref. <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_getlogs>
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct GetLogsResponse {
+    pub jsonrpc: String,
+    pub id: u32,
+
+    pub result: Vec<Log>,
+}
+
+/// Log object, as returned by "eth_getLogs" and in transaction receipts.
+/// The block and transaction fields are None for pending logs.
+/// ref. <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_getfilterchanges>
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Log {
+    #[serde_as(as = "Hex0xH160")]
+    pub address: primitive_types::H160,
+    #[serde_as(as = "Vec<Hex0xH256>")]
+    pub topics: Vec<primitive_types::H256>,
+    #[serde_as(as = "Hex0xBytes")]
+    pub data: Vec<u8>,
+
+    #[serde_as(as = "Option<Hex0xU64>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+    #[serde_as(as = "Option<Hex0xH256>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_hash: Option<primitive_types::H256>,
+
+    #[serde_as(as = "Option<Hex0xH256>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<primitive_types::H256>,
+    #[serde_as(as = "Option<Hex0xU64>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_index: Option<u64>,
+    #[serde_as(as = "Option<Hex0xU64>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_index: Option<u64>,
+
+    /// True if the log was removed due to a chain reorganization.
+    #[serde(default)]
+    pub removed: bool,
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib -- jsonrpc::evm::test_get_logs --exact --show-output
+#[test]
+fn test_get_logs() {
+    use std::str::FromStr;
+
+    let transfer_topic = primitive_types::H256::from_str(
+        "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+    )
+    .unwrap();
+    let filter = LogFilter {
+        from_block: Some(String::from("0x1")),
+        to_block: Some(String::from("latest")),
+        topics: Some(vec![Some(vec![transfer_topic]), None]),
+        ..Default::default()
+    };
+    assert_eq!(
+        serde_json::to_string(&filter).unwrap(),
+        r#"{"fromBlock":"0x1","toBlock":"latest","topics":[["0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"],null]}"#
+    );
+
+    let resp: GetLogsResponse = serde_json::from_str(
+        "
+
+{
+    \"jsonrpc\": \"2.0\",
+    \"result\": [{
+        \"address\": \"0x3c42649799074b438889b80312ea9f62bc798aa8\",
+        \"topics\": [\"0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef\"],
+        \"data\": \"0x2a\",
+        \"blockNumber\": \"0xb\",
+        \"blockHash\": \"0xc6ef2fc5426d6ad6fd9e2a26abeab0aa2411b7ab17f30a99d3cb96aed1d1055b\",
+        \"transactionHash\": \"0xb903239f8543d04b5dc1ba6579132b143087c68db1b2168786408fcbce568238\",
+        \"transactionIndex\": \"0x1\",
+        \"logIndex\": \"0x0\",
+        \"removed\": false
+    }],
+    \"id\": 1
+}
+
+",
+    )
+    .unwrap();
+    assert_eq!(resp.result.len(), 1);
+    assert_eq!(resp.result[0].topics, vec![transfer_topic]);
+    assert_eq!(resp.result[0].data, vec![42]);
+    assert_eq!(resp.result[0].block_number, Some(11));
+    assert_eq!(resp.result[0].transaction_index, Some(1));
+    assert_eq!(resp.result[0].log_index, Some(0));
+    assert!(!resp.result[0].removed);
+}
+
+/// Response for "eth_feeHistory".
+/// ref. <https://ethereum.github.io/execution-apis/api-documentation>
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct FeeHistoryResponse {
+    pub jsonrpc: String,
+    pub id: u32,
+
+    pub result: FeeHistoryResult,
+}
+
+/// ref. <https://ethereum.github.io/execution-apis/api-documentation>
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeHistoryResult {
+    /// Lowest block number in the returned range.
+    #[serde_as(as = "Hex0xU64")]
+    pub oldest_block: u64,
+    /// Base fee per gas for each block in the range, plus the next block.
+    #[serde_as(as = "Vec<Hex0xU256>")]
+    pub base_fee_per_gas: Vec<primitive_types::U256>,
+    /// Ratio of gas used to the gas limit for each block in the range.
+    pub gas_used_ratio: Vec<f64>,
+    /// Priority fees at the requested percentiles for each block in the range.
+    #[serde_as(as = "Option<Vec<Vec<Hex0xU256>>>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reward: Option<Vec<Vec<primitive_types::U256>>>,
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib -- jsonrpc::evm::test_fee_history --exact --show-output
+#[test]
+fn test_fee_history() {
+    let resp: FeeHistoryResponse = serde_json::from_str(
+        "
+
+{
+    \"jsonrpc\": \"2.0\",
+    \"result\": {
+        \"oldestBlock\": \"0x10\",
+        \"baseFeePerGas\": [\"0x5d21dba00\", \"0x5d21dba00\", \"0x6fc23ac00\"],
+        \"gasUsedRatio\": [0.5, 0.0],
+        \"reward\": [[\"0x0\", \"0x3b9aca00\"], [\"0x0\", \"0x0\"]]
+    },
+    \"id\": 1
+}
+
+",
+    )
+    .unwrap();
+    let expected = FeeHistoryResponse {
+        jsonrpc: "2.0".to_string(),
+        id: 1,
+        result: FeeHistoryResult {
+            oldest_block: 16,
+            base_fee_per_gas: vec![
+                primitive_types::U256::from(25_000_000_000_u64),
+                primitive_types::U256::from(25_000_000_000_u64),
+                primitive_types::U256::from(30_000_000_000_u64),
+            ],
+            gas_used_ratio: vec![0.5, 0.0],
+            reward: Some(vec![
+                vec![
+                    primitive_types::U256::zero(),
+                    primitive_types::U256::from(1_000_000_000_u64),
+                ],
+                vec![primitive_types::U256::zero(), primitive_types::U256::zero()],
+            ]),
+        },
+    };
+    assert_eq!(resp, expected);
+}
+
+/// Response for "eth_baseFee", which returns the base fee for the next block.
+/// ref. <https://docs.lux.network/apis/node/apis/c-chain#eth_basefee>
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct BaseFeeResponse {
+    pub jsonrpc: String,
+    pub id: u32,
+
+    #[serde(with = "crate::codec::serde::hex_0x_primitive_types_u256")]
+    pub result: primitive_types::U256,
+}
+
+/// Response for "eth_maxPriorityFeePerGas".
+/// ref. <https://docs.lux.network/apis/node/apis/c-chain#eth_maxpriorityfeepergas>
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct MaxPriorityFeePerGasResponse {
+    pub jsonrpc: String,
+    pub id: u32,
+
+    #[serde(with = "crate::codec::serde::hex_0x_primitive_types_u256")]
+    pub result: primitive_types::U256,
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib -- jsonrpc::evm::test_base_fee --exact --show-output
+#[test]
+fn test_base_fee() {
+    let resp: BaseFeeResponse = serde_json::from_str(
+        "
+
+{
+    \"jsonrpc\": \"2.0\",
+    \"result\": \"0x5d21dba00\",
+    \"id\": 1
+}
+
+",
+    )
+    .unwrap();
+    assert_eq!(resp.result, primitive_types::U256::from(25_000_000_000_u64));
+
+    let resp: MaxPriorityFeePerGasResponse = serde_json::from_str(
+        "
+
+{
+    \"jsonrpc\": \"2.0\",
+    \"result\": \"0x3b9aca00\",
+    \"id\": 1
+}
+
+",
+    )
+    .unwrap();
+    assert_eq!(resp.result, primitive_types::U256::from(1_000_000_000_u64));
+}
@@ -4,6 +4,7 @@ use std::{
     str::FromStr,
 };
 
+use crate::jsonrpc;
 use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use serde_with::serde_as;
@@ -36,6 +37,20 @@ pub struct CheckResult {
     pub time_of_first_failure: Option<DateTime<Utc>>,
 }
 
+/// Represents the JSON-RPC response of "health.health" and "health.readiness".
+/// ref. <https://docs.lux.network/apis/node/apis/health#healthhealth>
+#[derive(Debug, Deserialize, Eq, PartialEq, Clone)]
+pub struct HealthResponse {
+    pub jsonrpc: String,
+    pub id: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Response>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<jsonrpc::ResponseError>,
+}
+
 /// ref. <https://doc.rust-lang.org/std/str/trait.FromStr.html>
 impl FromStr for Response {
     type Err = Error;
@@ -135,3 +150,39 @@ fn test_parse() {
     info!("parsed: {:?}", parsed);
     assert!(parsed.healthy);
 }
+
+/// RUST_LOG=debug cargo test --package lux-types --lib -- jsonrpc::health::test_health_response --exact --show-output
+#[test]
+fn test_health_response() {
+    let data = r#"
+{
+    "jsonrpc": "2.0",
+    "result": {
+        "checks": {
+            "11111111111111111111111111111111LpoYY": {
+                "message": {
+                    "engine": {
+                        "consensus": {
+                            "lastAcceptedHeight": 123,
+                            "lastAcceptedID": "L4wUWcNz7Gmq2Bqr3PEQo7d87x1rWzUqPjiWXr28ihvYt6Rqm"
+                        }
+                    }
+                },
+                "timestamp": "2023-06-02T10:46:01.243291-07:00",
+                "duration": 8120
+            }
+        },
+        "healthy": true
+    },
+    "id": 1
+}
+"#;
+
+    let parsed: HealthResponse = serde_json::from_str(data).unwrap();
+    let result = parsed.result.unwrap();
+    assert!(result.healthy);
+    assert!(result
+        .checks
+        .unwrap()
+        .contains_key("11111111111111111111111111111111LpoYY"));
+}
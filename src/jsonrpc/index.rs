@@ -0,0 +1,243 @@
+use crate::{ids, jsonrpc};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+
+/// Maximum number of containers that can be fetched in a single
+/// "index.getContainerRange" call.
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/indexer#MaxFetchedByRange>
+pub const MAX_FETCHED_BY_RANGE: u64 = 1024;
+
+/// Represents an accepted container (tx, block, or vertex) in the index.
+/// ref. <https://docs.lux.network/apis/node/apis/index-api#indexgetlastaccepted>
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/indexer#FormattedContainer>
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct Container {
+    pub id: ids::Id,
+    #[serde_as(as = "crate::codec::serde::hex_0x_bytes::Hex0xBytes")]
+    pub bytes: Vec<u8>,
+    #[serde_as(as = "crate::codec::serde::rfc_3339::DateTimeUtc")]
+    pub timestamp: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+    #[serde_as(as = "DisplayFromStr")]
+    pub index: u64,
+}
+
+/// Response for "index.getLastAccepted" and "index.getContainerByIndex".
+/// ref. <https://docs.lux.network/apis/node/apis/index-api#indexgetcontainerbyindex>
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct GetContainerResponse {
+    pub jsonrpc: String,
+    pub id: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Container>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<jsonrpc::ResponseError>,
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib -- jsonrpc::index::test_get_container --exact --show-output
+#[test]
+fn test_get_container() {
+    use std::str::FromStr;
+
+    use chrono::TimeZone;
+
+    // ref. https://docs.lux.network/apis/node/apis/index-api#indexgetcontainerbyindex
+    let resp: GetContainerResponse = serde_json::from_str(
+        "
+
+{
+    \"jsonrpc\": \"2.0\",
+    \"result\": {
+        \"id\": \"6fXf5hncR8LXvwtM8iezFQBpK5cubV6y1dWgpJCcNyzGB1EzY\",
+        \"bytes\": \"0x00000000000400003039d891ad56056d9c01f18f43f58b5c784ad07a4a49cf3d1f11623804b5cba2c6bf\",
+        \"timestamp\": \"2021-04-02T15:34:00.262979-07:00\",
+        \"encoding\": \"hex\",
+        \"index\": \"0\"
+    },
+    \"id\": 1
+}
+
+",
+    )
+    .unwrap();
+
+    let expected = GetContainerResponse {
+        jsonrpc: "2.0".to_string(),
+        id: 1,
+        result: Some(Container {
+            id: ids::Id::from_str("6fXf5hncR8LXvwtM8iezFQBpK5cubV6y1dWgpJCcNyzGB1EzY").unwrap(),
+            bytes: hex::decode(
+                "00000000000400003039d891ad56056d9c01f18f43f58b5c784ad07a4a49cf3d1f11623804b5cba2c6bf",
+            )
+            .unwrap(),
+            timestamp: Utc
+                .with_ymd_and_hms(2021, 4, 2, 22, 34, 0)
+                .unwrap()
+                .checked_add_signed(chrono::Duration::microseconds(262979))
+                .unwrap(),
+            encoding: Some(String::from("hex")),
+            index: 0,
+        }),
+        error: None,
+    };
+    assert_eq!(resp, expected);
+}
+
+/// ref. <https://docs.lux.network/apis/node/apis/index-api#indexgetcontainerrange>
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct GetContainerRangeResponse {
+    pub jsonrpc: String,
+    pub id: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<GetContainerRangeResult>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<jsonrpc::ResponseError>,
+}
+
+/// ref. <https://docs.lux.network/apis/node/apis/index-api#indexgetcontainerrange>
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct GetContainerRangeResult {
+    pub containers: Vec<Container>,
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib -- jsonrpc::index::test_get_container_range --exact --show-output
+#[test]
+fn test_get_container_range() {
+    let resp: GetContainerRangeResponse = serde_json::from_str(
+        "
+
+{
+    \"jsonrpc\": \"2.0\",
+    \"result\": {
+        \"containers\": [
+            {
+                \"id\": \"6fXf5hncR8LXvwtM8iezFQBpK5cubV6y1dWgpJCcNyzGB1EzY\",
+                \"bytes\": \"0x0000\",
+                \"timestamp\": \"2021-04-02T15:34:00.262979-07:00\",
+                \"encoding\": \"hex\",
+                \"index\": \"0\"
+            },
+            {
+                \"id\": \"G3BuH6ytQ2averrLxJJugjWZHTRubzCrUZEXoheG5JMqL5ccY\",
+                \"bytes\": \"0x0001\",
+                \"timestamp\": \"2021-04-02T15:35:00.262979-07:00\",
+                \"encoding\": \"hex\",
+                \"index\": \"1\"
+            }
+        ]
+    },
+    \"id\": 1
+}
+
+",
+    )
+    .unwrap();
+
+    let containers = resp.result.unwrap().containers;
+    assert_eq!(containers.len(), 2);
+    assert_eq!(containers[0].index, 0);
+    assert_eq!(containers[1].index, 1);
+    assert_eq!(containers[1].bytes, vec![0x00, 0x01]);
+}
+
+/// ref. <https://docs.lux.network/apis/node/apis/index-api#indexgetindex>
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct GetIndexResponse {
+    pub jsonrpc: String,
+    pub id: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<GetIndexResult>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<jsonrpc::ResponseError>,
+}
+
+/// ref. <https://docs.lux.network/apis/node/apis/index-api#indexgetindex>
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct GetIndexResult {
+    #[serde_as(as = "DisplayFromStr")]
+    pub index: u64,
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib -- jsonrpc::index::test_get_index --exact --show-output
+#[test]
+fn test_get_index() {
+    let resp: GetIndexResponse = serde_json::from_str(
+        "
+
+{
+    \"jsonrpc\": \"2.0\",
+    \"result\": {
+        \"index\": \"10\"
+    },
+    \"id\": 1
+}
+
+",
+    )
+    .unwrap();
+
+    let expected = GetIndexResponse {
+        jsonrpc: "2.0".to_string(),
+        id: 1,
+        result: Some(GetIndexResult { index: 10 }),
+        error: None,
+    };
+    assert_eq!(resp, expected);
+}
+
+/// ref. <https://docs.lux.network/apis/node/apis/index-api#indexisaccepted>
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct IsAcceptedResponse {
+    pub jsonrpc: String,
+    pub id: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<IsAcceptedResult>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<jsonrpc::ResponseError>,
+}
+
+/// ref. <https://docs.lux.network/apis/node/apis/index-api#indexisaccepted>
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IsAcceptedResult {
+    pub is_accepted: bool,
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib -- jsonrpc::index::test_is_accepted --exact --show-output
+#[test]
+fn test_is_accepted() {
+    let resp: IsAcceptedResponse = serde_json::from_str(
+        "
+
+{
+    \"jsonrpc\": \"2.0\",
+    \"result\": {
+        \"isAccepted\": true
+    },
+    \"id\": 1
+}
+
+",
+    )
+    .unwrap();
+
+    let expected = IsAcceptedResponse {
+        jsonrpc: "2.0".to_string(),
+        id: 1,
+        result: Some(IsAcceptedResult { is_accepted: true }),
+        error: None,
+    };
+    assert_eq!(resp, expected);
+}
@@ -1,8 +1,10 @@
 pub mod admin;
 pub mod avm;
+pub mod batch;
 pub mod common;
 pub mod evm;
 pub mod health;
+pub mod index;
 pub mod info;
 pub mod platformvm;
 
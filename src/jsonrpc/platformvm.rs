@@ -293,6 +293,9 @@ impl GetTxStatusResponse {
 pub struct GetTxStatusResult {
     #[serde_as(as = "DisplayFromStr")]
     pub status: platformvm::txs::status::Status,
+    /// Reason the transaction was dropped, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
 }
 
 impl Default for GetTxStatusResult {
@@ -305,6 +308,7 @@ impl GetTxStatusResult {
     pub fn default() -> Self {
         Self {
             status: platformvm::txs::status::Status::Unknown(String::new()),
+            reason: None,
         }
     }
 }
@@ -332,6 +336,34 @@ fn test_get_tx_status() {
         id: 1,
         result: Some(GetTxStatusResult {
             status: platformvm::txs::status::Status::Committed,
+            reason: None,
+        }),
+        error: None,
+    };
+    assert_eq!(resp, expected);
+
+    let resp: GetTxStatusResponse = serde_json::from_str(
+        "
+
+{
+    \"jsonrpc\": \"2.0\",
+    \"result\": {
+        \"status\": \"Dropped\",
+        \"reason\": \"failed to verify tx: missing UTXO\"
+    },
+    \"id\": 1
+}
+
+",
+    )
+    .unwrap();
+
+    let expected = GetTxStatusResponse {
+        jsonrpc: "2.0".to_string(),
+        id: 1,
+        result: Some(GetTxStatusResult {
+            status: platformvm::txs::status::Status::Dropped,
+            reason: Some(String::from("failed to verify tx: missing UTXO")),
         }),
         error: None,
     };
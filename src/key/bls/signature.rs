@@ -1,10 +1,16 @@
-use std::io::{self, Error, ErrorKind};
+use std::{
+    collections::HashSet,
+    io::{self, Error, ErrorKind},
+};
 
 use crate::key::bls::{self, public_key::Key as PublicKey};
 use blst::{
-    min_pk::{AggregateSignature, Signature},
+    blst_scalar,
+    min_pk::{AggregateSignature, PublicKey as BlstPublicKey, Signature},
     BLST_ERROR,
 };
+use ring::rand::{SecureRandom, SystemRandom};
+use zeroize::Zeroizing;
 
 #[derive(Debug, Clone)]
 pub struct Sig(pub Signature);
@@ -92,6 +98,97 @@ pub fn aggregate(sigs: &[Sig]) -> io::Result<Sig> {
     Ok(Sig(agg_sig.to_signature()))
 }
 
+/// Number of random bits for each scalar in "batch_verify".
+/// ref. <https://ethresear.ch/t/fast-verification-of-multiple-bls-signatures/5407>
+pub const BATCH_VERIFY_RAND_BITS: usize = 64;
+
+/// Verifies the aggregate signature of the distinct messages, where
+/// "msgs[i]" is signed by "pubkeys[i]".
+/// The public keys and the signature are subgroup-checked.
+/// Returns false if the input is empty, the lengths mismatch, or any two
+/// messages are equal, since the rogue public key attack applies to the
+/// same message without the proofs of possession (see "fast_aggregate_verify").
+pub fn aggregate_verify(sig: &Sig, msgs: &[&[u8]], pubkeys: &[PublicKey]) -> bool {
+    if pubkeys.is_empty() || msgs.len() != pubkeys.len() {
+        return false;
+    }
+    let mut seen = HashSet::with_capacity(msgs.len());
+    if !msgs.iter().all(|msg| seen.insert(*msg)) {
+        return false;
+    }
+
+    let pks = pubkeys.iter().map(|pk| &pk.0).collect::<Vec<_>>();
+    sig.0.aggregate_verify(
+        true,
+        msgs,
+        &bls::private_key::CIPHER_SUITE_SIGNATURE,
+        &pks,
+        true,
+    ) == BLST_ERROR::BLST_SUCCESS
+}
+
+/// Verifies the aggregate signature of the same message signed by all
+/// the public keys, by verifying against the aggregated public key.
+/// Invariant: [pubkeys] have been verified with their proofs of possession,
+/// otherwise the rogue public key attack applies.
+/// ref. "node/utils/crypto/bls.AggregatePublicKeys" and "bls.Verify"
+pub fn fast_aggregate_verify(sig: &Sig, msg: &[u8], pubkeys: &[PublicKey]) -> bool {
+    if pubkeys.is_empty() {
+        return false;
+    }
+
+    let pks = pubkeys.iter().map(|pk| &pk.0).collect::<Vec<_>>();
+    sig.0
+        .fast_aggregate_verify(true, msg, &bls::private_key::CIPHER_SUITE_SIGNATURE, &pks)
+        == BLST_ERROR::BLST_SUCCESS
+}
+
+/// Verifies many (public key, message, signature) triples at once,
+/// which is faster than verifying each signature.
+/// Each triple is weighted by a secret random scalar, so that invalid
+/// signatures cannot cancel each other out. The public keys and the
+/// signatures are subgroup-checked.
+/// Returns false if the input is empty or the lengths mismatch.
+pub fn batch_verify(pubkeys: &[PublicKey], msgs: &[&[u8]], sigs: &[Sig]) -> io::Result<bool> {
+    let n = pubkeys.len();
+    if n == 0 || msgs.len() != n || sigs.len() != n {
+        return Ok(false);
+    }
+
+    // "blst_scalar" zeroizes on drop
+    let rng = SystemRandom::new();
+    let mut rands = Vec::with_capacity(n);
+    for _ in 0..n {
+        let mut b = Zeroizing::new([0u8; BATCH_VERIFY_RAND_BITS / 8]);
+        // zero scalar would skip the triple
+        while b.iter().all(|x| *x == 0) {
+            rng.fill(b.as_mut()).map_err(|e| {
+                Error::new(ErrorKind::Other, format!("failed SystemRandom::fill {}", e))
+            })?;
+        }
+
+        let mut scalar = blst_scalar::default();
+        scalar.b[..b.len()].copy_from_slice(b.as_ref());
+        rands.push(scalar);
+    }
+
+    let pks = pubkeys
+        .iter()
+        .map(|pk| &pk.0)
+        .collect::<Vec<&BlstPublicKey>>();
+    let ss = sigs.iter().map(|s| &s.0).collect::<Vec<_>>();
+    Ok(Signature::verify_multiple_aggregate_signatures(
+        msgs,
+        &bls::private_key::CIPHER_SUITE_SIGNATURE,
+        &pks,
+        true,
+        &ss,
+        true,
+        &rands,
+        BATCH_VERIFY_RAND_BITS,
+    ) == BLST_ERROR::BLST_SUCCESS)
+}
+
 /// RUST_LOG=debug cargo test --package lux-types --lib -- key::bls::signature::test_signature --exact --show-output
 #[test]
 fn test_signature() {
@@ -136,3 +233,115 @@ fn test_signature() {
     let agg_sig_pos = aggregate(&[sig1_pos, sig2_pos, sig3_pos]).unwrap();
     assert!(agg_pubkey.verify_proof_of_possession(&msg_to_sign, &agg_sig_pos));
 }
+
+/// RUST_LOG=debug cargo test --package lux-types --lib -- key::bls::signature::test_aggregate_verify --exact --show-output
+#[test]
+fn test_aggregate_verify() {
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .is_test(true)
+        .try_init();
+
+    let mut sks = Vec::new();
+    let mut pubkeys = Vec::new();
+    let mut msgs = Vec::new();
+    let mut sigs = Vec::new();
+    for _ in 0..5 {
+        let sk = crate::key::bls::private_key::Key::generate().unwrap();
+        let msg = random_manager::secure_bytes(50).unwrap();
+        pubkeys.push(sk.to_public_key());
+        sigs.push(sk.sign(&msg));
+        msgs.push(msg);
+        sks.push(sk);
+    }
+    let msg_refs = msgs.iter().map(|m| m.as_slice()).collect::<Vec<_>>();
+
+    // distinct messages
+    let agg_sig = aggregate(&sigs).unwrap();
+    assert!(aggregate_verify(&agg_sig, &msg_refs, &pubkeys));
+    assert!(!aggregate_verify(&agg_sig, &msg_refs[1..], &pubkeys[1..]));
+    let mut swapped = msg_refs.clone();
+    swapped.swap(0, 1);
+    assert!(!aggregate_verify(&agg_sig, &swapped, &pubkeys));
+    assert!(!aggregate_verify(&agg_sig, &[], &[]));
+
+    // same message
+    let msg = random_manager::secure_bytes(50).unwrap();
+    let same_sigs = sks.iter().map(|sk| sk.sign(&msg)).collect::<Vec<_>>();
+    let agg_sig = aggregate(&same_sigs).unwrap();
+    assert!(fast_aggregate_verify(&agg_sig, &msg, &pubkeys));
+    assert!(!fast_aggregate_verify(&agg_sig, &msg, &pubkeys[1..]));
+    assert!(!fast_aggregate_verify(&agg_sig, &msgs[0], &pubkeys));
+    assert!(!fast_aggregate_verify(&agg_sig, &msg, &[]));
+
+    // proof of possession signatures are not valid as normal signatures
+    let pos_sigs = sks
+        .iter()
+        .map(|sk| sk.sign_proof_of_possession(&msg))
+        .collect::<Vec<_>>();
+    assert!(!fast_aggregate_verify(
+        &aggregate(&pos_sigs).unwrap(),
+        &msg,
+        &pubkeys
+    ));
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib -- key::bls::signature::test_aggregate_verify_duplicate_messages --exact --show-output
+#[test]
+fn test_aggregate_verify_duplicate_messages() {
+    let msg = random_manager::secure_bytes(50).unwrap();
+    let other = random_manager::secure_bytes(50).unwrap();
+
+    let mut pubkeys = Vec::new();
+    let mut sigs = Vec::new();
+    for m in [&msg, &msg, &other] {
+        let sk = crate::key::bls::private_key::Key::generate().unwrap();
+        pubkeys.push(sk.to_public_key());
+        sigs.push(sk.sign(m));
+    }
+    let agg_sig = aggregate(&sigs).unwrap();
+
+    // validly signed, but the messages must be distinct
+    let msgs: [&[u8]; 3] = [&msg, &msg, &other];
+    assert!(!aggregate_verify(&agg_sig, &msgs, &pubkeys));
+    assert!(aggregate_verify(
+        &aggregate(&sigs[1..]).unwrap(),
+        &msgs[1..],
+        &pubkeys[1..]
+    ));
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib -- key::bls::signature::test_batch_verify --exact --show-output
+#[test]
+fn test_batch_verify() {
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .is_test(true)
+        .try_init();
+
+    let mut pubkeys = Vec::new();
+    let mut msgs = Vec::new();
+    let mut sigs = Vec::new();
+    for _ in 0..10 {
+        let sk = crate::key::bls::private_key::Key::generate().unwrap();
+        let msg = random_manager::secure_bytes(50).unwrap();
+        pubkeys.push(sk.to_public_key());
+        sigs.push(sk.sign(&msg));
+        msgs.push(msg);
+    }
+    let msg_refs = msgs.iter().map(|m| m.as_slice()).collect::<Vec<_>>();
+    assert!(batch_verify(&pubkeys, &msg_refs, &sigs).unwrap());
+
+    // one invalid signature fails the batch
+    let mut bad_sigs = sigs.clone();
+    bad_sigs.swap(2, 3);
+    assert!(!batch_verify(&pubkeys, &msg_refs, &bad_sigs).unwrap());
+
+    // swapped signatures cannot cancel each other out
+    let mut bad_msgs = msg_refs.clone();
+    bad_msgs.swap(0, 1);
+    assert!(!batch_verify(&pubkeys, &bad_msgs, &sigs).unwrap());
+
+    assert!(!batch_verify(&pubkeys[1..], &msg_refs, &sigs).unwrap());
+    assert!(!batch_verify(&[], &[], &[]).unwrap());
+}
@@ -0,0 +1,851 @@
+//! Password-encrypted key files, compatible with the Ethereum V3 keystore
+//! (a.k.a. "Web3 Secret Storage") for secp256k1 keys.
+//! ref. <https://ethereum.org/en/developers/docs/data-structures-and-encoding/web3-secret-storage>
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::Path,
+};
+
+use crate::{
+    errors::{Error, Result},
+    hash,
+    key::{bls, secp256k1},
+};
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes128Gcm,
+};
+use ctr::cipher::{KeyIvInit, StreamCipher};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_with::{hex::Hex, serde_as};
+use zeroize::Zeroizing;
+
+type Aes128Ctr = ctr::Ctr128BE<aes::Aes128>;
+
+pub const VERSION: u32 = 3;
+
+pub const CIPHER_AES_128_CTR: &str = "aes-128-ctr";
+/// Not part of the V3 spec, so only readable by this crate.
+pub const CIPHER_AES_128_GCM: &str = "aes-128-gcm";
+
+pub const KDF_SCRYPT: &str = "scrypt";
+pub const KDF_PBKDF2: &str = "pbkdf2";
+pub const PRF_HMAC_SHA256: &str = "hmac-sha256";
+
+pub const KEY_TYPE_SECP256K1: &str = "secp256k1";
+pub const KEY_TYPE_BLS: &str = "bls";
+
+const DK_LEN: usize = 32;
+const SALT_LEN: usize = 32;
+
+/// Maximum scrypt cost "n * r * p" of the key files to load, which is 16
+/// times the default parameters, so that a crafted file can not make the
+/// loader allocate or spin without bound. The memory scrypt uses is
+/// "128 * n * r" bytes.
+const MAX_SCRYPT_COST: u64 = 16 * (1 << 18) * 8;
+/// Maximum pbkdf2 iterations of the key files to load, which is 16 times
+/// the geth default.
+const MAX_PBKDF2_C: u32 = 16 * 262_144;
+
+/// Key derivation function and its cost parameters.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Kdf {
+    /// "n" must be a power of two.
+    Scrypt {
+        n: u32,
+        r: u32,
+        p: u32,
+    },
+    Pbkdf2 {
+        c: u32,
+    },
+}
+
+/// Cipher to encrypt the private key with.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Cipher {
+    /// Interoperable with the Ethereum V3 keystore.
+    Aes128Ctr,
+    /// Authenticated encryption, on top of the V3 MAC.
+    Aes128Gcm,
+}
+
+/// Options to encrypt a key file with.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Options {
+    pub kdf: Kdf,
+    pub cipher: Cipher,
+}
+
+impl Default for Options {
+    /// Same as the "standard" scrypt parameters in geth.
+    fn default() -> Self {
+        Self {
+            kdf: Kdf::Scrypt {
+                n: 1 << 18,
+                r: 8,
+                p: 1,
+            },
+            cipher: Cipher::Aes128Ctr,
+        }
+    }
+}
+
+impl Options {
+    /// Same as the "light" scrypt parameters in geth,
+    /// for tests and low-power devices.
+    pub fn light() -> Self {
+        Self {
+            kdf: Kdf::Scrypt {
+                n: 1 << 12,
+                r: 8,
+                p: 6,
+            },
+            cipher: Cipher::Aes128Ctr,
+        }
+    }
+}
+
+/// Represents the encrypted key file.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct Keystore {
+    pub version: u32,
+    pub id: String,
+    /// Hex-encoded ETH address without "0x", for secp256k1 keys.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    /// "secp256k1" or "bls", where none implies "secp256k1".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_type: Option<String>,
+
+    /// Some tools write "Crypto".
+    #[serde(alias = "Crypto")]
+    pub crypto: Crypto,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct Crypto {
+    pub cipher: String,
+    pub cipherparams: CipherParams,
+    #[serde_as(as = "Hex")]
+    pub ciphertext: Vec<u8>,
+
+    pub kdf: String,
+    pub kdfparams: KdfParams,
+
+    /// Keccak256 of the second half of the derived key and the ciphertext.
+    #[serde_as(as = "Hex")]
+    pub mac: Vec<u8>,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct CipherParams {
+    #[serde_as(as = "Hex")]
+    pub iv: Vec<u8>,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+#[serde(untagged)]
+pub enum KdfParams {
+    Scrypt {
+        dklen: usize,
+        n: u32,
+        r: u32,
+        p: u32,
+        #[serde_as(as = "Hex")]
+        salt: Vec<u8>,
+    },
+    Pbkdf2 {
+        dklen: usize,
+        c: u32,
+        prf: String,
+        #[serde_as(as = "Hex")]
+        salt: Vec<u8>,
+    },
+}
+
+impl KdfParams {
+    /// Returns the "kdf" name of the parameters.
+    pub fn kdf(&self) -> &'static str {
+        match self {
+            KdfParams::Scrypt { .. } => KDF_SCRYPT,
+            KdfParams::Pbkdf2 { .. } => KDF_PBKDF2,
+        }
+    }
+}
+
+impl Keystore {
+    /// Encrypts the secret with the password.
+    pub fn encrypt(secret: &[u8], password: &[u8], opts: &Options) -> Result<Self> {
+        let mut rng = rand::rngs::OsRng;
+        let mut salt = vec![0u8; SALT_LEN];
+        rng.fill_bytes(&mut salt);
+
+        let kdfparams = match opts.kdf {
+            Kdf::Scrypt { n, r, p } => KdfParams::Scrypt {
+                dklen: DK_LEN,
+                n,
+                r,
+                p,
+                salt,
+            },
+            Kdf::Pbkdf2 { c } => KdfParams::Pbkdf2 {
+                dklen: DK_LEN,
+                c,
+                prf: String::from(PRF_HMAC_SHA256),
+                salt,
+            },
+        };
+        let dk = derive_key(&kdfparams, password)?;
+
+        let (cipher, iv, ciphertext) = match opts.cipher {
+            Cipher::Aes128Ctr => {
+                let mut iv = vec![0u8; 16];
+                rng.fill_bytes(&mut iv);
+                let mut ciphertext = secret.to_vec();
+                Aes128Ctr::new(dk[..16].into(), iv.as_slice().into())
+                    .apply_keystream(&mut ciphertext);
+                (CIPHER_AES_128_CTR, iv, ciphertext)
+            }
+            Cipher::Aes128Gcm => {
+                let mut iv = vec![0u8; 12];
+                rng.fill_bytes(&mut iv);
+                let ciphertext = Aes128Gcm::new(dk[..16].into())
+                    .encrypt(iv.as_slice().into(), secret)
+                    .map_err(|e| Error::Other {
+                        message: format!("failed aes-128-gcm encrypt ({})", e),
+                        retryable: false,
+                    })?;
+                (CIPHER_AES_128_GCM, iv, ciphertext)
+            }
+        };
+
+        let mut id = [0u8; 16];
+        rng.fill_bytes(&mut id);
+
+        Ok(Self {
+            version: VERSION,
+            id: uuid_v4(id),
+            address: None,
+            key_type: None,
+            crypto: Crypto {
+                cipher: String::from(cipher),
+                cipherparams: CipherParams { iv },
+                mac: mac(dk.as_ref(), &ciphertext).to_vec(),
+                ciphertext,
+                kdf: String::from(kdfparams.kdf()),
+                kdfparams,
+            },
+        })
+    }
+
+    /// Decrypts the secret with the password.
+    /// Fails if the password is wrong or the file is corrupted.
+    pub fn decrypt(&self, password: &[u8]) -> Result<Zeroizing<Vec<u8>>> {
+        if self.version != VERSION {
+            return Err(Error::Other {
+                message: format!("unsupported keystore version {}", self.version),
+                retryable: false,
+            });
+        }
+
+        // "kdfparams" is untagged, so make sure it is what "kdf" names
+        if !self
+            .crypto
+            .kdf
+            .eq_ignore_ascii_case(self.crypto.kdfparams.kdf())
+        {
+            return Err(Error::Other {
+                message: format!(
+                    "kdf '{}' does not match the {} kdfparams",
+                    self.crypto.kdf,
+                    self.crypto.kdfparams.kdf()
+                ),
+                retryable: false,
+            });
+        }
+
+        let dk = derive_key(&self.crypto.kdfparams, password)?;
+        let expected = mac(dk.as_ref(), &self.crypto.ciphertext);
+        if ring::constant_time::verify_slices_are_equal(&expected, &self.crypto.mac).is_err() {
+            return Err(Error::Other {
+                message: String::from("keystore MAC mismatch (wrong password?)"),
+                retryable: false,
+            });
+        }
+
+        let iv = &self.crypto.cipherparams.iv;
+        match self.crypto.cipher.as_str() {
+            CIPHER_AES_128_CTR => {
+                if iv.len() != 16 {
+                    return Err(invalid_iv(iv.len()));
+                }
+                let mut secret = Zeroizing::new(self.crypto.ciphertext.clone());
+                Aes128Ctr::new(dk[..16].into(), iv.as_slice().into()).apply_keystream(&mut secret);
+                Ok(secret)
+            }
+            CIPHER_AES_128_GCM => {
+                if iv.len() != 12 {
+                    return Err(invalid_iv(iv.len()));
+                }
+                let secret = Aes128Gcm::new(dk[..16].into())
+                    .decrypt(iv.as_slice().into(), self.crypto.ciphertext.as_slice())
+                    .map_err(|e| Error::Other {
+                        message: format!("failed aes-128-gcm decrypt ({})", e),
+                        retryable: false,
+                    })?;
+                Ok(Zeroizing::new(secret))
+            }
+            cipher => Err(Error::Other {
+                message: format!("unsupported cipher '{}'", cipher),
+                retryable: false,
+            }),
+        }
+    }
+
+    pub fn load(file_path: &str) -> Result<Self> {
+        log::info!("loading keystore from {}", file_path);
+        let d = fs::read(file_path).map_err(|e| Error::Other {
+            message: format!("failed to read {} ({})", file_path, e),
+            retryable: false,
+        })?;
+        serde_json::from_slice(&d).map_err(|e| Error::Other {
+            message: format!("failed serde_json::from_slice {}", e),
+            retryable: false,
+        })
+    }
+
+    /// Writes the key file, readable only by the owner on unix.
+    pub fn sync(&self, file_path: &str) -> Result<()> {
+        log::info!("syncing keystore to '{}'", file_path);
+        self.write_file(file_path, false)
+    }
+
+    /// Writes and fsyncs the key file. If "create_new" is true, fails
+    /// if the file already exists rather than overwriting it.
+    fn write_file(&self, file_path: &str, create_new: bool) -> Result<()> {
+        let to_err = |e: std::io::Error| Error::Other {
+            message: format!("failed to write {} ({})", file_path, e),
+            retryable: false,
+        };
+
+        if let Some(parent_dir) = Path::new(file_path).parent() {
+            fs::create_dir_all(parent_dir).map_err(to_err)?;
+        }
+        let d = serde_json::to_vec(self).map_err(|e| Error::Other {
+            message: format!("failed to serialize JSON {}", e),
+            retryable: false,
+        })?;
+
+        let mut f = create_private_file(file_path, create_new).map_err(to_err)?;
+        f.write_all(&d).map_err(to_err)?;
+        f.sync_all().map_err(to_err)
+    }
+
+    fn check_key_type(&self, key_type: &str) -> Result<()> {
+        let found = self.key_type.as_deref().unwrap_or(KEY_TYPE_SECP256K1);
+        if found != key_type {
+            return Err(Error::Other {
+                message: format!("expected {} keystore, found {}", key_type, found),
+                retryable: false,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl secp256k1::private_key::Key {
+    /// Encrypts the private key into the Ethereum V3 keystore.
+    pub fn to_keystore(&self, password: &[u8], opts: &Options) -> Result<Keystore> {
+        let mut ks = Keystore::encrypt(Zeroizing::new(self.to_bytes()).as_ref(), password, opts)?;
+        ks.address = Some(hex::encode(self.to_public_key().to_h160()));
+        Ok(ks)
+    }
+
+    /// Decrypts the private key from the Ethereum V3 keystore.
+    pub fn from_keystore(ks: &Keystore, password: &[u8]) -> Result<Self> {
+        ks.check_key_type(KEY_TYPE_SECP256K1)?;
+        let sk = Self::from_bytes(&ks.decrypt(password)?)?;
+
+        if let Some(address) = &ks.address {
+            let expected = hex::encode(sk.to_public_key().to_h160());
+            if !address
+                .trim_start_matches("0x")
+                .eq_ignore_ascii_case(&expected)
+            {
+                return Err(Error::Other {
+                    message: format!("keystore address {} does not match key", address),
+                    retryable: false,
+                });
+            }
+        }
+        Ok(sk)
+    }
+
+    pub fn to_keystore_file(&self, file_path: &str, password: &[u8], opts: &Options) -> Result<()> {
+        self.to_keystore(password, opts)?.sync(file_path)
+    }
+
+    pub fn from_keystore_file(file_path: &str, password: &[u8]) -> Result<Self> {
+        Self::from_keystore(&Keystore::load(file_path)?, password)
+    }
+}
+
+impl bls::private_key::Key {
+    /// Encrypts the private key into the keystore, with the "bls" key type.
+    pub fn to_keystore(&self, password: &[u8], opts: &Options) -> Result<Keystore> {
+        let mut ks = Keystore::encrypt(Zeroizing::new(self.to_bytes()).as_ref(), password, opts)?;
+        ks.key_type = Some(String::from(KEY_TYPE_BLS));
+        Ok(ks)
+    }
+
+    pub fn from_keystore(ks: &Keystore, password: &[u8]) -> Result<Self> {
+        ks.check_key_type(KEY_TYPE_BLS)?;
+        Self::from_bytes(&ks.decrypt(password)?).map_err(|e| Error::Other {
+            message: format!("failed to load BLS key ({})", e),
+            retryable: false,
+        })
+    }
+
+    pub fn to_keystore_file(&self, file_path: &str, password: &[u8], opts: &Options) -> Result<()> {
+        self.to_keystore(password, opts)?.sync(file_path)
+    }
+
+    pub fn from_keystore_file(file_path: &str, password: &[u8]) -> Result<Self> {
+        Self::from_keystore(&Keystore::load(file_path)?, password)
+    }
+}
+
+/// Migrates the plaintext key info file (see "secp256k1::Info::sync")
+/// to the keystore file. The plaintext file is removed once the written
+/// keystore is verified to decrypt to the same key.
+/// "keystore_path" may be the same as "info_path" to replace the file in place.
+/// Fails if the file has the mnemonic phrase, which must be backed up
+/// and removed from the file first.
+pub fn migrate_secp256k1_info(
+    info_path: &str,
+    keystore_path: &str,
+    password: &[u8],
+    opts: &Options,
+) -> Result<secp256k1::private_key::Key> {
+    // "Info::sync" writes JSON, while "Info::load" reads YAML
+    let d = fs::read(info_path).map_err(|e| Error::Other {
+        message: format!("failed to read {} ({})", info_path, e),
+        retryable: false,
+    })?;
+    let info: secp256k1::Info = match serde_json::from_slice(&d) {
+        Ok(info) => info,
+        Err(_) => secp256k1::Info::load(info_path)?,
+    };
+    if info.mnemonic_phrase.is_some() {
+        return Err(Error::Other {
+            message: format!("{} has the mnemonic phrase, back it up first", info_path),
+            retryable: false,
+        });
+    }
+    let sk = if let Some(cb58) = &info.private_key_cb58 {
+        secp256k1::private_key::Key::from_cb58(cb58)?
+    } else if let Some(hex) = &info.private_key_hex {
+        secp256k1::private_key::Key::from_hex(hex)?
+    } else {
+        return Err(Error::Other {
+            message: format!("no private key found in {}", info_path),
+            retryable: false,
+        });
+    };
+
+    let ks = sk.to_keystore(password, opts)?;
+    replace_plaintext(&ks, info_path, keystore_path, |file_path| {
+        let loaded = secp256k1::private_key::Key::from_keystore_file(file_path, password)?;
+        if loaded != sk {
+            return Err(Error::Other {
+                message: format!("migrated keystore {} does not match", file_path),
+                retryable: false,
+            });
+        }
+        Ok(())
+    })?;
+    Ok(sk)
+}
+
+/// Migrates the raw BLS key file (see "bls::private_key::Key::generate_to_file")
+/// to the keystore file. The plaintext file is removed once the written
+/// keystore is verified to decrypt to the same key.
+/// "keystore_path" may be the same as "key_path" to replace the file in place.
+pub fn migrate_bls_key_file(
+    key_path: &str,
+    keystore_path: &str,
+    password: &[u8],
+    opts: &Options,
+) -> Result<bls::private_key::Key> {
+    let sk = bls::private_key::Key::from_file(key_path).map_err(|e| Error::Other {
+        message: format!("failed to load BLS key {} ({})", key_path, e),
+        retryable: false,
+    })?;
+
+    let ks = sk.to_keystore(password, opts)?;
+    replace_plaintext(&ks, key_path, keystore_path, |file_path| {
+        let loaded = bls::private_key::Key::from_keystore_file(file_path, password)?;
+        if loaded.to_bytes() != sk.to_bytes() {
+            return Err(Error::Other {
+                message: format!("migrated keystore {} does not match", file_path),
+                retryable: false,
+            });
+        }
+        Ok(())
+    })?;
+    Ok(sk)
+}
+
+/// Writes the migrated keystore and removes the plaintext key file, once
+/// "verify" reads the same key back from the written file. A new keystore
+/// file never overwrites an existing one. Migrating in place writes to a
+/// temporary file in the same directory, which is renamed over the plaintext
+/// file once synced and verified, so that a crash leaves either file intact.
+fn replace_plaintext(
+    ks: &Keystore,
+    plaintext_path: &str,
+    keystore_path: &str,
+    verify: impl FnOnce(&str) -> Result<()>,
+) -> Result<()> {
+    if Path::new(plaintext_path) != Path::new(keystore_path) {
+        ks.write_file(keystore_path, true)?;
+        verify(keystore_path)?;
+
+        log::info!("removing plaintext key file {}", plaintext_path);
+        return fs::remove_file(plaintext_path).map_err(|e| Error::Other {
+            message: format!("failed to remove {} ({})", plaintext_path, e),
+            retryable: false,
+        });
+    }
+
+    let mut suffix = [0u8; 8];
+    rand::rngs::OsRng.fill_bytes(&mut suffix);
+    let tmp_path = format!("{}.{}.tmp", keystore_path, hex::encode(suffix));
+    let res = ks
+        .write_file(&tmp_path, true)
+        .and_then(|_| verify(&tmp_path))
+        .and_then(|_| {
+            log::info!("replacing plaintext key file {}", plaintext_path);
+            fs::rename(&tmp_path, keystore_path).map_err(|e| Error::Other {
+                message: format!("failed to rename {} ({})", tmp_path, e),
+                retryable: false,
+            })
+        });
+    if res.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    res
+}
+
+fn derive_key(params: &KdfParams, password: &[u8]) -> Result<Zeroizing<[u8; DK_LEN]>> {
+    let mut dk = Zeroizing::new([0u8; DK_LEN]);
+    match params {
+        KdfParams::Scrypt {
+            dklen,
+            n,
+            r,
+            p,
+            salt,
+        } => {
+            check_dklen(*dklen)?;
+            if !n.is_power_of_two() || *n < 2 {
+                return Err(Error::Other {
+                    message: format!("invalid scrypt n {}", n),
+                    retryable: false,
+                });
+            }
+            if u64::from(*n) * u64::from(*r) * u64::from(*p) > MAX_SCRYPT_COST {
+                return Err(Error::Other {
+                    message: format!("scrypt n {} r {} p {} exceed the maximum cost", n, r, p),
+                    retryable: false,
+                });
+            }
+            let params = scrypt::Params::new(n.trailing_zeros() as u8, *r, *p).map_err(|e| {
+                Error::Other {
+                    message: format!("invalid scrypt params ({})", e),
+                    retryable: false,
+                }
+            })?;
+            scrypt::scrypt(password, salt, &params, dk.as_mut()).map_err(|e| Error::Other {
+                message: format!("failed scrypt ({})", e),
+                retryable: false,
+            })?;
+        }
+        KdfParams::Pbkdf2 {
+            dklen,
+            c,
+            prf,
+            salt,
+        } => {
+            check_dklen(*dklen)?;
+            if prf != PRF_HMAC_SHA256 {
+                return Err(Error::Other {
+                    message: format!("unsupported pbkdf2 prf '{}'", prf),
+                    retryable: false,
+                });
+            }
+            if *c > MAX_PBKDF2_C {
+                return Err(Error::Other {
+                    message: format!("pbkdf2 c {} exceeds the maximum {}", c, MAX_PBKDF2_C),
+                    retryable: false,
+                });
+            }
+            pbkdf2::pbkdf2_hmac::<sha2::Sha256>(password, salt, *c, dk.as_mut());
+        }
+    }
+    Ok(dk)
+}
+
+fn check_dklen(dklen: usize) -> Result<()> {
+    if dklen != DK_LEN {
+        return Err(Error::Other {
+            message: format!("unsupported dklen {}", dklen),
+            retryable: false,
+        });
+    }
+    Ok(())
+}
+
+fn mac(dk: &[u8], ciphertext: &[u8]) -> [u8; 32] {
+    let mut b = Vec::with_capacity(16 + ciphertext.len());
+    b.extend_from_slice(&dk[16..32]);
+    b.extend_from_slice(ciphertext);
+    hash::keccak256(b).0
+}
+
+fn invalid_iv(len: usize) -> Error {
+    Error::Other {
+        message: format!("invalid iv length {}", len),
+        retryable: false,
+    }
+}
+
+/// Formats the random bytes as the version 4 UUID.
+fn uuid_v4(mut b: [u8; 16]) -> String {
+    b[6] = (b[6] & 0x0f) | 0x40;
+    b[8] = (b[8] & 0x3f) | 0x80;
+    let h = hex::encode(b);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &h[0..8],
+        &h[8..12],
+        &h[12..16],
+        &h[16..20],
+        &h[20..32]
+    )
+}
+
+#[cfg(unix)]
+fn create_private_file(file_path: &str, create_new: bool) -> std::io::Result<File> {
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+    let mut opts = fs::OpenOptions::new();
+    opts.write(true).mode(0o600);
+    if create_new {
+        opts.create_new(true);
+    } else {
+        opts.create(true).truncate(true);
+    }
+    let f = opts.open(file_path)?;
+
+    // the mode only applies to the new file, so restrict the existing one too
+    f.set_permissions(fs::Permissions::from_mode(0o600))?;
+    Ok(f)
+}
+
+#[cfg(not(unix))]
+fn create_private_file(file_path: &str, create_new: bool) -> std::io::Result<File> {
+    let mut opts = fs::OpenOptions::new();
+    opts.write(true);
+    if create_new {
+        opts.create_new(true);
+    } else {
+        opts.create(true).truncate(true);
+    }
+    opts.open(file_path)
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --features keystore --lib -- key::keystore::test_eth_v3_vectors --exact --show-output
+#[test]
+fn test_eth_v3_vectors() {
+    // ref. <https://ethereum.org/en/developers/docs/data-structures-and-encoding/web3-secret-storage/#test-vectors>
+    let expected = "7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d";
+
+    let ks: Keystore = serde_json::from_str(
+        r#"{
+    "crypto" : {
+        "cipher" : "aes-128-ctr",
+        "cipherparams" : {
+            "iv" : "6087dab2f9fdbbfaddc31a909735c1e6"
+        },
+        "ciphertext" : "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+        "kdf" : "pbkdf2",
+        "kdfparams" : {
+            "c" : 262144,
+            "dklen" : 32,
+            "prf" : "hmac-sha256",
+            "salt" : "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+        },
+        "mac" : "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+    },
+    "id" : "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+    "version" : 3
+}"#,
+    )
+    .unwrap();
+    assert_eq!(hex::encode(ks.decrypt(b"testpassword").unwrap()), expected);
+    assert!(ks.decrypt(b"wrongpassword").is_err());
+
+    let sk = secp256k1::private_key::Key::from_keystore(&ks, b"testpassword").unwrap();
+    assert_eq!(sk.to_hex().trim_start_matches("0x"), expected);
+
+    // "kdf" must name the untagged "kdfparams"
+    let mut ks = ks;
+    ks.crypto.kdf = String::from(KDF_SCRYPT);
+    let err = ks.decrypt(b"testpassword").unwrap_err();
+    assert!(err.contains("does not match"));
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --features keystore --lib -- key::keystore::test_keystore --exact --show-output
+#[test]
+fn test_keystore() {
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .is_test(true)
+        .try_init();
+
+    let opts = [
+        Options::light(),
+        Options {
+            kdf: Kdf::Pbkdf2 { c: 1024 },
+            cipher: Cipher::Aes128Gcm,
+        },
+    ];
+    for opts in opts.iter() {
+        let sk = secp256k1::private_key::Key::generate().unwrap();
+        let ks = sk.to_keystore(b"password", opts).unwrap();
+        assert_eq!(ks.version, VERSION);
+        assert_eq!(ks.id.len(), 36);
+        assert_eq!(
+            ks.address.as_ref().unwrap(),
+            &hex::encode(sk.to_public_key().to_h160())
+        );
+
+        let encoded = serde_json::to_string(&ks).unwrap();
+        let decoded: Keystore = serde_json::from_str(&encoded).unwrap();
+        assert_eq!(ks, decoded);
+        assert_eq!(
+            secp256k1::private_key::Key::from_keystore(&decoded, b"password").unwrap(),
+            sk
+        );
+        assert!(secp256k1::private_key::Key::from_keystore(&decoded, b"wrong").is_err());
+        assert!(bls::private_key::Key::from_keystore(&decoded, b"password").is_err());
+
+        let bls_sk = bls::private_key::Key::generate().unwrap();
+        let ks = bls_sk.to_keystore(b"password", opts).unwrap();
+        assert_eq!(ks.key_type.as_deref(), Some(KEY_TYPE_BLS));
+        let loaded = bls::private_key::Key::from_keystore(&ks, b"password").unwrap();
+        assert_eq!(loaded.to_bytes(), bls_sk.to_bytes());
+        assert!(secp256k1::private_key::Key::from_keystore(&ks, b"password").is_err());
+    }
+
+    // the costs of the crafted files are rejected before deriving the key
+    let sk = secp256k1::private_key::Key::generate().unwrap();
+    let ks = sk.to_keystore(b"password", &Options::light()).unwrap();
+    for kdfparams in [
+        KdfParams::Scrypt {
+            dklen: DK_LEN,
+            n: 1 << 31,
+            r: 8,
+            p: 1,
+            salt: vec![0; SALT_LEN],
+        },
+        KdfParams::Scrypt {
+            dklen: DK_LEN,
+            n: 1 << 18,
+            r: 8,
+            p: 17,
+            salt: vec![0; SALT_LEN],
+        },
+        KdfParams::Pbkdf2 {
+            dklen: DK_LEN,
+            c: u32::MAX,
+            prf: String::from(PRF_HMAC_SHA256),
+            salt: vec![0; SALT_LEN],
+        },
+    ] {
+        let mut crafted = ks.clone();
+        crafted.crypto.kdf = String::from(kdfparams.kdf());
+        crafted.crypto.kdfparams = kdfparams;
+        let err = crafted.decrypt(b"password").unwrap_err();
+        assert!(err.contains("maximum"), "{}", err.message());
+    }
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --features keystore --lib -- key::keystore::test_migrate --exact --show-output
+#[test]
+fn test_migrate() {
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .is_test(true)
+        .try_init();
+
+    let opts = Options::light();
+
+    let sk = secp256k1::private_key::Key::generate().unwrap();
+    let info_path = random_manager::tmp_path(10, Some(".json")).unwrap();
+    sk.to_info(1).unwrap().sync(info_path.clone()).unwrap();
+    let keystore_path = random_manager::tmp_path(10, Some(".json")).unwrap();
+
+    let migrated = migrate_secp256k1_info(&info_path, &keystore_path, b"password", &opts).unwrap();
+    assert_eq!(migrated, sk);
+    assert!(!Path::new(&info_path).exists());
+    let loaded =
+        secp256k1::private_key::Key::from_keystore_file(&keystore_path, b"password").unwrap();
+    assert_eq!(loaded, sk);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(&keystore_path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    // never overwrites the existing keystore
+    sk.to_info(1).unwrap().sync(info_path.clone()).unwrap();
+    assert!(migrate_secp256k1_info(&info_path, &keystore_path, b"password", &opts).is_err());
+    assert!(Path::new(&info_path).exists());
+    let loaded =
+        secp256k1::private_key::Key::from_keystore_file(&keystore_path, b"password").unwrap();
+    assert_eq!(loaded, sk);
+    fs::remove_file(&info_path).unwrap();
+    fs::remove_file(&keystore_path).unwrap();
+
+    // in place
+    let key_path = random_manager::tmp_path(10, None).unwrap();
+    let bls_sk = bls::private_key::Key::generate_to_file(&key_path).unwrap();
+    let migrated = migrate_bls_key_file(&key_path, &key_path, b"password", &opts).unwrap();
+    assert_eq!(migrated.to_bytes(), bls_sk.to_bytes());
+    assert!(bls::private_key::Key::from_file(&key_path).is_err());
+    let loaded = bls::private_key::Key::from_keystore_file(&key_path, b"password").unwrap();
+    assert_eq!(loaded.to_bytes(), bls_sk.to_bytes());
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(&key_path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+    // no temporary file is left behind
+    let dir = Path::new(&key_path).parent().unwrap();
+    let name = Path::new(&key_path).file_name().unwrap().to_str().unwrap();
+    assert!(!fs::read_dir(dir).unwrap().any(|entry| {
+        let entry = entry.unwrap().file_name();
+        let entry = entry.to_string_lossy();
+        entry.starts_with(name) && entry.ends_with(".tmp")
+    }));
+    fs::remove_file(&key_path).unwrap();
+}
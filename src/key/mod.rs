@@ -1,2 +1,6 @@
 pub mod bls;
 pub mod secp256k1;
+
+#[cfg(feature = "keystore")]
+#[cfg_attr(docsrs, doc(cfg(feature = "keystore")))]
+pub mod keystore;
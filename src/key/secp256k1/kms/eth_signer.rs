@@ -0,0 +1,162 @@
+use std::{fmt, marker::PhantomData};
+
+use crate::{
+    errors::{Error, Result},
+    key,
+};
+use async_trait::async_trait;
+use ethers_core::types::{
+    transaction::{eip2718::TypedTransaction, eip712::Eip712},
+    Address, Signature,
+};
+
+/// Implements "ethers_signers::Signer" for the remote key that only signs
+/// the digests (e.g., AWS KMS, remote signer, PKCS#11).
+/// "E" is the signer error type, which the key errors are converted into.
+pub struct Signer<K, E = Error> {
+    pub inner: K,
+    pub chain_id: primitive_types::U256,
+    pub address: Address,
+    _error: PhantomData<fn() -> E>,
+}
+
+impl<K: Clone, E> Clone for Signer<K, E> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            chain_id: self.chain_id,
+            address: self.address,
+            _error: PhantomData,
+        }
+    }
+}
+
+impl<K: fmt::Debug, E> fmt::Debug for Signer<K, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Signer")
+            .field("inner", &self.inner)
+            .field("chain_id", &self.chain_id)
+            .field("address", &self.address)
+            .finish()
+    }
+}
+
+impl<K, E> Signer<K, E>
+where
+    K: key::secp256k1::SignOnly + key::secp256k1::ReadOnly,
+{
+    pub fn new(inner: K, chain_id: primitive_types::U256) -> Result<Self> {
+        let address: Address = inner.h160_address();
+        Ok(Self {
+            inner,
+            chain_id,
+            address,
+            _error: PhantomData,
+        })
+    }
+
+    async fn sign_digest(&self, digest: &[u8]) -> Result<Signature> {
+        let b = key::secp256k1::SignOnly::sign_digest(&self.inner, digest).await?;
+        let sig = key::secp256k1::signature::Sig::from_bytes(&b)?;
+        Ok(Signature {
+            r: sig.r(),
+            s: sig.s(),
+            v: sig.v(),
+        })
+    }
+
+    async fn sign_digest_with_eip155(
+        &self,
+        digest: ethers_core::types::H256,
+        chain_id: u64,
+    ) -> Result<Signature> {
+        let mut sig = self.sign_digest(digest.as_ref()).await?;
+        key::secp256k1::signature::apply_eip155(&mut sig, chain_id);
+        Ok(sig)
+    }
+}
+
+#[async_trait]
+impl<K, E> ethers_signers::Signer for Signer<K, E>
+where
+    K: key::secp256k1::SignOnly + key::secp256k1::ReadOnly + fmt::Debug + Send + Sync,
+    E: From<Error> + std::error::Error + Send + Sync,
+{
+    type Error = E;
+
+    /// Implements "eth_sign" using "ethers_core::utils::hash_message".
+    /// ref. <https://eips.ethereum.org/EIPS/eip-191>
+    /// ref. <https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_sign>
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: S,
+    ) -> std::result::Result<Signature, Self::Error> {
+        let message = message.as_ref();
+        let message_hash = ethers_core::utils::hash_message(message);
+
+        self.sign_digest_with_eip155(message_hash, self.chain_id.as_u64())
+            .await
+            .map_err(|e| {
+                E::from(Error::API {
+                    message: format!("failed sign_digest_with_eip155 {}", e),
+                    retryable: e.retryable(),
+                })
+            })
+    }
+
+    async fn sign_transaction(
+        &self,
+        tx: &TypedTransaction,
+    ) -> std::result::Result<Signature, Self::Error> {
+        let mut tx_with_chain = tx.clone();
+        let chain_id = tx_with_chain
+            .chain_id()
+            .map(|id| id.as_u64())
+            .unwrap_or(self.chain_id.as_u64());
+        tx_with_chain.set_chain_id(chain_id);
+
+        let sighash = tx_with_chain.sighash();
+        self.sign_digest_with_eip155(sighash, chain_id)
+            .await
+            .map_err(|e| {
+                E::from(Error::API {
+                    message: format!("failed sign_digest_with_eip155 {}", e),
+                    retryable: e.retryable(),
+                })
+            })
+    }
+
+    /// Implements "eth_signTypedData".
+    /// ref. <https://eips.ethereum.org/EIPS/eip-712>
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        payload: &T,
+    ) -> std::result::Result<Signature, Self::Error> {
+        let digest = payload.encode_eip712().map_err(|e| {
+            E::from(Error::Other {
+                message: format!("failed encode_eip712 {}", e),
+                retryable: false,
+            })
+        })?;
+        self.sign_digest(digest.as_ref()).await.map_err(|e| {
+            E::from(Error::API {
+                message: format!("failed sign_digest {}", e),
+                retryable: e.retryable(),
+            })
+        })
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.chain_id.as_u64()
+    }
+
+    fn with_chain_id<T: Into<u64>>(mut self, chain_id: T) -> Self {
+        let chain_id: u64 = chain_id.into();
+        self.chain_id = primitive_types::U256::from(chain_id);
+        self
+    }
+}
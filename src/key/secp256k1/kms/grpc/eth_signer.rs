@@ -0,0 +1,2 @@
+/// Ethers signer of the remote signer key.
+pub type Signer = crate::key::secp256k1::kms::eth_signer::Signer<super::Key>;
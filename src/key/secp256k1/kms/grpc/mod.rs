@@ -0,0 +1,329 @@
+pub mod eth_signer;
+
+use std::{collections::HashMap, future::Future, net::SocketAddr};
+
+use crate::{
+    errors::{Error, Result},
+    hash,
+    ids::short,
+    key,
+    proto::pb::signer::{
+        signer_client::SignerClient,
+        signer_server::{self, SignerServer},
+        GetPublicKeyRequest, GetPublicKeyResponse, SignDigestRequest, SignDigestResponse,
+    },
+};
+use async_trait::async_trait;
+use ethers_core::types::Signature as EthSig;
+use tonic::transport::{Channel, Endpoint};
+
+/// Represents a secp256k1 key held by a remote signer service
+/// (see "proto/protos/static/signer/signer.proto").
+/// Note that the actual private key never leaves the signer process.
+/// Private key signing operation must be done via the "SignDigest" RPC.
+#[derive(Debug, Clone)]
+pub struct Key {
+    /// gRPC endpoint of the signer (e.g., "http://127.0.0.1:9700").
+    pub endpoint: String,
+    client: SignerClient<Channel>,
+
+    /// Public key.
+    pub public_key: key::secp256k1::public_key::Key,
+}
+
+impl Key {
+    /// Connects to the remote signer and fetches its public key.
+    pub async fn connect(endpoint: &str) -> Result<Self> {
+        let channel = Endpoint::from_shared(endpoint.to_string())
+            .map_err(|e| Error::Other {
+                message: format!("invalid endpoint '{}' {}", endpoint, e),
+                retryable: false,
+            })?
+            .connect()
+            .await
+            .map_err(|e| Error::API {
+                message: format!("failed to connect to '{}' {}", endpoint, e),
+                retryable: true,
+            })?;
+        let mut client = SignerClient::new(channel);
+
+        let resp = client
+            .get_public_key(GetPublicKeyRequest {})
+            .await
+            .map_err(|e| Error::API {
+                message: format!("failed signer.GetPublicKey {}", e),
+                retryable: is_retryable(&e),
+            })?;
+        let public_key =
+            key::secp256k1::public_key::Key::from_sec1_bytes(&resp.into_inner().public_key)?;
+        log::info!(
+            "fetched public key with ETH address '{}' from '{}'",
+            public_key.to_eth_address(),
+            endpoint
+        );
+
+        Ok(Self {
+            endpoint: endpoint.to_string(),
+            client,
+            public_key,
+        })
+    }
+
+    pub fn to_public_key(&self) -> key::secp256k1::public_key::Key {
+        self.public_key
+    }
+
+    /// Converts to Info.
+    pub fn to_info(&self, network_id: u32) -> Result<key::secp256k1::Info> {
+        let short_addr = self.public_key.to_short_id()?;
+        let eth_addr = self.public_key.to_eth_address();
+        let h160_addr = self.public_key.to_h160();
+
+        let mut addresses = HashMap::new();
+        addresses.insert(
+            network_id,
+            key::secp256k1::ChainAddresses {
+                x: self.public_key.to_hrp_address(network_id, "X")?,
+                p: self.public_key.to_hrp_address(network_id, "P")?,
+            },
+        );
+
+        Ok(key::secp256k1::Info {
+            id: Some(self.endpoint.clone()),
+            key_type: key::secp256k1::KeyType::Remote,
+
+            addresses,
+
+            short_address: short_addr,
+            eth_address: eth_addr,
+            h160_address: h160_addr,
+
+            ..Default::default()
+        })
+    }
+
+    /// Signs the 32-byte digest via the remote signer, and returns
+    /// the 65-byte recoverable signature in [R || S || V] format.
+    /// The signature is verified against the public key, so a misbehaving
+    /// signer cannot return a signature for a different key.
+    pub async fn sign_digest_bytes(&self, digest: &[u8]) -> Result<[u8; 65]> {
+        if digest.len() != hash::SHA256_OUTPUT_LEN {
+            return Err(Error::Other {
+                message: format!(
+                    "invalid digest length {} (expected {})",
+                    digest.len(),
+                    hash::SHA256_OUTPUT_LEN
+                ),
+                retryable: false,
+            });
+        }
+
+        let resp = self
+            .client
+            .clone()
+            .sign_digest(SignDigestRequest {
+                digest: digest.to_vec().into(),
+            })
+            .await
+            .map_err(|e| Error::API {
+                message: format!("failed signer.SignDigest {}", e),
+                retryable: is_retryable(&e),
+            })?;
+        let sig = resp.into_inner().signature;
+
+        if !self.public_key.verify(digest, &sig)? {
+            return Err(Error::Other {
+                message: "remote signature does not match the public key".to_string(),
+                retryable: false,
+            });
+        }
+
+        let mut b = [0u8; key::secp256k1::signature::LEN];
+        b.copy_from_slice(&sig);
+        Ok(b)
+    }
+
+    /// Signs the 32-byte digest and converts to the ethers signature.
+    pub async fn sign_digest(&self, digest: &[u8]) -> Result<EthSig> {
+        let b = self.sign_digest_bytes(digest).await?;
+        let sig = key::secp256k1::signature::Sig::from_bytes(&b)?;
+        Ok(EthSig {
+            r: sig.r(),
+            s: sig.s(),
+            v: sig.v(),
+        })
+    }
+}
+
+fn is_retryable(s: &tonic::Status) -> bool {
+    matches!(
+        s.code(),
+        tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::ResourceExhausted
+    )
+}
+
+#[async_trait]
+impl key::secp256k1::SignOnly for Key {
+    fn signing_key(&self) -> Result<k256::ecdsa::SigningKey> {
+        Err(Error::Other {
+            message: "signing key not available for remote signer".to_string(),
+            retryable: false,
+        })
+    }
+
+    async fn sign_digest(&self, msg: &[u8]) -> Result<[u8; 65]> {
+        self.sign_digest_bytes(msg).await
+    }
+}
+
+/// ref. <https://doc.rust-lang.org/book/ch10-02-traits.html>
+impl key::secp256k1::ReadOnly for Key {
+    fn key_type(&self) -> key::secp256k1::KeyType {
+        key::secp256k1::KeyType::Remote
+    }
+
+    fn hrp_address(&self, network_id: u32, chain_id_alias: &str) -> Result<String> {
+        self.to_public_key()
+            .to_hrp_address(network_id, chain_id_alias)
+    }
+
+    fn short_address(&self) -> Result<short::Id> {
+        self.to_public_key().to_short_id()
+    }
+
+    fn short_address_bytes(&self) -> Result<Vec<u8>> {
+        self.to_public_key().to_short_bytes()
+    }
+
+    fn eth_address(&self) -> String {
+        self.to_public_key().to_eth_address()
+    }
+
+    fn h160_address(&self) -> primitive_types::H160 {
+        self.to_public_key().to_h160()
+    }
+}
+
+/// Serves any "SignOnly" key over the signer gRPC protocol,
+/// so that the key can be used remotely via "Key".
+pub struct Server<T> {
+    signer: T,
+    public_key: key::secp256k1::public_key::Key,
+}
+
+impl<T> Server<T>
+where
+    T: key::secp256k1::SignOnly + Send + Sync + 'static,
+{
+    /// Creates a new server for the signer.
+    /// The public key is required since "SignOnly" keys may not expose
+    /// their signing key (e.g., AWS KMS).
+    pub fn new(signer: T, public_key: key::secp256k1::public_key::Key) -> Self {
+        Self { signer, public_key }
+    }
+
+    /// Serves the signer on the address until the "shutdown" future completes.
+    pub async fn serve<F>(self, addr: SocketAddr, shutdown: F) -> Result<()>
+    where
+        F: Future<Output = ()>,
+    {
+        log::info!(
+            "serving signer for '{}' on {}",
+            self.public_key.to_eth_address(),
+            addr
+        );
+        tonic::transport::Server::builder()
+            .add_service(SignerServer::new(self))
+            .serve_with_shutdown(addr, shutdown)
+            .await
+            .map_err(|e| Error::Other {
+                message: format!("signer server failed {}", e),
+                retryable: false,
+            })
+    }
+}
+
+#[tonic::async_trait]
+impl<T> signer_server::Signer for Server<T>
+where
+    T: key::secp256k1::SignOnly + Send + Sync + 'static,
+{
+    async fn get_public_key(
+        &self,
+        _req: tonic::Request<GetPublicKeyRequest>,
+    ) -> std::result::Result<tonic::Response<GetPublicKeyResponse>, tonic::Status> {
+        Ok(tonic::Response::new(GetPublicKeyResponse {
+            public_key: self.public_key.to_compressed_bytes().to_vec().into(),
+        }))
+    }
+
+    async fn sign_digest(
+        &self,
+        req: tonic::Request<SignDigestRequest>,
+    ) -> std::result::Result<tonic::Response<SignDigestResponse>, tonic::Status> {
+        let digest = req.into_inner().digest;
+        if digest.len() != hash::SHA256_OUTPUT_LEN {
+            return Err(tonic::Status::invalid_argument(format!(
+                "invalid digest length {} (expected {})",
+                digest.len(),
+                hash::SHA256_OUTPUT_LEN
+            )));
+        }
+
+        let sig = self
+            .signer
+            .sign_digest(&digest)
+            .await
+            .map_err(|e| tonic::Status::unknown(e.message()))?;
+        Ok(tonic::Response::new(SignDigestResponse {
+            signature: sig.to_vec().into(),
+        }))
+    }
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features kms_grpc -- key::secp256k1::kms::grpc::test_remote_signer --exact --show-output
+#[tokio::test]
+async fn test_remote_signer() {
+    use crate::key::secp256k1::{ReadOnly, SignOnly};
+    use tokio::{net::TcpListener, sync::oneshot};
+    use tokio_stream::wrappers::TcpListenerStream;
+
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .is_test(true)
+        .try_init();
+
+    let pk = key::secp256k1::private_key::Key::generate().unwrap();
+    let pubkey = pk.to_public_key();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (stop_tx, stop_rx) = oneshot::channel::<()>();
+    let server = tokio::spawn(
+        tonic::transport::Server::builder()
+            .add_service(SignerServer::new(Server::new(pk.clone(), pubkey)))
+            .serve_with_incoming_shutdown(TcpListenerStream::new(listener), async {
+                stop_rx.await.ok();
+            }),
+    );
+
+    let remote = Key::connect(&format!("http://{}", addr)).await.unwrap();
+    assert_eq!(remote.to_public_key(), pubkey);
+    assert_eq!(remote.key_type(), key::secp256k1::KeyType::Remote);
+    assert_eq!(remote.eth_address(), pk.to_public_key().to_eth_address());
+
+    let digest = hash::sha256(b"hello world");
+    let sig = SignOnly::sign_digest(&remote, &digest).await.unwrap();
+    assert!(pubkey.verify(&digest, &sig).unwrap());
+    assert_eq!(
+        key::secp256k1::public_key::Key::from_signature(&digest, &sig).unwrap(),
+        pubkey
+    );
+
+    let err = remote.sign_digest_bytes(b"short").await.unwrap_err();
+    assert!(err.contains("invalid digest length"));
+    assert!(remote.signing_key().is_err());
+
+    stop_tx.send(()).unwrap();
+    server.await.unwrap().unwrap();
+}
@@ -1,3 +1,18 @@
+#[cfg(any(feature = "kms_aws", feature = "kms_grpc", feature = "kms_pkcs11"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "kms_aws", feature = "kms_grpc", feature = "kms_pkcs11")))
+)]
+pub mod eth_signer;
+
 #[cfg(feature = "kms_aws")]
 #[cfg_attr(docsrs, doc(cfg(feature = "kms_aws")))]
 pub mod aws;
+
+#[cfg(feature = "kms_grpc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "kms_grpc")))]
+pub mod grpc;
+
+#[cfg(all(unix, feature = "kms_pkcs11"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "kms_pkcs11")))]
+pub mod pkcs11;
@@ -0,0 +1,2 @@
+/// Ethers signer of the PKCS#11 key.
+pub type Signer = crate::key::secp256k1::kms::eth_signer::Signer<super::Key>;
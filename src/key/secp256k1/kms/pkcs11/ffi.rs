@@ -0,0 +1,630 @@
+//! Minimal PKCS#11 (Cryptoki) v2.40 bindings for ECDSA key generation and signing.
+//! ref. <https://docs.oasis-open.org/pkcs11/pkcs11-base/v2.40/os/pkcs11-base-v2.40-os.html>
+//! ref. <https://docs.oasis-open.org/pkcs11/pkcs11-curr/v2.40/os/pkcs11-curr-v2.40-os.html>
+#![allow(non_camel_case_types, non_snake_case)]
+
+use std::{
+    collections::HashMap,
+    ffi::{c_void, CStr, CString},
+    fs,
+    os::raw::{c_uchar, c_ulong},
+    path::PathBuf,
+    ptr,
+    sync::{Arc, Mutex, PoisonError, Weak},
+};
+
+use crate::errors::{Error, Result};
+use lazy_static::lazy_static;
+
+pub type CK_ULONG = c_ulong;
+pub type CK_RV = CK_ULONG;
+pub type CK_SLOT_ID = CK_ULONG;
+pub type CK_SESSION_HANDLE = CK_ULONG;
+pub type CK_OBJECT_HANDLE = CK_ULONG;
+
+pub const CKR_OK: CK_RV = 0x0000_0000;
+pub const CKR_USER_ALREADY_LOGGED_IN: CK_RV = 0x0000_0100;
+pub const CKR_CRYPTOKI_ALREADY_INITIALIZED: CK_RV = 0x0000_0191;
+
+pub const CKF_RW_SESSION: CK_ULONG = 0x0000_0002;
+pub const CKF_SERIAL_SESSION: CK_ULONG = 0x0000_0004;
+pub const CKF_OS_LOCKING_OK: CK_ULONG = 0x0000_0002;
+
+pub const CKU_USER: CK_ULONG = 1;
+
+pub const CKO_PUBLIC_KEY: CK_ULONG = 0x0000_0002;
+pub const CKO_PRIVATE_KEY: CK_ULONG = 0x0000_0003;
+
+pub const CKK_EC: CK_ULONG = 0x0000_0003;
+
+pub const CKA_CLASS: CK_ULONG = 0x0000_0000;
+pub const CKA_TOKEN: CK_ULONG = 0x0000_0001;
+pub const CKA_PRIVATE: CK_ULONG = 0x0000_0002;
+pub const CKA_LABEL: CK_ULONG = 0x0000_0003;
+pub const CKA_KEY_TYPE: CK_ULONG = 0x0000_0100;
+pub const CKA_SENSITIVE: CK_ULONG = 0x0000_0103;
+pub const CKA_SIGN: CK_ULONG = 0x0000_0108;
+pub const CKA_VERIFY: CK_ULONG = 0x0000_010A;
+pub const CKA_EXTRACTABLE: CK_ULONG = 0x0000_0162;
+pub const CKA_EC_PARAMS: CK_ULONG = 0x0000_0180;
+pub const CKA_EC_POINT: CK_ULONG = 0x0000_0181;
+
+pub const CKM_EC_KEY_PAIR_GEN: CK_ULONG = 0x0000_1040;
+pub const CKM_ECDSA: CK_ULONG = 0x0000_1041;
+
+/// DER-encoded OID of the secp256k1 curve (1.3.132.0.10), used for "CKA_EC_PARAMS".
+pub const SECP256K1_EC_PARAMS: [u8; 7] = [0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x0a];
+
+#[repr(C)]
+struct CK_VERSION {
+    major: c_uchar,
+    minor: c_uchar,
+}
+
+#[repr(C)]
+struct CK_ATTRIBUTE {
+    type_: CK_ULONG,
+    pValue: *mut c_void,
+    ulValueLen: CK_ULONG,
+}
+
+#[repr(C)]
+struct CK_MECHANISM {
+    mechanism: CK_ULONG,
+    pParameter: *mut c_void,
+    ulParameterLen: CK_ULONG,
+}
+
+#[repr(C)]
+struct CK_C_INITIALIZE_ARGS {
+    CreateMutex: *mut c_void,
+    DestroyMutex: *mut c_void,
+    LockMutex: *mut c_void,
+    UnlockMutex: *mut c_void,
+    flags: CK_ULONG,
+    pReserved: *mut c_void,
+}
+
+type Unused = Option<unsafe extern "C" fn()>;
+
+/// Prefix of "CK_FUNCTION_LIST" up to "C_GenerateKeyPair".
+/// The list is only accessed via the pointer returned by the module,
+/// so the trailing functions can be omitted.
+#[repr(C)]
+struct CK_FUNCTION_LIST {
+    version: CK_VERSION,
+    C_Initialize: Option<unsafe extern "C" fn(*mut c_void) -> CK_RV>,
+    C_Finalize: Option<unsafe extern "C" fn(*mut c_void) -> CK_RV>,
+    C_GetInfo: Unused,
+    C_GetFunctionList: Unused,
+    C_GetSlotList: Unused,
+    C_GetSlotInfo: Unused,
+    C_GetTokenInfo: Unused,
+    C_GetMechanismList: Unused,
+    C_GetMechanismInfo: Unused,
+    C_InitToken: Unused,
+    C_InitPIN: Unused,
+    C_SetPIN: Unused,
+    C_OpenSession: Option<
+        unsafe extern "C" fn(
+            CK_SLOT_ID,
+            CK_ULONG,
+            *mut c_void,
+            *mut c_void,
+            *mut CK_SESSION_HANDLE,
+        ) -> CK_RV,
+    >,
+    C_CloseSession: Option<unsafe extern "C" fn(CK_SESSION_HANDLE) -> CK_RV>,
+    C_CloseAllSessions: Unused,
+    C_GetSessionInfo: Unused,
+    C_GetOperationState: Unused,
+    C_SetOperationState: Unused,
+    C_Login: Option<
+        unsafe extern "C" fn(CK_SESSION_HANDLE, CK_ULONG, *const c_uchar, CK_ULONG) -> CK_RV,
+    >,
+    C_Logout: Unused,
+    C_CreateObject: Unused,
+    C_CopyObject: Unused,
+    C_DestroyObject: Unused,
+    C_GetObjectSize: Unused,
+    C_GetAttributeValue: Option<
+        unsafe extern "C" fn(
+            CK_SESSION_HANDLE,
+            CK_OBJECT_HANDLE,
+            *mut CK_ATTRIBUTE,
+            CK_ULONG,
+        ) -> CK_RV,
+    >,
+    C_SetAttributeValue: Unused,
+    C_FindObjectsInit:
+        Option<unsafe extern "C" fn(CK_SESSION_HANDLE, *mut CK_ATTRIBUTE, CK_ULONG) -> CK_RV>,
+    C_FindObjects: Option<
+        unsafe extern "C" fn(
+            CK_SESSION_HANDLE,
+            *mut CK_OBJECT_HANDLE,
+            CK_ULONG,
+            *mut CK_ULONG,
+        ) -> CK_RV,
+    >,
+    C_FindObjectsFinal: Option<unsafe extern "C" fn(CK_SESSION_HANDLE) -> CK_RV>,
+    C_EncryptInit: Unused,
+    C_Encrypt: Unused,
+    C_EncryptUpdate: Unused,
+    C_EncryptFinal: Unused,
+    C_DecryptInit: Unused,
+    C_Decrypt: Unused,
+    C_DecryptUpdate: Unused,
+    C_DecryptFinal: Unused,
+    C_DigestInit: Unused,
+    C_Digest: Unused,
+    C_DigestUpdate: Unused,
+    C_DigestKey: Unused,
+    C_DigestFinal: Unused,
+    C_SignInit: Option<
+        unsafe extern "C" fn(CK_SESSION_HANDLE, *mut CK_MECHANISM, CK_OBJECT_HANDLE) -> CK_RV,
+    >,
+    C_Sign: Option<
+        unsafe extern "C" fn(
+            CK_SESSION_HANDLE,
+            *const c_uchar,
+            CK_ULONG,
+            *mut c_uchar,
+            *mut CK_ULONG,
+        ) -> CK_RV,
+    >,
+    C_SignUpdate: Unused,
+    C_SignFinal: Unused,
+    C_SignRecoverInit: Unused,
+    C_SignRecover: Unused,
+    C_VerifyInit: Unused,
+    C_Verify: Unused,
+    C_VerifyUpdate: Unused,
+    C_VerifyFinal: Unused,
+    C_VerifyRecoverInit: Unused,
+    C_VerifyRecover: Unused,
+    C_DigestEncryptUpdate: Unused,
+    C_DecryptDigestUpdate: Unused,
+    C_SignEncryptUpdate: Unused,
+    C_DecryptVerifyUpdate: Unused,
+    C_GenerateKey: Unused,
+    C_GenerateKeyPair: Option<
+        unsafe extern "C" fn(
+            CK_SESSION_HANDLE,
+            *mut CK_MECHANISM,
+            *mut CK_ATTRIBUTE,
+            CK_ULONG,
+            *mut CK_ATTRIBUTE,
+            CK_ULONG,
+            *mut CK_OBJECT_HANDLE,
+            *mut CK_OBJECT_HANDLE,
+        ) -> CK_RV,
+    >,
+}
+
+/// Attribute in a search or key generation template.
+#[derive(Debug, Clone)]
+pub struct Attribute {
+    pub type_: CK_ULONG,
+    pub value: Vec<u8>,
+}
+
+impl Attribute {
+    pub fn bool(type_: CK_ULONG, v: bool) -> Self {
+        Self {
+            type_,
+            value: vec![u8::from(v)],
+        }
+    }
+
+    pub fn ulong(type_: CK_ULONG, v: CK_ULONG) -> Self {
+        Self {
+            type_,
+            value: v.to_ne_bytes().to_vec(),
+        }
+    }
+
+    pub fn bytes(type_: CK_ULONG, v: &[u8]) -> Self {
+        Self {
+            type_,
+            value: v.to_vec(),
+        }
+    }
+}
+
+/// Converts the attributes to the C template.
+/// The template borrows the attribute values, so the attributes
+/// must outlive the template.
+fn to_template(attrs: &mut [Attribute]) -> Vec<CK_ATTRIBUTE> {
+    attrs
+        .iter_mut()
+        .map(|a| CK_ATTRIBUTE {
+            type_: a.type_,
+            pValue: a.value.as_mut_ptr() as *mut c_void,
+            ulValueLen: a.value.len() as CK_ULONG,
+        })
+        .collect()
+}
+
+fn check(rv: CK_RV, name: &str) -> Result<()> {
+    if rv == CKR_OK {
+        return Ok(());
+    }
+    Err(Error::Other {
+        message: format!("failed {} (CKR 0x{:08x})", name, rv),
+        retryable: false,
+    })
+}
+
+fn missing(name: &str) -> Error {
+    Error::Other {
+        message: format!("PKCS#11 module does not implement {}", name),
+        retryable: false,
+    }
+}
+
+lazy_static! {
+    /// Loaded modules by their library paths. "C_Initialize" and "C_Finalize"
+    /// apply to the whole process, so every session of the same library must
+    /// share one module, which is finalized once the last session drops it.
+    static ref MODULES: Mutex<HashMap<PathBuf, Weak<Module>>> = Mutex::new(HashMap::new());
+}
+
+/// Dynamically loaded PKCS#11 module (e.g., "/usr/lib/softhsm/libsofthsm2.so").
+pub struct Module {
+    path: PathBuf,
+    lib: *mut c_void,
+    funcs: *const CK_FUNCTION_LIST,
+    /// True if this module called "C_Initialize", thus should call "C_Finalize".
+    finalize: bool,
+}
+
+/// The module is initialized with "CKF_OS_LOCKING_OK",
+/// so its functions can be called from multiple threads.
+unsafe impl Send for Module {}
+unsafe impl Sync for Module {}
+
+impl Module {
+    /// Returns the module of the shared library path, loading and initializing
+    /// it unless it is already loaded in the process.
+    pub fn shared(path: &str) -> Result<Arc<Self>> {
+        // the same library may be given by different paths (e.g., symlinks),
+        // while the bare file names are resolved by the dynamic loader
+        let key = fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path));
+        loop {
+            let mut modules = MODULES.lock().unwrap_or_else(PoisonError::into_inner);
+            match modules.get(&key) {
+                Some(module) => {
+                    if let Some(module) = module.upgrade() {
+                        return Ok(module);
+                    }
+                    // the last reference is being dropped, so wait for it to
+                    // finalize the module before initializing it again
+                    drop(modules);
+                    std::thread::yield_now();
+                }
+                None => {
+                    let module = Arc::new(Self::load(path, key.clone())?);
+                    modules.insert(key, Arc::downgrade(&module));
+                    return Ok(module);
+                }
+            }
+        }
+    }
+
+    /// Loads the module from the shared library path and initializes it.
+    fn load(path: &str, key: PathBuf) -> Result<Self> {
+        let c_path = CString::new(path).map_err(|e| Error::Other {
+            message: format!("invalid module path '{}' {}", path, e),
+            retryable: false,
+        })?;
+
+        unsafe {
+            let lib = libc::dlopen(c_path.as_ptr(), libc::RTLD_NOW | libc::RTLD_LOCAL);
+            if lib.is_null() {
+                return Err(Error::Other {
+                    message: format!("failed dlopen '{}' {}", path, dl_error()),
+                    retryable: false,
+                });
+            }
+
+            let sym = libc::dlsym(lib, b"C_GetFunctionList\0".as_ptr() as *const _);
+            if sym.is_null() {
+                libc::dlclose(lib);
+                return Err(Error::Other {
+                    message: format!("failed dlsym C_GetFunctionList {}", dl_error()),
+                    retryable: false,
+                });
+            }
+            let get_function_list: unsafe extern "C" fn(*mut *const CK_FUNCTION_LIST) -> CK_RV =
+                std::mem::transmute(sym);
+
+            let mut funcs: *const CK_FUNCTION_LIST = ptr::null();
+            let rv = get_function_list(&mut funcs);
+            if rv != CKR_OK || funcs.is_null() {
+                libc::dlclose(lib);
+                check(rv, "C_GetFunctionList")?;
+                return Err(missing("C_GetFunctionList"));
+            }
+
+            // only constructs the module once initialized, since dropping
+            // it locks the loaded modules (see "shared")
+            let initialize = match (*funcs).C_Initialize {
+                Some(initialize) => initialize,
+                None => {
+                    libc::dlclose(lib);
+                    return Err(missing("C_Initialize"));
+                }
+            };
+            let mut args = CK_C_INITIALIZE_ARGS {
+                CreateMutex: ptr::null_mut(),
+                DestroyMutex: ptr::null_mut(),
+                LockMutex: ptr::null_mut(),
+                UnlockMutex: ptr::null_mut(),
+                flags: CKF_OS_LOCKING_OK,
+                pReserved: ptr::null_mut(),
+            };
+            let rv = initialize(&mut args as *mut _ as *mut c_void);
+            let finalize = rv != CKR_CRYPTOKI_ALREADY_INITIALIZED;
+            if finalize {
+                if let Err(e) = check(rv, "C_Initialize") {
+                    libc::dlclose(lib);
+                    return Err(e);
+                }
+            }
+
+            Ok(Self {
+                path: key,
+                lib,
+                funcs,
+                finalize,
+            })
+        }
+    }
+
+    fn funcs(&self) -> &CK_FUNCTION_LIST {
+        unsafe { &*self.funcs }
+    }
+}
+
+impl Drop for Module {
+    fn drop(&mut self) {
+        // finalizes while holding the lock, so that "shared" never initializes
+        // the same library before the module is finalized
+        let mut modules = MODULES.lock().unwrap_or_else(PoisonError::into_inner);
+        if modules
+            .get(&self.path)
+            .map_or(false, |module| module.strong_count() == 0)
+        {
+            modules.remove(&self.path);
+        }
+
+        unsafe {
+            if self.finalize {
+                if let Some(finalize) = self.funcs().C_Finalize {
+                    finalize(ptr::null_mut());
+                }
+            }
+            libc::dlclose(self.lib);
+        }
+    }
+}
+
+fn dl_error() -> String {
+    unsafe {
+        let e = libc::dlerror();
+        if e.is_null() {
+            return String::from("unknown error");
+        }
+        CStr::from_ptr(e).to_string_lossy().into_owned()
+    }
+}
+
+/// Read-write session on a token, logged in as the user.
+/// PKCS#11 sessions must not be used by multiple threads at the same time,
+/// so the caller must serialize the calls.
+pub struct Session {
+    module: Arc<Module>,
+    handle: CK_SESSION_HANDLE,
+}
+
+impl Session {
+    /// Opens a session on the slot, and logs in with the user PIN.
+    pub fn open(module: Arc<Module>, slot_id: CK_SLOT_ID, pin: &str) -> Result<Self> {
+        let f = module.funcs();
+        let open_session = f.C_OpenSession.ok_or_else(|| missing("C_OpenSession"))?;
+        let login = f.C_Login.ok_or_else(|| missing("C_Login"))?;
+
+        let mut handle: CK_SESSION_HANDLE = 0;
+        check(
+            unsafe {
+                open_session(
+                    slot_id,
+                    CKF_SERIAL_SESSION | CKF_RW_SESSION,
+                    ptr::null_mut(),
+                    ptr::null_mut(),
+                    &mut handle,
+                )
+            },
+            "C_OpenSession",
+        )?;
+        let session = Self { module, handle };
+
+        let rv = unsafe { login(handle, CKU_USER, pin.as_ptr(), pin.len() as CK_ULONG) };
+        if rv != CKR_USER_ALREADY_LOGGED_IN {
+            check(rv, "C_Login")?;
+        }
+
+        Ok(session)
+    }
+
+    /// Returns the handles of the objects matching the template.
+    pub fn find_objects(&self, mut attrs: Vec<Attribute>) -> Result<Vec<CK_OBJECT_HANDLE>> {
+        let f = self.module.funcs();
+        let find_init = f
+            .C_FindObjectsInit
+            .ok_or_else(|| missing("C_FindObjectsInit"))?;
+        let find = f.C_FindObjects.ok_or_else(|| missing("C_FindObjects"))?;
+        let find_final = f
+            .C_FindObjectsFinal
+            .ok_or_else(|| missing("C_FindObjectsFinal"))?;
+
+        let mut template = to_template(&mut attrs);
+        check(
+            unsafe {
+                find_init(
+                    self.handle,
+                    template.as_mut_ptr(),
+                    template.len() as CK_ULONG,
+                )
+            },
+            "C_FindObjectsInit",
+        )?;
+
+        let mut found = Vec::new();
+        let mut res = Ok(());
+        loop {
+            let mut batch = [0 as CK_OBJECT_HANDLE; 16];
+            let mut n: CK_ULONG = 0;
+            let rv = unsafe {
+                find(
+                    self.handle,
+                    batch.as_mut_ptr(),
+                    batch.len() as CK_ULONG,
+                    &mut n,
+                )
+            };
+            if rv != CKR_OK {
+                res = check(rv, "C_FindObjects");
+                break;
+            }
+            if n == 0 {
+                break;
+            }
+            found.extend_from_slice(&batch[..n as usize]);
+        }
+
+        // always finalize the search, so the session can start another operation
+        check(unsafe { find_final(self.handle) }, "C_FindObjectsFinal")?;
+        res.map(|_| found)
+    }
+
+    /// Returns the value of the object attribute.
+    pub fn get_attribute(&self, obj: CK_OBJECT_HANDLE, type_: CK_ULONG) -> Result<Vec<u8>> {
+        let get_attr = self
+            .module
+            .funcs()
+            .C_GetAttributeValue
+            .ok_or_else(|| missing("C_GetAttributeValue"))?;
+
+        // first call with null value to get the length
+        let mut attr = CK_ATTRIBUTE {
+            type_,
+            pValue: ptr::null_mut(),
+            ulValueLen: 0,
+        };
+        check(
+            unsafe { get_attr(self.handle, obj, &mut attr, 1) },
+            "C_GetAttributeValue",
+        )?;
+
+        let mut value = vec![0u8; attr.ulValueLen as usize];
+        attr.pValue = value.as_mut_ptr() as *mut c_void;
+        check(
+            unsafe { get_attr(self.handle, obj, &mut attr, 1) },
+            "C_GetAttributeValue",
+        )?;
+        value.truncate(attr.ulValueLen as usize);
+
+        Ok(value)
+    }
+
+    /// Generates an EC key pair with the "CKM_EC_KEY_PAIR_GEN" mechanism.
+    /// Returns the public and private key handles.
+    pub fn generate_key_pair(
+        &self,
+        mut pub_attrs: Vec<Attribute>,
+        mut priv_attrs: Vec<Attribute>,
+    ) -> Result<(CK_OBJECT_HANDLE, CK_OBJECT_HANDLE)> {
+        let generate = self
+            .module
+            .funcs()
+            .C_GenerateKeyPair
+            .ok_or_else(|| missing("C_GenerateKeyPair"))?;
+
+        let mut mechanism = CK_MECHANISM {
+            mechanism: CKM_EC_KEY_PAIR_GEN,
+            pParameter: ptr::null_mut(),
+            ulParameterLen: 0,
+        };
+        let mut pub_template = to_template(&mut pub_attrs);
+        let mut priv_template = to_template(&mut priv_attrs);
+
+        let (mut pub_key, mut priv_key): (CK_OBJECT_HANDLE, CK_OBJECT_HANDLE) = (0, 0);
+        check(
+            unsafe {
+                generate(
+                    self.handle,
+                    &mut mechanism,
+                    pub_template.as_mut_ptr(),
+                    pub_template.len() as CK_ULONG,
+                    priv_template.as_mut_ptr(),
+                    priv_template.len() as CK_ULONG,
+                    &mut pub_key,
+                    &mut priv_key,
+                )
+            },
+            "C_GenerateKeyPair",
+        )?;
+
+        Ok((pub_key, priv_key))
+    }
+
+    /// Signs the data with the "CKM_ECDSA" mechanism, which signs the
+    /// pre-hashed data as is. Returns the signature in the module's
+    /// encoding (64-byte [R || S] for conforming modules).
+    pub fn sign_ecdsa(&self, key: CK_OBJECT_HANDLE, data: &[u8]) -> Result<Vec<u8>> {
+        let f = self.module.funcs();
+        let sign_init = f.C_SignInit.ok_or_else(|| missing("C_SignInit"))?;
+        let sign = f.C_Sign.ok_or_else(|| missing("C_Sign"))?;
+
+        let mut mechanism = CK_MECHANISM {
+            mechanism: CKM_ECDSA,
+            pParameter: ptr::null_mut(),
+            ulParameterLen: 0,
+        };
+        check(
+            unsafe { sign_init(self.handle, &mut mechanism, key) },
+            "C_SignInit",
+        )?;
+
+        // large enough for both [R || S] and DER-encoded signatures,
+        // so the single call terminates the signing operation
+        let mut sig = vec![0u8; 128];
+        let mut sig_len = sig.len() as CK_ULONG;
+        check(
+            unsafe {
+                sign(
+                    self.handle,
+                    data.as_ptr(),
+                    data.len() as CK_ULONG,
+                    sig.as_mut_ptr(),
+                    &mut sig_len,
+                )
+            },
+            "C_Sign",
+        )?;
+        sig.truncate(sig_len as usize);
+
+        Ok(sig)
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        if let Some(close) = self.module.funcs().C_CloseSession {
+            unsafe {
+                close(self.handle);
+            }
+        }
+    }
+}
@@ -0,0 +1,433 @@
+pub mod eth_signer;
+mod ffi;
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    errors::{Error, Result},
+    hash,
+    ids::short,
+    key,
+};
+use async_trait::async_trait;
+use ethers_core::types::Signature as EthSig;
+
+/// Represents a secp256k1 key pair in a PKCS#11 token (e.g., HSM, SoftHSM).
+/// Note that the actual private key never leaves the token.
+/// Private key signing operation must be done via the "CKM_ECDSA" mechanism.
+/// ref. <https://docs.oasis-open.org/pkcs11/pkcs11-curr/v2.40/os/pkcs11-curr-v2.40-os.html>
+#[derive(Clone)]
+pub struct Key {
+    /// Path to the PKCS#11 module (e.g., "/usr/lib/softhsm/libsofthsm2.so").
+    pub module_path: String,
+    /// Slot Id of the token.
+    pub slot_id: u64,
+    /// "CKA_LABEL" of the key pair.
+    pub label: String,
+
+    /// Public key.
+    pub public_key: key::secp256k1::public_key::Key,
+
+    session: Arc<Mutex<ffi::Session>>,
+    private_key: ffi::CK_OBJECT_HANDLE,
+}
+
+impl fmt::Debug for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Key")
+            .field("module_path", &self.module_path)
+            .field("slot_id", &self.slot_id)
+            .field("label", &self.label)
+            .field("public_key", &self.public_key)
+            .finish()
+    }
+}
+
+impl Key {
+    /// Generates a new key pair in the token with the label.
+    /// The private key is sensitive and non-extractable.
+    pub async fn create(module_path: &str, slot_id: u64, pin: &str, label: &str) -> Result<Self> {
+        let (module_path, pin, label) =
+            (module_path.to_string(), pin.to_string(), label.to_string());
+        spawn_blocking(move || {
+            let session = open_session(&module_path, slot_id, &pin)?;
+
+            let pub_attrs = vec![
+                ffi::Attribute::bool(ffi::CKA_TOKEN, true),
+                ffi::Attribute::bool(ffi::CKA_VERIFY, true),
+                ffi::Attribute::bytes(ffi::CKA_EC_PARAMS, &ffi::SECP256K1_EC_PARAMS),
+                ffi::Attribute::bytes(ffi::CKA_LABEL, label.as_bytes()),
+            ];
+            let priv_attrs = vec![
+                ffi::Attribute::bool(ffi::CKA_TOKEN, true),
+                ffi::Attribute::bool(ffi::CKA_PRIVATE, true),
+                ffi::Attribute::bool(ffi::CKA_SENSITIVE, true),
+                ffi::Attribute::bool(ffi::CKA_EXTRACTABLE, false),
+                ffi::Attribute::bool(ffi::CKA_SIGN, true),
+                ffi::Attribute::bytes(ffi::CKA_LABEL, label.as_bytes()),
+            ];
+            let (pub_handle, priv_handle) = session.generate_key_pair(pub_attrs, priv_attrs)?;
+            log::info!("generated key pair '{label}' in slot {slot_id}");
+
+            let public_key =
+                parse_ec_point(&session.get_attribute(pub_handle, ffi::CKA_EC_POINT)?)?;
+            Ok(Self {
+                module_path,
+                slot_id,
+                label,
+                public_key,
+                session: Arc::new(Mutex::new(session)),
+                private_key: priv_handle,
+            })
+        })
+        .await
+    }
+
+    /// Loads the existing key pair from the token by its label.
+    pub async fn from_label(
+        module_path: &str,
+        slot_id: u64,
+        pin: &str,
+        label: &str,
+    ) -> Result<Self> {
+        let (module_path, pin, label) =
+            (module_path.to_string(), pin.to_string(), label.to_string());
+        spawn_blocking(move || {
+            let session = open_session(&module_path, slot_id, &pin)?;
+
+            let priv_handle = find_one(&session, ffi::CKO_PRIVATE_KEY, &label)?;
+            let pub_handle = find_one(&session, ffi::CKO_PUBLIC_KEY, &label)?;
+            let public_key =
+                parse_ec_point(&session.get_attribute(pub_handle, ffi::CKA_EC_POINT)?)?;
+            log::info!(
+                "loaded key pair '{label}' with ETH address '{}'",
+                public_key.to_eth_address()
+            );
+
+            Ok(Self {
+                module_path,
+                slot_id,
+                label,
+                public_key,
+                session: Arc::new(Mutex::new(session)),
+                private_key: priv_handle,
+            })
+        })
+        .await
+    }
+
+    pub fn to_public_key(&self) -> key::secp256k1::public_key::Key {
+        self.public_key
+    }
+
+    /// Converts to Info.
+    pub fn to_info(&self, network_id: u32) -> Result<key::secp256k1::Info> {
+        let short_addr = self.public_key.to_short_id()?;
+        let eth_addr = self.public_key.to_eth_address();
+        let h160_addr = self.public_key.to_h160();
+
+        let mut addresses = HashMap::new();
+        addresses.insert(
+            network_id,
+            key::secp256k1::ChainAddresses {
+                x: self.public_key.to_hrp_address(network_id, "X")?,
+                p: self.public_key.to_hrp_address(network_id, "P")?,
+            },
+        );
+
+        Ok(key::secp256k1::Info {
+            id: Some(format!(
+                "pkcs11:slot-id={};object={}",
+                self.slot_id, self.label
+            )),
+            key_type: key::secp256k1::KeyType::Pkcs11,
+
+            addresses,
+
+            short_address: short_addr,
+            eth_address: eth_addr,
+            h160_address: h160_addr,
+
+            ..Default::default()
+        })
+    }
+
+    pub async fn sign_digest(&self, digest: &[u8]) -> Result<EthSig> {
+        // ref. "crypto/sha256.Size"
+        if digest.len() != hash::SHA256_OUTPUT_LEN {
+            return Err(Error::Other {
+                message: format!(
+                    "invalid digest length {} (expected {})",
+                    digest.len(),
+                    hash::SHA256_OUTPUT_LEN
+                ),
+                retryable: false,
+            });
+        }
+        let mut fixed_digest = [0u8; hash::SHA256_OUTPUT_LEN];
+        fixed_digest.copy_from_slice(digest);
+
+        let (session, private_key) = (self.session.clone(), self.private_key);
+        let raw = spawn_blocking(move || {
+            // PKCS#11 sessions do not allow concurrent operations
+            let session = session.lock().map_err(|e| Error::Other {
+                message: format!("failed to lock session {}", e),
+                retryable: false,
+            })?;
+            session.sign_ecdsa(private_key, &fixed_digest)
+        })
+        .await?;
+
+        let sig = decode_signature(&raw)?;
+        key::secp256k1::signature::sig_from_digest_bytes_trial_recovery(
+            &sig,
+            &fixed_digest,
+            &self.public_key.to_verifying_key(),
+        )
+        .map_err(|e| Error::Other {
+            message: format!(
+                "failed key::secp256k1::signature::sig_from_digest_bytes_trial_recovery {}",
+                e
+            ),
+            retryable: false,
+        })
+    }
+}
+
+fn open_session(module_path: &str, slot_id: u64, pin: &str) -> Result<ffi::Session> {
+    let module = ffi::Module::shared(module_path)?;
+    ffi::Session::open(module, slot_id as ffi::CK_SLOT_ID, pin)
+}
+
+/// Finds the only object of the class with the label.
+fn find_one(
+    session: &ffi::Session,
+    class: ffi::CK_ULONG,
+    label: &str,
+) -> Result<ffi::CK_OBJECT_HANDLE> {
+    let found = session.find_objects(vec![
+        ffi::Attribute::ulong(ffi::CKA_CLASS, class),
+        ffi::Attribute::ulong(ffi::CKA_KEY_TYPE, ffi::CKK_EC),
+        ffi::Attribute::bytes(ffi::CKA_LABEL, label.as_bytes()),
+    ])?;
+    match found.as_slice() {
+        [handle] => Ok(*handle),
+        [] => Err(Error::Other {
+            message: format!("key '{}' (class {}) not found", label, class),
+            retryable: false,
+        }),
+        _ => Err(Error::Other {
+            message: format!(
+                "found {} keys with label '{}' (class {})",
+                found.len(),
+                label,
+                class
+            ),
+            retryable: false,
+        }),
+    }
+}
+
+async fn spawn_blocking<T, F>(f: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| Error::Other {
+            message: format!("failed spawn_blocking {}", e),
+            retryable: false,
+        })?
+}
+
+/// Loads the public key from the "CKA_EC_POINT" attribute, which is the
+/// DER-encoded OCTET STRING of the SEC1 point. Some modules return the
+/// raw SEC1 point, so both forms are accepted.
+pub fn parse_ec_point(b: &[u8]) -> Result<key::secp256k1::public_key::Key> {
+    let point = match b {
+        // raw uncompressed or compressed point
+        [0x04, ..] if b.len() == 65 => b,
+        [0x02 | 0x03, ..] if b.len() == 33 => b,
+        // DER OCTET STRING with short-form length
+        [0x04, len, rest @ ..] if *len as usize == rest.len() => rest,
+        _ => {
+            return Err(Error::Other {
+                message: format!("invalid CKA_EC_POINT ({} bytes)", b.len()),
+                retryable: false,
+            })
+        }
+    };
+    key::secp256k1::public_key::Key::from_sec1_bytes(point)
+}
+
+/// Loads the ECDSA signature from the "C_Sign" output, which is 64-byte
+/// [R || S] for conforming modules, or DER-encoded for some.
+/// The "s" value is normalized to the lower half of the curve (EIP-2),
+/// same as the DER signatures from AWS KMS.
+pub fn decode_signature(b: &[u8]) -> Result<k256::ecdsa::Signature> {
+    if b.len() != 64 {
+        return key::secp256k1::signature::decode_signature(b);
+    }
+    let sig = k256::ecdsa::Signature::from_slice(b).map_err(|e| Error::Other {
+        message: format!("failed Signature::from_slice {}", e),
+        retryable: false,
+    })?;
+    Ok(sig.normalize_s().unwrap_or(sig))
+}
+
+#[async_trait]
+impl key::secp256k1::SignOnly for Key {
+    fn signing_key(&self) -> Result<k256::ecdsa::SigningKey> {
+        Err(Error::Other {
+            message: "signing key not available for PKCS#11 keys".to_string(),
+            retryable: false,
+        })
+    }
+
+    async fn sign_digest(&self, msg: &[u8]) -> Result<[u8; 65]> {
+        let sig = self.sign_digest(msg).await?;
+
+        let mut b = [0u8; key::secp256k1::signature::LEN];
+        b.copy_from_slice(&sig.to_vec());
+
+        Ok(b)
+    }
+}
+
+/// ref. <https://doc.rust-lang.org/book/ch10-02-traits.html>
+impl key::secp256k1::ReadOnly for Key {
+    fn key_type(&self) -> key::secp256k1::KeyType {
+        key::secp256k1::KeyType::Pkcs11
+    }
+
+    fn hrp_address(&self, network_id: u32, chain_id_alias: &str) -> Result<String> {
+        self.to_public_key()
+            .to_hrp_address(network_id, chain_id_alias)
+    }
+
+    fn short_address(&self) -> Result<short::Id> {
+        self.to_public_key().to_short_id()
+    }
+
+    fn short_address_bytes(&self) -> Result<Vec<u8>> {
+        self.to_public_key().to_short_bytes()
+    }
+
+    fn eth_address(&self) -> String {
+        self.to_public_key().to_eth_address()
+    }
+
+    fn h160_address(&self) -> primitive_types::H160 {
+        self.to_public_key().to_h160()
+    }
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features kms_pkcs11 -- key::secp256k1::kms::pkcs11::test_parse_ec_point --exact --show-output
+#[test]
+fn test_parse_ec_point() {
+    let pk = key::secp256k1::private_key::Key::generate().unwrap();
+    let pubkey = pk.to_public_key();
+
+    let uncompressed = pubkey.to_uncompressed_bytes();
+    let compressed = pubkey.to_compressed_bytes();
+    assert_eq!(parse_ec_point(&uncompressed).unwrap(), pubkey);
+    assert_eq!(parse_ec_point(&compressed).unwrap(), pubkey);
+
+    let der = [&[0x04, 0x41][..], &uncompressed[..]].concat();
+    assert_eq!(parse_ec_point(&der).unwrap(), pubkey);
+    let der = [&[0x04, 0x21][..], &compressed[..]].concat();
+    assert_eq!(parse_ec_point(&der).unwrap(), pubkey);
+
+    assert!(parse_ec_point(&uncompressed[..64]).is_err());
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features kms_pkcs11 -- key::secp256k1::kms::pkcs11::test_decode_signature --exact --show-output
+#[test]
+fn test_decode_signature() {
+    use k256::elliptic_curve::scalar::IsHigh;
+
+    let pk = key::secp256k1::private_key::Key::generate().unwrap();
+    let pubkey = pk.to_public_key();
+    let digest = hash::sha256(b"hello world");
+
+    let sig = pk.sign_digest(&digest).unwrap();
+    let (r, s) = sig.0 .0.split_scalars();
+
+    // high "s" as returned by some modules, must be normalized
+    let high = k256::ecdsa::Signature::from_scalars(r, -s).unwrap();
+    assert!(bool::from(high.s().is_high()));
+
+    let mut fixed_digest = [0u8; hash::SHA256_OUTPUT_LEN];
+    fixed_digest.copy_from_slice(&digest);
+    for raw in [
+        sig.0 .0.to_vec(),
+        high.to_vec(),
+        sig.0 .0.to_der().as_bytes().to_vec(),
+        high.to_der().as_bytes().to_vec(),
+    ] {
+        let decoded = decode_signature(&raw).unwrap();
+        assert!(!bool::from(decoded.s().is_high()));
+
+        let eth_sig = key::secp256k1::signature::sig_from_digest_bytes_trial_recovery(
+            &decoded,
+            &fixed_digest,
+            &pubkey.to_verifying_key(),
+        )
+        .unwrap();
+        assert!(pubkey.verify(&digest, &eth_sig.to_vec()).unwrap());
+    }
+}
+
+/// Requires a SoftHSM token, so is ignored by default (run in CI by "scripts/tests.softhsm.sh"):
+/// softhsm2-util --init-token --free --label test --so-pin 1234 --pin 1234
+/// PKCS11_MODULE=/usr/lib/softhsm/libsofthsm2.so PKCS11_SLOT_ID=[SLOT_ID] PKCS11_PIN=1234 RUST_LOG=debug cargo test --package lux-types --lib --features kms_pkcs11 -- key::secp256k1::kms::pkcs11::test_softhsm --exact --show-output --ignored
+#[tokio::test]
+#[ignore]
+async fn test_softhsm() {
+    use ethers_signers::Signer as _;
+
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .is_test(true)
+        .try_init();
+
+    let module_path = std::env::var("PKCS11_MODULE").expect("no PKCS11_MODULE");
+    let slot_id: u64 = std::env::var("PKCS11_SLOT_ID")
+        .expect("no PKCS11_SLOT_ID")
+        .parse()
+        .expect("invalid PKCS11_SLOT_ID");
+    let pin = std::env::var("PKCS11_PIN").expect("no PKCS11_PIN");
+
+    let label = id_manager::time::with_prefix("test");
+    let key = Key::create(&module_path, slot_id, &pin, &label)
+        .await
+        .unwrap();
+    let key2 = Key::from_label(&module_path, slot_id, &pin, &label)
+        .await
+        .unwrap();
+    assert_eq!(key.to_public_key(), key2.to_public_key());
+
+    // the keys share the module, so dropping one never finalizes the other
+    drop(key2);
+    let digest = hash::sha256(b"hello world");
+    let sig = key.sign_digest(&digest).await.unwrap();
+    assert!(key.to_public_key().verify(&digest, &sig.to_vec()).unwrap());
+
+    let signer = eth_signer::Signer::new(key.clone(), primitive_types::U256::from(1)).unwrap();
+    let sig = signer.sign_message(b"hello world").await.unwrap();
+    sig.verify(&b"hello world"[..], signer.address()).unwrap();
+
+    // reloads the module once every key is dropped
+    drop(signer);
+    drop(key);
+    let key = Key::from_label(&module_path, slot_id, &pin, &label)
+        .await
+        .unwrap();
+    let sig = key.sign_digest(&digest).await.unwrap();
+    assert!(key.to_public_key().verify(&digest, &sig.to_vec()).unwrap());
+}
@@ -1,7 +1,15 @@
-use crate::errors::{Error, Result};
-use bip32::{DerivationPath, Language, Mnemonic, XPrv};
+use std::{fmt, future::Future};
+
+use crate::{
+    errors::{Error, Result},
+    key::secp256k1::{private_key, public_key},
+};
+use bip32::{ChildNumber, DerivationPath, Prefix, XPrv, XPub};
+use bip39::Mnemonic;
 use rand_core::OsRng;
 
+pub use bip39::Language;
+
 /// ref. <https://github.com/luxfi/lux-js-cli-tools/blob/3e3f714e4227aca83dc3978fcb6a4fd698e09065/address_gen.js>
 pub const LUX_ACCOUNT_DERIV_PATH: &str = "m/44'/9000'/0'";
 pub const LUX_ACCOUNT_DERIV_PATH_0: &str = "m/44'/9000'/0'/0/0";
@@ -10,37 +18,81 @@ pub const LUX_ACCOUNT_DERIV_PATH_0: &str = "m/44'/9000'/0'/0/0";
 pub const LUX_ACCOUNT_EXT_PUB_KEY_DERIV_PATH: &str = "m/44'/9000'/0'";
 pub const ETH_ACCOUNT_EXT_PUB_KEY_DERIV_PATH: &str = "m/44'/60'/0'/0/0";
 
-/// Only supports "English" for now.
+/// Account-level path for the EVM chains, whose first external key is
+/// "ETH_ACCOUNT_EXT_PUB_KEY_DERIV_PATH".
+pub const ETH_ACCOUNT_DERIV_PATH: &str = "m/44'/60'/0'";
+
+/// Number of consecutive unused addresses after which the scan stops.
+/// ref. <https://github.com/bitcoin/bips/blob/master/bip-0044.mediawiki#address-gap-limit>
+pub const DEFAULT_GAP_LIMIT: u32 = 20;
+
+/// Legacy seed passphrase used by "Key::from_mnemonic_phrase".
+pub const LEGACY_PASSPHRASE: &str = "password";
+
+/// Generates a 24-word English mnemonic phrase.
 /// ref. <https://github.com/bitcoin/bips/blob/master/bip-0032.mediawiki>
 /// ref. <https://github.com/rust-bitcoin/rust-bitcoin/blob/master/src/util/bip32.rs>
 /// ref. <https://github.com/bitcoin/bips/blob/master/bip-0039/bip-0039-wordlists.md>
 /// ref. <https://iancoleman.io/bip39/>
 pub fn gen_24() -> String {
-    let m = Mnemonic::random(&mut OsRng, Language::English);
-    let s = m.phrase();
+    let s = gen(Language::English, 24).unwrap();
     assert_eq!(s.split(' ').count(), 24);
-    String::from(s)
+    s
+}
+
+/// Generates a mnemonic phrase in the language, with 12, 15, 18, 21, or 24 words.
+pub fn gen(language: Language, word_count: usize) -> Result<String> {
+    let m =
+        Mnemonic::generate_in_with(&mut OsRng, language, word_count).map_err(|e| Error::Other {
+            message: format!("failed to generate mnemonic phrase ({})", e),
+            retryable: false,
+        })?;
+    Ok(m.to_string())
+}
+
+/// Converts the mnemonic phrase to the 64-byte BIP39 seed.
+/// The wordlist language is detected from the phrase.
+pub fn to_seed<S>(phrase: S, passphrase: &str) -> Result<[u8; 64]>
+where
+    S: AsRef<str>,
+{
+    let mnemonic = Mnemonic::parse(phrase.as_ref()).map_err(|e| Error::Other {
+        message: format!("failed to read mnemonic phrase ({})", e),
+        retryable: false,
+    })?;
+    Ok(mnemonic.to_seed(passphrase))
 }
 
 impl crate::key::secp256k1::private_key::Key {
-    /// Loads the private key from the mnemonic phrase.
+    /// Loads the private key from the mnemonic phrase,
+    /// with the "LEGACY_PASSPHRASE" seed passphrase.
+    /// Use "from_mnemonic_phrase_with_passphrase" to load the keys
+    /// of other wallets (e.g., an empty passphrase for lux-wallet).
     pub fn from_mnemonic_phrase<S>(phrase: S, derive_path: S) -> Result<Self>
     where
         S: AsRef<str>,
     {
-        let deriv: DerivationPath = derive_path.as_ref().parse().map_err(|e| Error::Other {
-            message: format!("failed to parse derive path ({})", e),
-            retryable: false,
-        })?;
+        Self::from_mnemonic_phrase_with_passphrase(
+            phrase.as_ref(),
+            LEGACY_PASSPHRASE,
+            derive_path.as_ref(),
+        )
+    }
 
-        let mnemonic = Mnemonic::new(phrase, Language::English).map_err(|e| Error::Other {
-            message: format!("failed to read mnemonic phrase ({})", e),
-            retryable: false,
-        })?;
-        let seed = mnemonic.to_seed("password");
+    /// Loads the private key from the mnemonic phrase and the seed passphrase.
+    pub fn from_mnemonic_phrase_with_passphrase<S>(
+        phrase: S,
+        passphrase: &str,
+        derive_path: S,
+    ) -> Result<Self>
+    where
+        S: AsRef<str>,
+    {
+        let deriv = parse_path(derive_path.as_ref())?;
+        let seed = to_seed(phrase, passphrase)?;
 
         // ref. https://github.com/luxfi/lux-wallet/blob/v0.3.8/src/js/wallets/MnemonicWallet.ts
-        let child_xprv = XPrv::derive_from_path(&seed, &deriv).map_err(|e| Error::Other {
+        let child_xprv = XPrv::derive_from_path(seed, &deriv).map_err(|e| Error::Other {
             message: format!("failed to derive LUX account path ({})", e),
             retryable: false,
         })?;
@@ -49,3 +101,269 @@ impl crate::key::secp256k1::private_key::Key {
         Self::from_bytes(&pk)
     }
 }
+
+/// BIP44 HD account (e.g., "m/44'/9000'/0'"), whose addresses are
+/// the external chain keys at "{account path}/0/{index}".
+/// An account loaded from the extended public key is read-only,
+/// and can only enumerate the public keys.
+/// ref. <https://github.com/bitcoin/bips/blob/master/bip-0044.mediawiki>
+/// ref. <https://github.com/luxfi/lux-wallet/blob/v0.3.8/src/js/wallets/MnemonicWallet.ts>
+#[derive(Clone)]
+pub struct Account {
+    xprv: Option<XPrv>,
+    xpub: XPub,
+}
+
+impl fmt::Debug for Account {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Account")
+            .field("xpub", &self.to_xpub())
+            .field("read_only", &self.is_read_only())
+            .finish()
+    }
+}
+
+impl Account {
+    /// Loads the account at the path (e.g., "LUX_ACCOUNT_DERIV_PATH")
+    /// from the mnemonic phrase in any BIP39 language, and the seed passphrase.
+    pub fn from_mnemonic_phrase<S>(phrase: S, passphrase: &str, account_path: &str) -> Result<Self>
+    where
+        S: AsRef<str>,
+    {
+        let seed = to_seed(phrase, passphrase)?;
+        Self::from_seed(&seed, account_path)
+    }
+
+    /// Loads the account at the path from the BIP39 seed.
+    pub fn from_seed(seed: &[u8], account_path: &str) -> Result<Self> {
+        let deriv = parse_path(account_path)?;
+        let xprv = XPrv::derive_from_path(seed, &deriv).map_err(|e| Error::Other {
+            message: format!("failed to derive account path ({})", e),
+            retryable: false,
+        })?;
+        Ok(Self {
+            xpub: xprv.public_key(),
+            xprv: Some(xprv),
+        })
+    }
+
+    /// Loads the account from the extended private key (e.g., "xprv...").
+    pub fn from_xprv(s: &str) -> Result<Self> {
+        let xprv: XPrv = s.parse().map_err(|e| Error::Other {
+            message: format!("failed to parse xprv ({})", e),
+            retryable: false,
+        })?;
+        Ok(Self {
+            xpub: xprv.public_key(),
+            xprv: Some(xprv),
+        })
+    }
+
+    /// Loads the read-only account from the extended public key (e.g., "xpub...").
+    pub fn from_xpub(s: &str) -> Result<Self> {
+        let xpub: XPub = s.parse().map_err(|e| Error::Other {
+            message: format!("failed to parse xpub ({})", e),
+            retryable: false,
+        })?;
+        Ok(Self { xprv: None, xpub })
+    }
+
+    /// Returns true if the account was loaded from the extended public key.
+    pub fn is_read_only(&self) -> bool {
+        self.xprv.is_none()
+    }
+
+    /// Exports the extended private key of the account.
+    pub fn to_xprv(&self) -> Result<String> {
+        let xprv = self.xprv()?;
+        Ok(xprv.to_string(Prefix::XPRV).to_string())
+    }
+
+    /// Exports the extended public key of the account.
+    pub fn to_xpub(&self) -> String {
+        self.xpub.to_string(Prefix::XPUB)
+    }
+
+    /// Derives the private key at "{account path}/0/{index}".
+    pub fn derive_private_key(&self, index: u32) -> Result<private_key::Key> {
+        let xprv = self.xprv()?;
+        let (external, index_cn) = (child_number(0)?, child_number(index)?);
+        let child = xprv
+            .derive_child(external)
+            .and_then(|x| x.derive_child(index_cn))
+            .map_err(|e| Error::Other {
+                message: format!("failed to derive private key {} ({})", index, e),
+                retryable: false,
+            })?;
+        private_key::Key::from_bytes(&child.private_key().to_bytes())
+    }
+
+    /// Derives the public key at "{account path}/0/{index}".
+    pub fn derive_public_key(&self, index: u32) -> Result<public_key::Key> {
+        let (external, index_cn) = (child_number(0)?, child_number(index)?);
+        let child = self
+            .xpub
+            .derive_child(external)
+            .and_then(|x| x.derive_child(index_cn))
+            .map_err(|e| Error::Other {
+                message: format!("failed to derive public key {} ({})", index, e),
+                retryable: false,
+            })?;
+        public_key::Key::from_sec1_bytes(&child.to_bytes())
+    }
+
+    /// Derives "count" public keys starting at "start".
+    pub fn derive_public_keys(&self, start: u32, count: u32) -> Result<Vec<public_key::Key>> {
+        let mut keys = Vec::with_capacity(count as usize);
+        for index in start..start.saturating_add(count) {
+            keys.push(self.derive_public_key(index)?);
+        }
+        Ok(keys)
+    }
+
+    /// Enumerates the addresses in order, and returns the ones that "is_used"
+    /// reports as used (e.g., funded or with transaction history) with their indices.
+    /// Stops after "gap_limit" consecutive unused addresses (see "DEFAULT_GAP_LIMIT").
+    /// Works on read-only accounts.
+    pub async fn scan<F, Fut>(
+        &self,
+        gap_limit: u32,
+        mut is_used: F,
+    ) -> Result<Vec<(u32, public_key::Key)>>
+    where
+        F: FnMut(u32, public_key::Key) -> Fut,
+        Fut: Future<Output = Result<bool>>,
+    {
+        let mut used = Vec::new();
+        let (mut index, mut gap) = (0_u32, 0_u32);
+        while gap < gap_limit {
+            let key = self.derive_public_key(index)?;
+            if is_used(index, key).await? {
+                used.push((index, key));
+                gap = 0;
+            } else {
+                gap += 1;
+            }
+            index = index.checked_add(1).ok_or_else(|| Error::Other {
+                message: String::from("ran out of non-hardened indices"),
+                retryable: false,
+            })?;
+        }
+        Ok(used)
+    }
+
+    fn xprv(&self) -> Result<&XPrv> {
+        self.xprv.as_ref().ok_or_else(|| Error::Other {
+            message: String::from("read-only account has no private key"),
+            retryable: false,
+        })
+    }
+}
+
+fn parse_path(p: &str) -> Result<DerivationPath> {
+    p.parse().map_err(|e| Error::Other {
+        message: format!("failed to parse derive path ({})", e),
+        retryable: false,
+    })
+}
+
+/// Returns the non-hardened child number.
+fn child_number(index: u32) -> Result<ChildNumber> {
+    ChildNumber::new(index, false).map_err(|e| Error::Other {
+        message: format!("invalid child index {} ({})", index, e),
+        retryable: false,
+    })
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib -- key::secp256k1::mnemonic::test_account --exact --show-output
+#[test]
+fn test_account() {
+    // ref. <https://iancoleman.io/bip39/>
+    let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    let eth = Account::from_mnemonic_phrase(phrase, "", ETH_ACCOUNT_DERIV_PATH).unwrap();
+    assert_eq!(
+        eth.derive_public_key(0).unwrap().to_eth_address(),
+        "0x9858EfFD232B4033E47d90003D41EC34EcaEda94"
+    );
+
+    let pk = private_key::Key::from_mnemonic_phrase_with_passphrase(
+        phrase,
+        "",
+        ETH_ACCOUNT_EXT_PUB_KEY_DERIV_PATH,
+    )
+    .unwrap();
+    assert_eq!(pk, eth.derive_private_key(0).unwrap());
+
+    // the passphrase changes the seed
+    let lux = Account::from_mnemonic_phrase(phrase, "", LUX_ACCOUNT_DERIV_PATH).unwrap();
+    let lux_pw = Account::from_mnemonic_phrase(phrase, "password", LUX_ACCOUNT_DERIV_PATH).unwrap();
+    assert_ne!(lux.to_xpub(), lux_pw.to_xpub());
+    let pk = private_key::Key::from_mnemonic_phrase(phrase, LUX_ACCOUNT_DERIV_PATH_0).unwrap();
+    assert_eq!(pk, lux_pw.derive_private_key(0).unwrap());
+
+    // xprv round trip
+    let imported = Account::from_xprv(&lux.to_xprv().unwrap()).unwrap();
+    assert!(!imported.is_read_only());
+    assert_eq!(imported.to_xpub(), lux.to_xpub());
+    assert_eq!(
+        imported.derive_private_key(3).unwrap(),
+        lux.derive_private_key(3).unwrap()
+    );
+
+    // xpub is read-only, but derives the same public keys
+    let watch = Account::from_xpub(&lux.to_xpub()).unwrap();
+    assert!(watch.is_read_only());
+    assert!(watch.to_xprv().is_err());
+    assert!(watch.derive_private_key(0).is_err());
+    let keys = watch.derive_public_keys(0, 5).unwrap();
+    assert_eq!(keys.len(), 5);
+    for (i, key) in keys.iter().enumerate() {
+        assert_eq!(
+            key,
+            &lux.derive_private_key(i as u32).unwrap().to_public_key()
+        );
+    }
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib -- key::secp256k1::mnemonic::test_languages --exact --show-output
+#[test]
+fn test_languages() {
+    for language in Language::all() {
+        let phrase = gen(*language, 12).unwrap();
+        let acct = Account::from_mnemonic_phrase(&phrase, "", LUX_ACCOUNT_DERIV_PATH).unwrap();
+        acct.derive_private_key(0).unwrap();
+    }
+
+    assert!(gen(Language::English, 13).is_err());
+    assert!(Account::from_mnemonic_phrase("abandon about", "", LUX_ACCOUNT_DERIV_PATH).is_err());
+    assert_eq!(gen_24().split(' ').count(), 24);
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib -- key::secp256k1::mnemonic::test_scan --exact --show-output
+#[test]
+fn test_scan() {
+    let phrase = gen_24();
+    let acct = Account::from_mnemonic_phrase(&phrase, "", LUX_ACCOUNT_DERIV_PATH).unwrap();
+    let watch = Account::from_xpub(&acct.to_xpub()).unwrap();
+
+    let funded = [0_u32, 3, 23, 44];
+    let used = tokio_test::block_on(watch.scan(DEFAULT_GAP_LIMIT, |index, _| async move {
+        Ok(funded.contains(&index))
+    }))
+    .unwrap();
+
+    // 44 is past the gap of 20 unused addresses after 23
+    let indices: Vec<u32> = used.iter().map(|(i, _)| *i).collect();
+    assert_eq!(indices, vec![0, 3, 23]);
+    assert_eq!(used[2].1, acct.derive_public_key(23).unwrap());
+
+    let err = tokio_test::block_on(watch.scan(DEFAULT_GAP_LIMIT, |_, _| async move {
+        Err(Error::API {
+            message: String::from("unreachable"),
+            retryable: true,
+        })
+    }))
+    .unwrap_err();
+    assert!(err.retryable());
+}
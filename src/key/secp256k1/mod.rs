@@ -249,6 +249,12 @@ pub enum KeyType {
     Hot,
     #[serde(rename = "aws-kms")]
     AwsKms,
+    /// Signs via the remote signer service (see "kms::grpc").
+    #[serde(rename = "remote")]
+    Remote,
+    /// Signs via the PKCS#11 hardware security module (see "kms::pkcs11").
+    #[serde(rename = "pkcs11")]
+    Pkcs11,
     Unknown(String),
 }
 
@@ -258,6 +264,8 @@ impl std::convert::From<&str> for KeyType {
             "hot" => KeyType::Hot,
             "aws-kms" => KeyType::AwsKms,
             "aws_kms" => KeyType::AwsKms,
+            "remote" => KeyType::Remote,
+            "pkcs11" => KeyType::Pkcs11,
 
             other => KeyType::Unknown(other.to_owned()),
         }
@@ -287,6 +295,8 @@ impl KeyType {
         match self {
             KeyType::Hot => "hot",
             KeyType::AwsKms => "aws-kms",
+            KeyType::Remote => "remote",
+            KeyType::Pkcs11 => "pkcs11",
 
             KeyType::Unknown(s) => s.as_ref(),
         }
@@ -297,6 +307,8 @@ impl KeyType {
         &[
             "hot",     //
             "aws-kms", //
+            "remote",  //
+            "pkcs11",  //
         ]
     }
 }
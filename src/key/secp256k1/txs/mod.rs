@@ -4,7 +4,9 @@ use std::cmp::Ordering;
 
 use crate::{
     codec::{self, serde::hex_0x_bytes::Hex0xBytes},
+    errors::{Error, Result},
     ids::short,
+    key,
 };
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
@@ -45,6 +47,90 @@ impl Credential {
     pub fn type_id() -> u32 {
         *(codec::X_TYPES.get(&Self::type_name()).unwrap()) as u32
     }
+
+    /// Recovers the signer addresses from the recoverable signatures
+    /// over the hash of the unsigned tx bytes, in the order of signatures.
+    /// ref. "fx.SECPFactory.RecoverHashPublicKey"
+    pub fn recover_addresses(&self, tx_hash: &[u8]) -> Result<Vec<short::Id>> {
+        let mut addrs = Vec::with_capacity(self.signatures.len());
+        for sig in self.signatures.iter() {
+            let pubkey = key::secp256k1::public_key::Key::from_signature(tx_hash, sig)?;
+            addrs.push(pubkey.to_short_id()?);
+        }
+        Ok(addrs)
+    }
+
+    /// Verifies that the credential authorizes the spend of the output
+    /// owned by "owners", where the "i"-th signature must be signed by the
+    /// owner address at "sig_indices[i]". Returns the recovered signers.
+    /// "now" is the unix timestamp in seconds, to check the locktime.
+    /// ref. "vms/secp256k1fx.Fx.VerifyCredentials"
+    pub fn verify_spend(
+        &self,
+        tx_hash: &[u8],
+        sig_indices: &[u32],
+        owners: &OutputOwners,
+        now: u64,
+    ) -> Result<Vec<short::Id>> {
+        let num_sigs = sig_indices.len();
+        let message = if owners.locktime > now {
+            Some(format!(
+                "output is locked until {} (now {})", // ref. "errTimelocked"
+                owners.locktime, now
+            ))
+        } else if (owners.threshold as usize) < num_sigs {
+            Some(format!(
+                "too many signers {} (threshold {})", // ref. "errTooManySigners"
+                num_sigs, owners.threshold
+            ))
+        } else if (owners.threshold as usize) > num_sigs {
+            Some(format!(
+                "too few signers {} (threshold {})", // ref. "errTooFewSigners"
+                num_sigs, owners.threshold
+            ))
+        } else if num_sigs != self.signatures.len() {
+            Some(format!(
+                "{} signature indices but {} signatures", // ref. "errInputCredentialSignersMismatch"
+                num_sigs,
+                self.signatures.len()
+            ))
+        } else if !cmp_manager::is_sorted_and_unique(sig_indices) {
+            Some("signatures not sorted and unique".to_string()) // ref. "errNotSortedUnique"
+        } else {
+            None
+        };
+        if let Some(message) = message {
+            return Err(Error::Other {
+                message,
+                retryable: false,
+            });
+        }
+
+        let signers = self.recover_addresses(tx_hash)?;
+        for (signer, idx) in signers.iter().zip(sig_indices.iter()) {
+            let expected = owners
+                .addresses
+                .get(*idx as usize)
+                .ok_or_else(|| Error::Other {
+                    message: format!(
+                        "signature index {} out of bounds (addresses {})", // ref. "errInputOutputIndexOutOfBounds"
+                        idx,
+                        owners.addresses.len()
+                    ),
+                    retryable: false,
+                })?;
+            if signer != expected {
+                return Err(Error::Other {
+                    message: format!(
+                        "wrong signature at index {} (expected {}, recovered {})", // ref. "errWrongSig"
+                        idx, expected, signer
+                    ),
+                    retryable: false,
+                });
+            }
+        }
+        Ok(signers)
+    }
 }
 
 impl Ord for Credential {
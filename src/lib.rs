@@ -42,6 +42,10 @@ pub mod evm;
 #[cfg_attr(docsrs, doc(cfg(feature = "message")))]
 pub mod message;
 
+#[cfg(feature = "staking")]
+#[cfg_attr(docsrs, doc(cfg(feature = "staking")))]
+pub mod staking;
+
 #[cfg(feature = "wallet")]
 #[cfg_attr(docsrs, doc(cfg(feature = "wallet")))]
 pub mod wallet;
@@ -24,14 +24,9 @@ pub mod version;
 
 pub fn ip_addr_to_bytes(ip_addr: std::net::IpAddr) -> Vec<u8> {
     match ip_addr {
-        std::net::IpAddr::V4(v) => {
-            // "node" encodes IPv4 address as it is
-            // (not compatible with IPv6, e.g., prepends 2 "0xFF"s as in Rust)
-            let octets = v.octets();
-            vec![
-                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, octets[0], octets[1], octets[2], octets[3],
-            ]
-        }
+        // "node" encodes IPv4 address as IPv4-mapped IPv6 (i.e., "net.IP.To16"),
+        // as decoded by "staking::ip::ip_addr_from_bytes"
+        std::net::IpAddr::V4(v) => v.to_ipv6_mapped().octets().to_vec(),
         std::net::IpAddr::V6(v) => v.octets().to_vec(),
     }
 }
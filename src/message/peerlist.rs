@@ -1,6 +1,6 @@
 use std::io::{self, Error, ErrorKind};
 
-use crate::{ids, message, proto::pb::p2p};
+use crate::{ids, message, proto::pb::p2p, staking};
 use prost::Message as ProstMessage;
 
 #[derive(
@@ -21,6 +21,61 @@ pub struct ClaimedIpPort {
     pub tx_id: ids::Id,
 }
 
+impl ClaimedIpPort {
+    /// Builds the claimed IP from the signed IP of the node with the
+    /// DER-encoded staking certificate.
+    pub fn new(certificate: &[u8], signed_ip: &staking::ip::SignedIp, tx_id: ids::Id) -> Self {
+        Self {
+            certificate: certificate.to_vec(),
+            ip_addr: signed_ip.unsigned_ip.ip_addr,
+            ip_port: signed_ip.unsigned_ip.ip_port as u32,
+            time: signed_ip.unsigned_ip.timestamp,
+            sig: signed_ip.signature.clone(),
+            tx_id,
+        }
+    }
+
+    pub fn from_proto(ip: &p2p::ClaimedIpPort) -> io::Result<Self> {
+        if ip.tx_id.len() != ids::LEN {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "invalid tx ID length {} (expected {})",
+                    ip.tx_id.len(),
+                    ids::LEN
+                ),
+            ));
+        }
+        Ok(Self {
+            certificate: ip.x509_certificate.to_vec(),
+            ip_addr: staking::ip::ip_addr_from_bytes(&ip.ip_addr)?,
+            ip_port: ip.ip_port,
+            time: ip.timestamp,
+            sig: ip.signature.to_vec(),
+            tx_id: ids::Id::from_slice(&ip.tx_id),
+        })
+    }
+
+    pub fn to_signed_ip(&self) -> io::Result<staking::ip::SignedIp> {
+        let ip_port = u16::try_from(self.ip_port).map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("invalid port {}", self.ip_port),
+            )
+        })?;
+        Ok(staking::ip::SignedIp {
+            unsigned_ip: staking::ip::UnsignedIp::new(self.ip_addr, ip_port, self.time),
+            signature: self.sig.clone(),
+        })
+    }
+
+    /// Verifies the signed IP against the certificate in the claim.
+    /// ref. "node/network.Network.Track"
+    pub fn verify(&self) -> io::Result<()> {
+        self.to_signed_ip()?.verify(&self.certificate)
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct Message {
     pub msg: p2p::PeerList,
@@ -185,3 +240,39 @@ fn test_message() {
     let msg2_with_compression_deserialized = Message::deserialize(&data2).unwrap();
     assert_eq!(msg1_with_no_compression, msg2_with_compression_deserialized);
 }
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features message -- message::peerlist::test_claimed_ip_port --exact --show-output
+#[test]
+fn test_claimed_ip_port() {
+    let signer = staking::Signer::from_pem_files(
+        "./artifacts/staker1.insecure.key",
+        "./artifacts/staker1.insecure.crt",
+    )
+    .unwrap();
+    let signed_ip = staking::ip::UnsignedIp::new("10.0.0.1".parse().unwrap(), 9651, 1234567)
+        .sign(&signer)
+        .unwrap();
+    let claimed = ClaimedIpPort::new(signer.cert_der(), &signed_ip, ids::Id::sha256("tx"));
+    claimed.verify().unwrap();
+
+    let msg = Message::default().claimed_ip_ports(vec![claimed.clone()]);
+    let msg = Message::deserialize(msg.serialize().unwrap()).unwrap();
+    let received = ClaimedIpPort::from_proto(&msg.msg.claimed_ip_ports[0]).unwrap();
+    assert_eq!(received, claimed);
+    received.verify().unwrap();
+
+    let mut moved = received.clone();
+    moved.ip_addr = "10.0.0.2".parse().unwrap();
+    assert!(moved.verify().is_err());
+
+    let mut out_of_range = received;
+    out_of_range.ip_port = u16::MAX as u32 + 1;
+    assert!(out_of_range.verify().is_err());
+
+    for len in [0, ids::LEN + 1] {
+        let mut ip = msg.msg.claimed_ip_ports[0].clone();
+        ip.tx_id = prost::bytes::Bytes::from(vec![1; len]);
+        let err = ClaimedIpPort::from_proto(&ip).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+}
@@ -1,6 +1,6 @@
 use std::io::{self, Error, ErrorKind};
 
-use crate::{ids, message, proto::pb::p2p};
+use crate::{ids, message, proto::pb::p2p, staking};
 use prost::Message as ProstMessage;
 
 #[derive(Debug, PartialEq, Clone)]
@@ -74,6 +74,19 @@ impl Message {
         self
     }
 
+    /// Sets the IP, port and signature of the signed IP, where the
+    /// signing timestamp is sent as "my_version_time".
+    /// ref. "node/network/peer.Peer.writeMessages"
+    #[must_use]
+    pub fn signed_ip(mut self, signed_ip: &staking::ip::SignedIp) -> Self {
+        let ip = &signed_ip.unsigned_ip;
+        self.msg.ip_addr = prost::bytes::Bytes::from(super::ip_addr_to_bytes(ip.ip_addr));
+        self.msg.ip_port = ip.ip_port as u32;
+        self.msg.my_version_time = ip.timestamp;
+        self.msg.sig = prost::bytes::Bytes::from(signed_ip.signature.clone());
+        self
+    }
+
     #[must_use]
     pub fn tracked_subnets(mut self, tracked_subnets: Vec<ids::Id>) -> Self {
         let mut tracked_subnet_bytes: Vec<prost::bytes::Bytes> =
@@ -91,6 +104,26 @@ impl Message {
         self
     }
 
+    /// Returns the signed IP claimed by the peer, to be verified
+    /// against its staking certificate.
+    /// ref. "node/network/peer.Peer.handleVersion"
+    pub fn to_signed_ip(&self) -> io::Result<staking::ip::SignedIp> {
+        let ip_port = u16::try_from(self.msg.ip_port).map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("invalid port {}", self.msg.ip_port),
+            )
+        })?;
+        Ok(staking::ip::SignedIp {
+            unsigned_ip: staking::ip::UnsignedIp::new(
+                staking::ip::ip_addr_from_bytes(&self.msg.ip_addr)?,
+                ip_port,
+                self.msg.my_version_time,
+            ),
+            signature: self.msg.sig.to_vec(),
+        })
+    }
+
     pub fn serialize(&self) -> io::Result<Vec<u8>> {
         let msg = p2p::Message {
             message: Some(p2p::message::Message::Version(self.msg.clone())),
@@ -211,3 +244,34 @@ fn test_message() {
     let msg2_with_compression_deserialized = Message::deserialize(&data2).unwrap();
     assert_eq!(msg1_with_no_compression, msg2_with_compression_deserialized);
 }
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features message -- message::version::test_signed_ip --exact --show-output
+#[test]
+fn test_signed_ip() {
+    let signer = staking::Signer::from_pem_files(
+        "./artifacts/staker1.insecure.key",
+        "./artifacts/staker1.insecure.crt",
+    )
+    .unwrap();
+    let signed_ip = staking::ip::UnsignedIp::new("10.0.0.1".parse().unwrap(), 9651, 1234567)
+        .sign(&signer)
+        .unwrap();
+
+    let msg = Message::default()
+        .network_id(1)
+        .my_version(String::from("lux/1.10.1"))
+        .signed_ip(&signed_ip);
+    let msg = Message::deserialize(msg.serialize().unwrap()).unwrap();
+    assert_eq!(msg.msg.my_version_time, 1234567);
+
+    let received = msg.to_signed_ip().unwrap();
+    assert_eq!(received, signed_ip);
+    received.verify(signer.cert_der()).unwrap();
+
+    let tampered = msg.my_version_time(1234568);
+    assert!(tampered
+        .to_signed_ip()
+        .unwrap()
+        .verify(signer.cert_der())
+        .is_err());
+}
@@ -1,4 +1,5 @@
 pub mod txs;
+pub mod warp;
 
 use crate::ids;
 
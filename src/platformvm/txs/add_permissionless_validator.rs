@@ -326,6 +326,20 @@ impl Tx {
     }
 }
 
+impl txs::credentials::Signed for Tx {
+    fn base_tx(&self) -> &txs::Tx {
+        &self.base_tx
+    }
+
+    fn credentials(&self) -> Vec<&key::secp256k1::txs::Credential> {
+        self.creds.iter().collect()
+    }
+
+    fn is_staking(&self) -> bool {
+        true
+    }
+}
+
 /// RUST_LOG=debug cargo test --package lux-types --lib -- platformvm::txs::add_permissionless_validator::test_add_permissionless_validator_tx_serialization_with_one_signer --exact --show-output
 #[test]
 fn test_add_permissionless_validator_tx_serialization_with_one_signer() {
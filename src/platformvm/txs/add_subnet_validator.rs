@@ -176,6 +176,20 @@ impl Tx {
     }
 }
 
+impl txs::credentials::Signed for Tx {
+    fn base_tx(&self) -> &txs::Tx {
+        &self.base_tx
+    }
+
+    fn credentials(&self) -> Vec<&key::secp256k1::txs::Credential> {
+        self.creds.iter().collect()
+    }
+
+    fn subnet_auth(&self) -> Option<&key::secp256k1::txs::Input> {
+        Some(&self.subnet_auth)
+    }
+}
+
 /// RUST_LOG=debug cargo test --package lux-types --lib -- platformvm::txs::add_subnet_validator::test_add_subnet_validator_tx_serialization_with_one_signer --exact --show-output
 #[test]
 fn test_add_subnet_validator_tx_serialization_with_one_signer() {
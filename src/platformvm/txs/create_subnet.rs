@@ -149,6 +149,16 @@ impl Tx {
     }
 }
 
+impl txs::credentials::Signed for Tx {
+    fn base_tx(&self) -> &txs::Tx {
+        &self.base_tx
+    }
+
+    fn credentials(&self) -> Vec<&key::secp256k1::txs::Credential> {
+        self.creds.iter().collect()
+    }
+}
+
 /// RUST_LOG=debug cargo test --package lux-types --lib -- platformvm::txs::create_subnet::test_create_subnet_tx_serialization_with_one_signer --exact --show-output
 #[test]
 fn test_create_subnet_tx_serialization_with_one_signer() {
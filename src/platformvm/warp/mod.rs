@@ -0,0 +1,267 @@
+//! Lux Warp cross-subnet messages, signed by the BLS keys of the
+//! source subnet validators.
+//! ref. <https://pkg.go.dev/github.com/luxfi/node/vms/platformvm/warp>
+pub mod payload;
+pub mod signature;
+
+#[cfg(feature = "subnet")]
+#[cfg_attr(docsrs, doc(cfg(feature = "subnet")))]
+pub mod validators;
+
+use crate::{
+    errors::{Error, Result},
+    ids, packer, units,
+};
+
+/// ref. "node/vms/platformvm/warp.codecVersion"
+pub const CODEC_VERSION: u16 = 0;
+
+/// Upper bound of the encoded message size, used for unpacking.
+pub const MAX_MESSAGE_SIZE: usize = 256 * units::KIB as usize;
+
+/// Default quorum of the signed weight over the total weight.
+/// ref. "node/vms/platformvm/warp.WarpDefaultQuorumNumerator"
+pub const DEFAULT_QUORUM_NUMERATOR: u64 = 67;
+/// ref. "node/vms/platformvm/warp.WarpQuorumDenominator"
+pub const QUORUM_DENOMINATOR: u64 = 100;
+
+/// Message to be signed by the source chain validators.
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/vms/platformvm/warp#UnsignedMessage>
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct UnsignedMessage {
+    pub network_id: u32,
+    pub source_chain_id: ids::Id,
+    /// Encoded "payload::Payload" or any application-defined bytes.
+    pub payload: Vec<u8>,
+}
+
+impl UnsignedMessage {
+    pub fn new(network_id: u32, source_chain_id: ids::Id, payload: &[u8]) -> Self {
+        Self {
+            network_id,
+            source_chain_id,
+            payload: payload.to_vec(),
+        }
+    }
+
+    fn pack_fields(&self, packer: &packer::Packer) -> Result<()> {
+        packer.pack_u32(self.network_id)?;
+        packer.pack_bytes(self.source_chain_id.as_ref())?;
+        packer.pack_bytes_with_header(&self.payload)
+    }
+
+    fn unpack_fields(packer: &packer::Packer) -> Result<Self> {
+        let network_id = packer.unpack_u32()?;
+        let source_chain_id = ids::Id::from_slice(&packer.unpack_bytes(ids::LEN)?);
+        let payload = packer.unpack_bytes_with_header()?;
+        Ok(Self {
+            network_id,
+            source_chain_id,
+            payload,
+        })
+    }
+
+    /// Returns the bytes to be signed by the validators.
+    /// ref. "node/vms/platformvm/warp.UnsignedMessage.Bytes"
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let packer = packer::Packer::new(MAX_MESSAGE_SIZE, 128);
+        packer.pack_u16(CODEC_VERSION)?;
+        self.pack_fields(&packer)?;
+        Ok(packer.take_bytes().to_vec())
+    }
+
+    /// ref. "node/vms/platformvm/warp.ParseUnsignedMessage"
+    pub fn from_bytes(b: &[u8]) -> Result<Self> {
+        let packer = packer::Packer::load_bytes_for_unpack(MAX_MESSAGE_SIZE, b);
+        unpack_codec_version(&packer)?;
+        let msg = Self::unpack_fields(&packer)?;
+        check_fully_unpacked(&packer, b.len())?;
+        Ok(msg)
+    }
+
+    /// Returns the SHA256 of the unsigned message bytes.
+    /// ref. "node/vms/platformvm/warp.UnsignedMessage.ID"
+    pub fn id(&self) -> Result<ids::Id> {
+        Ok(ids::Id::sha256(self.to_bytes()?))
+    }
+}
+
+/// Unsigned message with the aggregate signature of the source subnet validators.
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/vms/platformvm/warp#Message>
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct Message {
+    pub unsigned_message: UnsignedMessage,
+    pub signature: signature::BitSetSignature,
+}
+
+impl Message {
+    pub fn new(unsigned_message: UnsignedMessage, signature: signature::BitSetSignature) -> Self {
+        Self {
+            unsigned_message,
+            signature,
+        }
+    }
+
+    /// ref. "node/vms/platformvm/warp.Message.Bytes"
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let packer = packer::Packer::new(MAX_MESSAGE_SIZE, 256);
+        packer.pack_u16(CODEC_VERSION)?;
+        // embedded struct, thus no type ID
+        self.unsigned_message.pack_fields(&packer)?;
+        // "Signature" is an interface, thus encodes the type ID
+        packer.pack_u32(signature::BitSetSignature::type_id())?;
+        self.signature.pack_fields(&packer)?;
+        Ok(packer.take_bytes().to_vec())
+    }
+
+    /// ref. "node/vms/platformvm/warp.ParseMessage"
+    pub fn from_bytes(b: &[u8]) -> Result<Self> {
+        let packer = packer::Packer::load_bytes_for_unpack(MAX_MESSAGE_SIZE, b);
+        unpack_codec_version(&packer)?;
+        let unsigned_message = UnsignedMessage::unpack_fields(&packer)?;
+
+        let type_id = packer.unpack_u32()?;
+        if type_id != signature::BitSetSignature::type_id() {
+            return Err(Error::Other {
+                message: format!("unknown signature type ID {}", type_id),
+                retryable: false,
+            });
+        }
+        let signature = signature::BitSetSignature::unpack_fields(&packer)?;
+        check_fully_unpacked(&packer, b.len())?;
+
+        Ok(Self {
+            unsigned_message,
+            signature,
+        })
+    }
+
+    /// Returns the ID of the unsigned message, which does not change
+    /// with the signature.
+    pub fn id(&self) -> Result<ids::Id> {
+        self.unsigned_message.id()
+    }
+
+    /// Verifies the signature against the validator set of the source
+    /// chain's subnet at the P-chain height.
+    /// ref. "node/vms/platformvm/warp.Message.Signature.Verify"
+    #[cfg(feature = "subnet")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subnet")))]
+    pub async fn verify<S>(
+        &self,
+        network_id: u32,
+        state: &S,
+        p_chain_height: u64,
+        quorum_num: u64,
+        quorum_den: u64,
+    ) -> Result<()>
+    where
+        S: crate::subnet::rpc::snow::validators::State + ?Sized,
+    {
+        let source_chain_id = self.unsigned_message.source_chain_id;
+        let subnet_id = state
+            .get_subnet_id(source_chain_id)
+            .await
+            .map_err(|e| Error::API {
+                message: format!("failed get_subnet_id for chain {} '{}'", source_chain_id, e),
+                retryable: true,
+            })?;
+        let vdrs =
+            validators::CanonicalValidatorSet::load(state, p_chain_height, subnet_id).await?;
+
+        self.signature.verify(
+            &self.unsigned_message,
+            network_id,
+            &vdrs,
+            quorum_num,
+            quorum_den,
+        )
+    }
+}
+
+pub(crate) fn unpack_codec_version(packer: &packer::Packer) -> Result<()> {
+    let codec_version = packer.unpack_u16()?;
+    if codec_version != CODEC_VERSION {
+        return Err(Error::Other {
+            message: format!("unknown codec version {}", codec_version),
+            retryable: false,
+        });
+    }
+    Ok(())
+}
+
+pub(crate) fn check_fully_unpacked(packer: &packer::Packer, n: usize) -> Result<()> {
+    if packer.get_offset() != n {
+        return Err(Error::Other {
+            message: format!(
+                "unexpected {} trailing bytes after unpacking",
+                n - packer.get_offset()
+            ),
+            retryable: false,
+        });
+    }
+    Ok(())
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib -- platformvm::warp::test_message --exact --show-output
+#[test]
+fn test_message() {
+    let source_chain_id = ids::Id::sha256("source");
+    let payload = payload::Payload::AddressedCall(payload::AddressedCall {
+        source_address: vec![0x01; 20],
+        payload: b"hello".to_vec(),
+    })
+    .to_bytes()
+    .unwrap();
+
+    let unsigned = UnsignedMessage::new(5, source_chain_id, &payload);
+    let unsigned_bytes = unsigned.to_bytes().unwrap();
+    assert_eq!(
+        unsigned_bytes[..],
+        [
+            &[0x00, 0x00][..],             // codec version
+            &[0x00, 0x00, 0x00, 0x05][..], // network ID
+            source_chain_id.as_ref(),      // source chain ID
+            &(payload.len() as u32).to_be_bytes()[..],
+            &payload[..],
+        ]
+        .concat()[..]
+    );
+    assert_eq!(
+        UnsignedMessage::from_bytes(&unsigned_bytes).unwrap(),
+        unsigned
+    );
+    assert_eq!(unsigned.id().unwrap(), ids::Id::sha256(&unsigned_bytes));
+
+    let msg = Message::new(
+        unsigned.clone(),
+        signature::BitSetSignature {
+            signers: vec![0x05],
+            signature: [0x07; crate::key::bls::signature::LEN],
+        },
+    );
+    let msg_bytes = msg.to_bytes().unwrap();
+    assert_eq!(
+        msg_bytes[..],
+        [
+            &unsigned_bytes[..],
+            &[0x00, 0x00, 0x00, 0x00][..], // BitSetSignature type ID
+            &[0x00, 0x00, 0x00, 0x01, 0x05][..],
+            &[0x07; crate::key::bls::signature::LEN][..],
+        ]
+        .concat()[..]
+    );
+    assert_eq!(Message::from_bytes(&msg_bytes).unwrap(), msg);
+    assert_eq!(msg.id().unwrap(), unsigned.id().unwrap());
+
+    // trailing bytes
+    let mut b = msg_bytes.clone();
+    b.push(0x00);
+    assert!(Message::from_bytes(&b).is_err());
+    // unknown codec version
+    let mut b = unsigned_bytes.clone();
+    b[1] = 0x01;
+    assert!(UnsignedMessage::from_bytes(&b).is_err());
+    // truncated
+    assert!(Message::from_bytes(&msg_bytes[..msg_bytes.len() - 1]).is_err());
+}
@@ -0,0 +1,129 @@
+//! Typed payloads of the Warp unsigned message.
+//! ref. <https://pkg.go.dev/github.com/luxfi/node/vms/platformvm/warp/payload>
+use crate::{
+    errors::{Error, Result},
+    ids, packer, units,
+};
+
+/// ref. "node/vms/platformvm/warp/payload.MaxMessageSize"
+pub const MAX_MESSAGE_SIZE: usize = 24 * units::KIB as usize;
+
+/// Hash of the data (e.g., block) on the source chain.
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/vms/platformvm/warp/payload#Hash>
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct Hash {
+    pub hash: ids::Id,
+}
+
+impl Hash {
+    /// ref. "node/vms/platformvm/warp/payload.Codec"
+    pub fn type_id() -> u32 {
+        0
+    }
+}
+
+/// Call from the address on the source chain.
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/vms/platformvm/warp/payload#AddressedCall>
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct AddressedCall {
+    pub source_address: Vec<u8>,
+    pub payload: Vec<u8>,
+}
+
+impl AddressedCall {
+    /// ref. "node/vms/platformvm/warp/payload.Codec"
+    pub fn type_id() -> u32 {
+        1
+    }
+}
+
+/// Payload of the Warp unsigned message.
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/vms/platformvm/warp/payload#Payload>
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Payload {
+    Hash(Hash),
+    AddressedCall(AddressedCall),
+}
+
+impl Payload {
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let packer = packer::Packer::new(MAX_MESSAGE_SIZE, 64);
+        packer.pack_u16(super::CODEC_VERSION)?;
+        match self {
+            Payload::Hash(h) => {
+                packer.pack_u32(Hash::type_id())?;
+                packer.pack_bytes(h.hash.as_ref())?;
+            }
+            Payload::AddressedCall(c) => {
+                packer.pack_u32(AddressedCall::type_id())?;
+                packer.pack_bytes_with_header(&c.source_address)?;
+                packer.pack_bytes_with_header(&c.payload)?;
+            }
+        }
+        Ok(packer.take_bytes().to_vec())
+    }
+
+    /// ref. "node/vms/platformvm/warp/payload.Parse"
+    pub fn from_bytes(b: &[u8]) -> Result<Self> {
+        let packer = packer::Packer::load_bytes_for_unpack(MAX_MESSAGE_SIZE, b);
+        super::unpack_codec_version(&packer)?;
+
+        let type_id = packer.unpack_u32()?;
+        let payload = if type_id == Hash::type_id() {
+            Payload::Hash(Hash {
+                hash: ids::Id::from_slice(&packer.unpack_bytes(ids::LEN)?),
+            })
+        } else if type_id == AddressedCall::type_id() {
+            Payload::AddressedCall(AddressedCall {
+                source_address: packer.unpack_bytes_with_header()?,
+                payload: packer.unpack_bytes_with_header()?,
+            })
+        } else {
+            return Err(Error::Other {
+                message: format!("unknown payload type ID {}", type_id),
+                retryable: false,
+            });
+        };
+        super::check_fully_unpacked(&packer, b.len())?;
+
+        Ok(payload)
+    }
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib -- platformvm::warp::payload::test_payload --exact --show-output
+#[test]
+fn test_payload() {
+    let hash = Payload::Hash(Hash {
+        hash: ids::Id::sha256("block"),
+    });
+    let b = hash.to_bytes().unwrap();
+    assert_eq!(
+        b[..],
+        [
+            &[0x00, 0x00, 0x00, 0x00, 0x00, 0x00][..],
+            ids::Id::sha256("block").as_ref(),
+        ]
+        .concat()[..]
+    );
+    assert_eq!(Payload::from_bytes(&b).unwrap(), hash);
+
+    let call = Payload::AddressedCall(AddressedCall {
+        source_address: vec![0xaa, 0xbb],
+        payload: vec![0x01, 0x02, 0x03],
+    });
+    let b = call.to_bytes().unwrap();
+    assert_eq!(
+        b,
+        vec![
+            0x00, 0x00, // codec version
+            0x00, 0x00, 0x00, 0x01, // type ID
+            0x00, 0x00, 0x00, 0x02, 0xaa, 0xbb, // source address
+            0x00, 0x00, 0x00, 0x03, 0x01, 0x02, 0x03, // payload
+        ]
+    );
+    assert_eq!(Payload::from_bytes(&b).unwrap(), call);
+
+    let mut unknown = b.clone();
+    unknown[5] = 0x02;
+    assert!(Payload::from_bytes(&unknown).is_err());
+}
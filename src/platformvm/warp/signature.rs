@@ -0,0 +1,277 @@
+use crate::{
+    errors::{Error, Result},
+    key::bls,
+    packer,
+};
+
+/// Aggregate BLS signature of the validators whose canonical indices
+/// are set in "signers".
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/vms/platformvm/warp#BitSetSignature>
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BitSetSignature {
+    /// Big-endian bitset, where the least significant bit of the last
+    /// byte is the validator at index 0.
+    /// ref. "node/utils/set.BitsFromBytes"
+    pub signers: Vec<u8>,
+    pub signature: [u8; bls::signature::LEN],
+}
+
+impl Default for BitSetSignature {
+    fn default() -> Self {
+        Self {
+            signers: Vec::new(),
+            signature: [0u8; bls::signature::LEN],
+        }
+    }
+}
+
+impl BitSetSignature {
+    /// Aggregates the signatures and encodes the signer indices.
+    /// "sigs[i]" must be signed by the validator at "indices[i]".
+    pub fn new(indices: &[usize], sigs: &[bls::signature::Sig]) -> Result<Self> {
+        if indices.len() != sigs.len() {
+            return Err(Error::Other {
+                message: format!(
+                    "indices length {} != signatures length {}",
+                    indices.len(),
+                    sigs.len()
+                ),
+                retryable: false,
+            });
+        }
+        let agg_sig = bls::signature::aggregate(sigs).map_err(|e| Error::Other {
+            message: format!("failed bls::signature::aggregate '{}'", e),
+            retryable: false,
+        })?;
+
+        Ok(Self {
+            signers: signers_from_indices(indices),
+            signature: agg_sig.to_compressed_bytes(),
+        })
+    }
+
+    /// ref. "node/vms/platformvm/warp.Codec"
+    pub fn type_id() -> u32 {
+        0
+    }
+
+    pub(crate) fn pack_fields(&self, packer: &packer::Packer) -> Result<()> {
+        packer.pack_bytes_with_header(&self.signers)?;
+        packer.pack_bytes(&self.signature)
+    }
+
+    pub(crate) fn unpack_fields(packer: &packer::Packer) -> Result<Self> {
+        let signers = packer.unpack_bytes_with_header()?;
+        let mut signature = [0u8; bls::signature::LEN];
+        signature.copy_from_slice(&packer.unpack_bytes(bls::signature::LEN)?);
+        Ok(Self { signers, signature })
+    }
+
+    /// Returns the indices of the signers in ascending order.
+    /// Fails if the bitset has leading zero bytes, so that each
+    /// signer set has the unique encoding.
+    pub fn signer_indices(&self) -> Result<Vec<usize>> {
+        if self.signers.first() == Some(&0) {
+            return Err(Error::Other {
+                message: "signers bitset has padding".to_string(),
+                retryable: false,
+            });
+        }
+
+        let n = self.signers.len();
+        let mut indices = Vec::new();
+        for i in 0..n * 8 {
+            if self.signers[n - 1 - i / 8] & (1 << (i % 8)) != 0 {
+                indices.push(i);
+            }
+        }
+        Ok(indices)
+    }
+
+    /// Verifies the signature of the message against the canonical validator
+    /// set of the source subnet, and checks that the signers have at least
+    /// "quorum_num / quorum_den" of the total weight.
+    /// ref. "node/vms/platformvm/warp.BitSetSignature.Verify"
+    #[cfg(feature = "subnet")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subnet")))]
+    pub fn verify(
+        &self,
+        msg: &super::UnsignedMessage,
+        network_id: u32,
+        vdrs: &super::validators::CanonicalValidatorSet,
+        quorum_num: u64,
+        quorum_den: u64,
+    ) -> Result<()> {
+        if msg.network_id != network_id {
+            return Err(Error::Other {
+                message: format!(
+                    "incorrect network ID {} (expected {})",
+                    msg.network_id, network_id
+                ),
+                retryable: false,
+            });
+        }
+
+        let indices = self.signer_indices()?;
+        let mut signed_weight = 0_u64;
+        let mut pubkeys = Vec::with_capacity(indices.len());
+        for i in indices {
+            let vdr = vdrs.validators.get(i).ok_or_else(|| Error::Other {
+                message: format!(
+                    "signer index {} out of range (validators {})",
+                    i,
+                    vdrs.validators.len()
+                ),
+                retryable: false,
+            })?;
+            signed_weight = signed_weight
+                .checked_add(vdr.weight)
+                .ok_or_else(|| Error::Other {
+                    message: "signed weight overflow".to_string(),
+                    retryable: false,
+                })?;
+            pubkeys.push(vdr.public_key);
+        }
+        verify_weight(signed_weight, vdrs.total_weight, quorum_num, quorum_den)?;
+
+        let sig = bls::signature::Sig::from_bytes(&self.signature).map_err(|e| Error::Other {
+            message: format!("failed to parse signature '{}'", e),
+            retryable: false,
+        })?;
+        if !bls::signature::fast_aggregate_verify(&sig, &msg.to_bytes()?, &pubkeys) {
+            return Err(Error::Other {
+                message: "invalid aggregate signature".to_string(),
+                retryable: false,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Encodes the signer indices as a big-endian bitset without padding.
+/// ref. "node/utils/set.Bits.Bytes"
+pub fn signers_from_indices(indices: &[usize]) -> Vec<u8> {
+    let max = match indices.iter().max() {
+        Some(max) => *max,
+        None => return Vec::new(),
+    };
+
+    let n = max / 8 + 1;
+    let mut b = vec![0u8; n];
+    for i in indices {
+        b[n - 1 - i / 8] |= 1 << (i % 8);
+    }
+    b
+}
+
+/// Checks "signed_weight / total_weight >= quorum_num / quorum_den".
+/// ref. "node/vms/platformvm/warp.VerifyWeight"
+pub fn verify_weight(
+    signed_weight: u64,
+    total_weight: u64,
+    quorum_num: u64,
+    quorum_den: u64,
+) -> Result<()> {
+    let scaled_total = total_weight as u128 * quorum_num as u128;
+    let scaled_signed = signed_weight as u128 * quorum_den as u128;
+    if scaled_total > scaled_signed {
+        return Err(Error::Other {
+            message: format!(
+                "insufficient weight {} of total {} (quorum {}/{})",
+                signed_weight, total_weight, quorum_num, quorum_den
+            ),
+            retryable: false,
+        });
+    }
+    Ok(())
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib -- platformvm::warp::signature::test_signers --exact --show-output
+#[test]
+fn test_signers() {
+    assert!(signers_from_indices(&[]).is_empty());
+    assert_eq!(signers_from_indices(&[0]), vec![0x01]);
+    assert_eq!(signers_from_indices(&[0, 2, 7]), vec![0x85]);
+    assert_eq!(signers_from_indices(&[1, 8]), vec![0x01, 0x02]);
+
+    for indices in [vec![], vec![0], vec![0, 2, 7], vec![1, 8, 20]] {
+        let sig = BitSetSignature {
+            signers: signers_from_indices(&indices),
+            ..Default::default()
+        };
+        assert_eq!(sig.signer_indices().unwrap(), indices);
+    }
+
+    let padded = BitSetSignature {
+        signers: vec![0x00, 0x01],
+        ..Default::default()
+    };
+    assert!(padded.signer_indices().is_err());
+
+    assert!(verify_weight(67, 100, 67, 100).is_ok());
+    assert!(verify_weight(66, 100, 67, 100).is_err());
+    assert!(verify_weight(u64::MAX, u64::MAX, 67, 100).is_ok());
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features subnet -- platformvm::warp::signature::test_verify --exact --show-output
+#[cfg(feature = "subnet")]
+#[test]
+fn test_verify() {
+    use std::collections::BTreeMap;
+
+    use crate::{ids, subnet::rpc::snow::validators::GetValidatorOutput};
+
+    let sks = (0..4)
+        .map(|_| bls::private_key::Key::generate().unwrap())
+        .collect::<Vec<_>>();
+    let mut vdr_set = BTreeMap::new();
+    for (i, sk) in sks.iter().enumerate() {
+        let node_id = ids::node::Id::from_slice(&[i as u8; ids::node::LEN]);
+        vdr_set.insert(
+            node_id,
+            GetValidatorOutput {
+                node_id,
+                public_key: Some(sk.to_public_key()),
+                weight: 25,
+            },
+        );
+    }
+    let vdrs = super::validators::CanonicalValidatorSet::new(&vdr_set).unwrap();
+
+    let msg = super::UnsignedMessage::new(1, ids::Id::sha256("source"), b"hello");
+    let msg_bytes = msg.to_bytes().unwrap();
+    let sign = |indices: &[usize]| {
+        let sigs = indices
+            .iter()
+            .map(|i| {
+                let sk = sks
+                    .iter()
+                    .find(|sk| sk.to_public_key() == vdrs.validators[*i].public_key)
+                    .unwrap();
+                sk.sign(&msg_bytes)
+            })
+            .collect::<Vec<_>>();
+        BitSetSignature::new(indices, &sigs).unwrap()
+    };
+
+    // 75% signed
+    let sig = sign(&[0, 1, 3]);
+    assert!(sig.verify(&msg, 1, &vdrs, 67, 100).is_ok());
+    assert!(sig.verify(&msg, 2, &vdrs, 67, 100).is_err());
+    assert!(sig.verify(&msg, 1, &vdrs, 80, 100).is_err());
+
+    // signers bitset does not match the signature
+    let mut wrong = sig.clone();
+    wrong.signers = signers_from_indices(&[0, 1, 2]);
+    assert!(wrong.verify(&msg, 1, &vdrs, 67, 100).is_err());
+
+    // signer out of range
+    let mut wrong = sig.clone();
+    wrong.signers = signers_from_indices(&[0, 1, 3, 4]);
+    assert!(wrong.verify(&msg, 1, &vdrs, 67, 100).is_err());
+
+    // 50% signed
+    let sig = sign(&[1, 2]);
+    assert!(sig.verify(&msg, 1, &vdrs, 67, 100).is_err());
+    assert!(sig.verify(&msg, 1, &vdrs, 50, 100).is_ok());
+}
@@ -0,0 +1,152 @@
+//! Canonical ordering of the validator set, used for the signer bitset.
+//! ref. <https://pkg.go.dev/github.com/luxfi/node/vms/platformvm/warp#GetCanonicalValidatorSet>
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    errors::{Error, Result},
+    ids,
+    key::bls,
+    subnet::rpc::snow::validators::{GetValidatorOutput, State},
+};
+
+/// Validators with the same BLS public key, merged into one signer.
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/vms/platformvm/warp#Validator>
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Validator {
+    pub public_key: bls::public_key::Key,
+    pub public_key_bytes: Vec<u8>,
+    pub weight: u64,
+    pub node_ids: Vec<ids::node::Id>,
+}
+
+/// Validators sorted by their public key bytes, where the index of each
+/// validator is its bit in "signature::BitSetSignature::signers".
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct CanonicalValidatorSet {
+    pub validators: Vec<Validator>,
+    /// Total weight of all validators, including the ones without BLS keys.
+    pub total_weight: u64,
+}
+
+impl CanonicalValidatorSet {
+    /// Builds the canonical set from the validator set of a subnet.
+    /// Validators without BLS public keys are excluded, but still count
+    /// towards the total weight.
+    pub fn new(vdr_set: &BTreeMap<ids::node::Id, GetValidatorOutput>) -> Result<Self> {
+        let mut total_weight = 0_u64;
+        let mut unique: HashMap<Vec<u8>, Validator> = HashMap::new();
+        for vdr in vdr_set.values() {
+            total_weight = total_weight
+                .checked_add(vdr.weight)
+                .ok_or_else(|| weight_overflow(&vdr.node_id))?;
+
+            let public_key = match vdr.public_key {
+                Some(pk) => pk,
+                None => continue,
+            };
+            let public_key_bytes = public_key.to_compressed_bytes().to_vec();
+            let v = unique
+                .entry(public_key_bytes.clone())
+                .or_insert_with(|| Validator {
+                    public_key,
+                    public_key_bytes,
+                    weight: 0,
+                    node_ids: Vec::new(),
+                });
+            v.weight = v
+                .weight
+                .checked_add(vdr.weight)
+                .ok_or_else(|| weight_overflow(&vdr.node_id))?;
+            v.node_ids.push(vdr.node_id);
+        }
+
+        let mut validators: Vec<Validator> = unique.into_values().collect();
+        validators.sort_by(|a, b| a.public_key_bytes.cmp(&b.public_key_bytes));
+
+        Ok(Self {
+            validators,
+            total_weight,
+        })
+    }
+
+    /// Fetches the validator set of the subnet at the P-chain height,
+    /// and builds the canonical set.
+    pub async fn load<S: State + ?Sized>(
+        state: &S,
+        p_chain_height: u64,
+        subnet_id: ids::Id,
+    ) -> Result<Self> {
+        let vdr_set = state
+            .get_validator_set(p_chain_height, subnet_id)
+            .await
+            .map_err(|e| Error::API {
+                message: format!(
+                    "failed get_validator_set for subnet {} at height {} '{}'",
+                    subnet_id, p_chain_height, e
+                ),
+                retryable: true,
+            })?;
+        Self::new(&vdr_set)
+    }
+}
+
+fn weight_overflow(node_id: &ids::node::Id) -> Error {
+    Error::Other {
+        message: format!("weight overflow at validator {}", node_id),
+        retryable: false,
+    }
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features subnet -- platformvm::warp::validators::test_canonical_validator_set --exact --show-output
+#[test]
+fn test_canonical_validator_set() {
+    let sk1 = bls::private_key::Key::generate().unwrap();
+    let sk2 = bls::private_key::Key::generate().unwrap();
+
+    let mut vdr_set = BTreeMap::new();
+    for (i, (pk, weight)) in [
+        (Some(sk1.to_public_key()), 10),
+        (Some(sk2.to_public_key()), 20),
+        // same public key as the first, thus merged
+        (Some(sk1.to_public_key()), 30),
+        // no BLS key, thus only counts towards the total weight
+        (None, 40),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let node_id = ids::node::Id::from_slice(&[i as u8; ids::node::LEN]);
+        vdr_set.insert(
+            node_id,
+            GetValidatorOutput {
+                node_id,
+                public_key: pk,
+                weight,
+            },
+        );
+    }
+
+    let set = CanonicalValidatorSet::new(&vdr_set).unwrap();
+    assert_eq!(set.total_weight, 100);
+    assert_eq!(set.validators.len(), 2);
+    assert!(set.validators[0].public_key_bytes < set.validators[1].public_key_bytes);
+
+    let merged = set
+        .validators
+        .iter()
+        .find(|v| v.public_key == sk1.to_public_key())
+        .unwrap();
+    assert_eq!(merged.weight, 40);
+    assert_eq!(merged.node_ids.len(), 2);
+
+    let node_id = ids::node::Id::from_slice(&[9; ids::node::LEN]);
+    vdr_set.insert(
+        node_id,
+        GetValidatorOutput {
+            node_id,
+            public_key: None,
+            weight: u64::MAX,
+        },
+    );
+    assert!(CanonicalValidatorSet::new(&vdr_set).is_err());
+}
@@ -6,5 +6,5 @@ pub mod pb;
 #[doc(hidden)]
 pub use pb::*;
 
-/// ref. <https://github.com/luxfi/node/blob/v1.10.1/version/constants.go#L15-L17>
-pub const PROTOCOL_VERSION: u32 = 26;
+/// ref. <https://github.com/luxfi/node/blob/v1.10.5/version/constants.go#L15-L17>
+pub const PROTOCOL_VERSION: u32 = 27;
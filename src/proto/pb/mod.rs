@@ -87,6 +87,11 @@ pub mod sharedmemory {
     include!("sharedmemory.rs");
     // @@protoc_insertion_point(sharedmemory)
 }
+// @@protoc_insertion_point(attribute:signer)
+pub mod signer {
+    include!("signer.rs");
+    // @@protoc_insertion_point(signer)
+}
 // @@protoc_insertion_point(attribute:sync)
 pub mod sync {
     include!("sync.rs");
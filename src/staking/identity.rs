@@ -0,0 +1,333 @@
+//! Node identity, consisting of the staking TLS certificate and the BLS
+//! signer key.
+use std::{
+    fs::{self, File},
+    io::{self, Error, ErrorKind, Write},
+};
+
+use crate::{ids, jsonrpc::info::GetNodeIdResult, key::bls};
+
+/// Staking certificate and key, BLS signer key, and the derived node ID
+/// and proof of possession of a node.
+/// ref. "node/staking.NewCertAndKeyBytes"
+/// ref. "node/vms/platformvm/signer.NewProofOfPossession"
+#[derive(Debug, Clone)]
+pub struct Identity {
+    pub staking_key_pem: String,
+    pub staking_cert_pem: String,
+    /// PKCS#8-encoded private key of the staking certificate.
+    pub staking_key_der: Vec<u8>,
+    pub staking_cert_der: Vec<u8>,
+    pub signer_key: bls::private_key::Key,
+    pub node_id: ids::node::Id,
+    pub proof_of_possession: bls::ProofOfPossession,
+}
+
+impl Identity {
+    /// Generates a new staking certificate and BLS signer key.
+    #[cfg(not(windows))]
+    pub fn generate() -> io::Result<Self> {
+        let cert = cert_manager::x509::generate(None)?;
+
+        // serialize once, since each serialization re-signs the certificate
+        let staking_cert_pem = cert
+            .serialize_pem()
+            .map_err(|e| Error::new(ErrorKind::Other, format!("failed serialize_pem {}", e)))?;
+        let (_, pem) = x509_parser::pem::parse_x509_pem(staking_cert_pem.as_bytes())
+            .map_err(|e| Error::new(ErrorKind::Other, format!("failed parse_x509_pem {}", e)))?;
+        let staking_cert_der = pem.contents;
+
+        let staking_key_pem = cert.serialize_private_key_pem();
+        let staking_key_der = cert.serialize_private_key_der();
+
+        let signer_key = bls::private_key::Key::generate()?;
+        let node_id = ids::node::Id::from_cert_der_bytes(&staking_cert_der)?;
+        let proof_of_possession = signer_key.to_proof_of_possession();
+
+        Ok(Self {
+            staking_key_pem,
+            staking_cert_pem,
+            staking_key_der,
+            staking_cert_der,
+            signer_key,
+            node_id,
+            proof_of_possession,
+        })
+    }
+
+    #[cfg(windows)]
+    pub fn generate() -> io::Result<Self> {
+        unimplemented!("not implemented")
+    }
+
+    /// Loads the staking key, certificate and BLS signer key files,
+    /// as written by "write_to_files".
+    pub fn from_files(
+        staking_key_path: &str,
+        staking_cert_path: &str,
+        signer_key_path: &str,
+    ) -> io::Result<Self> {
+        let (key, cert) =
+            cert_manager::x509::load_pem_key_cert_to_der(staking_key_path, staking_cert_path)?;
+        let signer_key = bls::private_key::Key::from_file(signer_key_path)?;
+
+        Ok(Self {
+            staking_key_pem: fs::read_to_string(staking_key_path)?,
+            staking_cert_pem: fs::read_to_string(staking_cert_path)?,
+            staking_key_der: key.0,
+            node_id: ids::node::Id::from_cert_der_bytes(&cert.0)?,
+            staking_cert_der: cert.0,
+            proof_of_possession: signer_key.to_proof_of_possession(),
+            signer_key,
+        })
+    }
+
+    /// Loads the existing staking certificate and BLS signer key if exist,
+    /// and generates the missing ones otherwise.
+    /// Returns "true" if any is generated.
+    pub fn load_or_generate(
+        staking_key_path: &str,
+        staking_cert_path: &str,
+        signer_key_path: &str,
+    ) -> io::Result<(Self, bool)> {
+        let (_, tls_generated) =
+            ids::node::Id::load_or_generate_pem(staking_key_path, staking_cert_path)?;
+        let (_, signer_generated) = bls::private_key::Key::load_or_generate(signer_key_path)?;
+
+        let identity = Self::from_files(staking_key_path, staking_cert_path, signer_key_path)?;
+        Ok((identity, tls_generated || signer_generated))
+    }
+
+    /// Writes the PEM-encoded staking key and certificate, and the raw
+    /// BLS signer key, readable only by the owner. Fails if any of the
+    /// files already exists, without touching the existing ones.
+    pub fn write_to_files(
+        &self,
+        staking_key_path: &str,
+        staking_cert_path: &str,
+        signer_key_path: &str,
+    ) -> io::Result<()> {
+        let signer_key = self.signer_key.to_bytes();
+        let contents: [(&str, &[u8]); 3] = [
+            (staking_key_path, self.staking_key_pem.as_bytes()),
+            (staking_cert_path, self.staking_cert_pem.as_bytes()),
+            (signer_key_path, &signer_key),
+        ];
+
+        // create all files first, so an existing one fails the write as a whole
+        let mut files = Vec::with_capacity(contents.len());
+        for (p, _) in contents.iter() {
+            match create_new_private_file(p) {
+                Ok(f) => files.push(f),
+                Err(e) => {
+                    for (created, _) in contents.iter().take(files.len()) {
+                        let _ = fs::remove_file(created);
+                    }
+                    if e.kind() == ErrorKind::AlreadyExists {
+                        return Err(Error::new(
+                            ErrorKind::AlreadyExists,
+                            format!("path {} already exists", p),
+                        ));
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        for (mut f, (_, d)) in files.into_iter().zip(contents.iter()) {
+            f.write_all(d)?;
+            f.sync_all()?;
+        }
+
+        log::info!(
+            "saved node {} identity to '{}', '{}', '{}'",
+            self.node_id,
+            staking_key_path,
+            staking_cert_path,
+            signer_key_path
+        );
+        Ok(())
+    }
+
+    /// Returns the signer of the staking TLS key.
+    pub fn to_staking_signer(&self) -> io::Result<super::Signer> {
+        super::Signer::from_der(&self.staking_key_der, &self.staking_cert_der)
+    }
+
+    /// Checks that the node serving "info.getNodeID" runs with this identity.
+    pub fn verify_node_id_result(&self, result: &GetNodeIdResult) -> io::Result<()> {
+        verify_node_id_result(&self.node_id, Some(&self.proof_of_possession), result)
+    }
+}
+
+/// Checks that the node ID and the proof of possession returned by
+/// "info.getNodeID" match the expected ones, so that the validator is not
+/// staked with a BLS key that the node does not sign with.
+/// Set "pop" to "None" to only check the node ID (e.g., subnet validators).
+/// ref. <https://docs.lux.network/build/node-apis/info/#infogetnodeid>
+pub fn verify_node_id_result(
+    node_id: &ids::node::Id,
+    pop: Option<&bls::ProofOfPossession>,
+    result: &GetNodeIdResult,
+) -> io::Result<()> {
+    if result.node_id != *node_id {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "node ID mismatch (expected {}, node returned {})",
+                node_id, result.node_id
+            ),
+        ));
+    }
+
+    let pop = match pop {
+        Some(pop) => pop,
+        None => return Ok(()),
+    };
+    let node_pop = result.node_pop.as_ref().ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("node {} returned no proof of possession", node_id),
+        )
+    })?;
+    if node_pop.public_key != pop.public_key
+        || node_pop.proof_of_possession != pop.proof_of_possession
+    {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "proof of possession mismatch for node {} (expected public key 0x{}, node returned 0x{})",
+                node_id,
+                hex::encode(&pop.public_key),
+                hex::encode(&node_pop.public_key)
+            ),
+        ));
+    }
+    if !node_pop.verify()? {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("invalid proof of possession for node {}", node_id),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn create_new_private_file(file_path: &str) -> io::Result<File> {
+    use std::os::unix::fs::OpenOptionsExt;
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(file_path)
+}
+
+#[cfg(not(unix))]
+fn create_new_private_file(file_path: &str) -> io::Result<File> {
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(file_path)
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features staking -- staking::identity::test_identity --exact --show-output
+#[test]
+fn test_identity() {
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .is_test(true)
+        .try_init();
+
+    let identity = Identity::generate().unwrap();
+    assert!(bls::private_key::Key::from_bytes(&identity.signer_key.to_bytes()).is_ok());
+    assert_eq!(
+        identity.proof_of_possession.pubkey,
+        Some(identity.signer_key.to_public_key())
+    );
+    assert!(identity.proof_of_possession.verify().unwrap());
+
+    let signer = identity.to_staking_signer().unwrap();
+    assert_eq!(signer.node_id().unwrap(), identity.node_id);
+    let sig = signer.sign(b"hello").unwrap();
+    super::verify(&identity.staking_cert_der, b"hello", &sig).unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let p = |name: &str| dir.path().join(name).display().to_string();
+    identity
+        .write_to_files(&p("staker.key"), &p("staker.crt"), &p("signer.key"))
+        .unwrap();
+    assert!(identity
+        .write_to_files(&p("staker.key"), &p("staker.crt"), &p("signer.key"))
+        .is_err());
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(p("staker.key")).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    // an existing file fails the write without leaving the other files behind
+    fs::write(p("other.crt"), b"existing").unwrap();
+    assert_eq!(
+        identity
+            .write_to_files(&p("other.key"), &p("other.crt"), &p("other.signer"))
+            .unwrap_err()
+            .kind(),
+        ErrorKind::AlreadyExists
+    );
+    assert!(!dir.path().join("other.key").exists());
+    assert_eq!(fs::read(p("other.crt")).unwrap(), b"existing");
+    assert_eq!(
+        ids::node::Id::from_cert_pem_file(&p("staker.crt")).unwrap(),
+        identity.node_id
+    );
+
+    let loaded =
+        Identity::from_files(&p("staker.key"), &p("staker.crt"), &p("signer.key")).unwrap();
+    assert_eq!(loaded.node_id, identity.node_id);
+    assert_eq!(loaded.staking_cert_der, identity.staking_cert_der);
+    assert_eq!(loaded.proof_of_possession, identity.proof_of_possession);
+
+    let (loaded, generated) =
+        Identity::load_or_generate(&p("staker.key"), &p("staker.crt"), &p("signer.key")).unwrap();
+    assert!(!generated);
+    assert_eq!(loaded.node_id, identity.node_id);
+
+    let (created, generated) =
+        Identity::load_or_generate(&p("new.key"), &p("new.crt"), &p("new.signer")).unwrap();
+    assert!(generated);
+    assert_ne!(created.node_id, identity.node_id);
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features staking -- staking::identity::test_verify_node_id_result --exact --show-output
+#[test]
+fn test_verify_node_id_result() {
+    let identity = Identity::generate().unwrap();
+    let other = Identity::generate().unwrap();
+
+    let mut result = GetNodeIdResult {
+        node_id: identity.node_id,
+        node_pop: Some(identity.proof_of_possession.clone()),
+    };
+    identity.verify_node_id_result(&result).unwrap();
+
+    // node runs with the other signer key
+    result.node_pop = Some(other.proof_of_possession.clone());
+    assert!(identity.verify_node_id_result(&result).is_err());
+    assert!(verify_node_id_result(&identity.node_id, None, &result).is_ok());
+
+    result.node_pop = None;
+    assert!(identity.verify_node_id_result(&result).is_err());
+
+    // different node
+    result.node_id = other.node_id;
+    assert!(verify_node_id_result(&identity.node_id, None, &result).is_err());
+
+    // mismatched proof of the same public key
+    let mut forged = identity.proof_of_possession.clone();
+    forged.proof_of_possession = other.proof_of_possession.proof_of_possession.clone();
+    let result = GetNodeIdResult {
+        node_id: identity.node_id,
+        node_pop: Some(forged.clone()),
+    };
+    assert!(verify_node_id_result(&identity.node_id, Some(&forged), &result).is_err());
+}
@@ -0,0 +1,143 @@
+//! Signed IP payloads of the p2p handshake ("Version" and "PeerList").
+//! ref. <https://pkg.go.dev/github.com/luxfi/node/network/peer#SignedIP>
+use std::{
+    io::{self, Error, ErrorKind},
+    net::{IpAddr, Ipv6Addr},
+};
+
+use crate::packer;
+
+/// ref. "node/utils/wrappers.IPLen"
+pub const IP_LEN: usize = 16 + 2;
+
+/// IP and port of a node, claimed at the timestamp.
+/// ref. "node/network/peer.UnsignedIP"
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct UnsignedIp {
+    pub ip_addr: IpAddr,
+    pub ip_port: u16,
+    /// Unix time in seconds.
+    pub timestamp: u64,
+}
+
+impl UnsignedIp {
+    pub fn new(ip_addr: IpAddr, ip_port: u16, timestamp: u64) -> Self {
+        Self {
+            ip_addr,
+            ip_port,
+            timestamp,
+        }
+    }
+
+    /// Returns the bytes to be signed, where the IPv4 address is encoded
+    /// as the IPv4-mapped IPv6 address.
+    /// ref. "node/network/peer.UnsignedIP.bytes"
+    /// ref. "node/utils/ips.PackIP"
+    pub fn to_bytes(&self) -> io::Result<Vec<u8>> {
+        let ip = match self.ip_addr {
+            IpAddr::V4(v) => v.to_ipv6_mapped(),
+            IpAddr::V6(v) => v,
+        };
+
+        let packer = packer::Packer::new(IP_LEN + 8, IP_LEN + 8);
+        packer
+            .pack_bytes(&ip.octets())
+            .and_then(|_| packer.pack_u16(self.ip_port))
+            .and_then(|_| packer.pack_u64(self.timestamp))
+            .map_err(|e| Error::new(ErrorKind::Other, e.message()))?;
+        Ok(packer.take_bytes().to_vec())
+    }
+
+    /// Signs the IP with the staking TLS key.
+    /// ref. "node/network/peer.UnsignedIP.Sign"
+    pub fn sign(self, signer: &super::Signer) -> io::Result<SignedIp> {
+        let signature = signer.sign(&self.to_bytes()?)?;
+        Ok(SignedIp {
+            unsigned_ip: self,
+            signature,
+        })
+    }
+}
+
+/// IP signed by the staking TLS key of the node that claims it.
+/// ref. "node/network/peer.SignedIP"
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SignedIp {
+    pub unsigned_ip: UnsignedIp,
+    pub signature: Vec<u8>,
+}
+
+impl SignedIp {
+    /// Verifies the signature against the DER-encoded staking certificate.
+    /// ref. "node/network/peer.SignedIP.Verify"
+    pub fn verify(&self, cert_der: &[u8]) -> io::Result<()> {
+        super::verify(cert_der, &self.unsigned_ip.to_bytes()?, &self.signature)
+    }
+}
+
+/// Decodes the 16-byte IP address from the p2p messages.
+/// Only the IPv4-mapped addresses (i.e., "::ffff:a.b.c.d") are decoded as
+/// IPv4, as "net.IP.To4" does.
+pub fn ip_addr_from_bytes(b: &[u8]) -> io::Result<IpAddr> {
+    let octets: [u8; 16] = b.try_into().map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("invalid IP address length {} (expected 16)", b.len()),
+        )
+    })?;
+
+    let v6 = Ipv6Addr::from(octets);
+    Ok(match v6.to_ipv4_mapped() {
+        Some(v4) => IpAddr::V4(v4),
+        None => IpAddr::V6(v6),
+    })
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features staking -- staking::ip::test_signed_ip --exact --show-output
+#[test]
+fn test_signed_ip() {
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .is_test(true)
+        .try_init();
+
+    let ip = UnsignedIp::new("127.0.0.1".parse().unwrap(), 9651, 1234);
+    assert_eq!(
+        ip.to_bytes().unwrap(),
+        vec![
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff, 127, 0, 0, 1, // IPv4-mapped
+            0x25, 0xb3, // port
+            0, 0, 0, 0, 0, 0, 0x04, 0xd2, // timestamp
+        ]
+    );
+
+    let signer = super::Signer::from_pem_files(
+        "./artifacts/staker1.insecure.key",
+        "./artifacts/staker1.insecure.crt",
+    )
+    .unwrap();
+    let signed = ip.sign(&signer).unwrap();
+    signed.verify(signer.cert_der()).unwrap();
+
+    let mut moved = signed.clone();
+    moved.unsigned_ip.ip_port = 9650;
+    assert!(moved.verify(signer.cert_der()).is_err());
+
+    let other =
+        cert_manager::x509::load_pem_cert_to_der("./artifacts/staker2.insecure.crt").unwrap();
+    assert!(signed.verify(&other.0).is_err());
+
+    let mapped = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff, 127, 0, 0, 1];
+    let compat = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 127, 0, 0, 1];
+    assert_eq!(ip_addr_from_bytes(&mapped).unwrap(), ip.ip_addr);
+    // the deprecated IPv4-compatible address is a distinct IPv6 address
+    assert_eq!(
+        ip_addr_from_bytes(&compat).unwrap(),
+        IpAddr::V6(Ipv6Addr::from(compat))
+    );
+    assert_eq!(
+        ip_addr_from_bytes(&Ipv6Addr::LOCALHOST.octets()).unwrap(),
+        IpAddr::V6(Ipv6Addr::LOCALHOST)
+    );
+    assert!(ip_addr_from_bytes(&[127, 0, 0, 1]).is_err());
+}
@@ -0,0 +1,219 @@
+//! Staking TLS keys, used to sign and verify the p2p handshake payloads.
+//! ref. <https://pkg.go.dev/github.com/luxfi/node/staking>
+pub mod identity;
+pub mod ip;
+
+use std::io::{self, Error, ErrorKind};
+
+use crate::ids;
+use ring::{
+    rand::SystemRandom,
+    signature::{self, EcdsaKeyPair, KeyPair, RsaKeyPair},
+};
+use x509_parser::oid_registry::{OID_EC_P256, OID_KEY_TYPE_EC_PUBLIC_KEY, OID_PKCS1_RSAENCRYPTION};
+
+/// Private key of the staking TLS certificate.
+enum SigningKey {
+    Rsa(RsaKeyPair),
+    EcdsaP256(EcdsaKeyPair),
+}
+
+/// Signs messages with the staking TLS key, so that the peers can
+/// verify them against the staking certificate.
+/// ref. "node/staking.TLSSigner"
+pub struct Signer {
+    key: SigningKey,
+    cert_der: Vec<u8>,
+    rng: SystemRandom,
+}
+
+impl std::fmt::Debug for Signer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Signer")
+            .field("key_type", &self.key_type())
+            .field("node_id", &self.node_id().ok())
+            .finish()
+    }
+}
+
+impl Signer {
+    /// Loads the signer from the DER-encoded private key (PKCS#8, or
+    /// PKCS#1 for RSA) and certificate.
+    pub fn from_der(key_der: &[u8], cert_der: &[u8]) -> io::Result<Self> {
+        let key = if let Ok(kp) = RsaKeyPair::from_pkcs8(key_der) {
+            SigningKey::Rsa(kp)
+        } else if let Ok(kp) =
+            EcdsaKeyPair::from_pkcs8(&signature::ECDSA_P256_SHA256_ASN1_SIGNING, key_der)
+        {
+            SigningKey::EcdsaP256(kp)
+        } else {
+            let kp = RsaKeyPair::from_der(key_der).map_err(|e| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "unsupported staking key (neither RSA nor ECDSA P-256) '{}'",
+                        e
+                    ),
+                )
+            })?;
+            SigningKey::Rsa(kp)
+        };
+
+        let signer = Self {
+            key,
+            cert_der: cert_der.to_vec(),
+            rng: SystemRandom::new(),
+        };
+
+        // make sure the key matches the certificate
+        let public_key = signer.public_key_bytes();
+        let (_, cert) = parse_cert(cert_der)?;
+        if cert.public_key().subject_public_key.data.as_ref() != public_key.as_slice() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "staking key does not match the certificate",
+            ));
+        }
+        Ok(signer)
+    }
+
+    /// Loads the signer from the PEM-encoded key and certificate files.
+    pub fn from_pem_files(key_path: &str, cert_path: &str) -> io::Result<Self> {
+        let (key, cert) = cert_manager::x509::load_pem_key_cert_to_der(key_path, cert_path)?;
+        Self::from_der(&key.0, &cert.0)
+    }
+
+    /// Returns "rsa" or "ecdsa-p256".
+    pub fn key_type(&self) -> &'static str {
+        match self.key {
+            SigningKey::Rsa(_) => "rsa",
+            SigningKey::EcdsaP256(_) => "ecdsa-p256",
+        }
+    }
+
+    /// Returns the DER-encoded staking certificate.
+    pub fn cert_der(&self) -> &[u8] {
+        &self.cert_der
+    }
+
+    pub fn node_id(&self) -> io::Result<ids::node::Id> {
+        ids::node::Id::from_cert_der_bytes(&self.cert_der)
+    }
+
+    fn public_key_bytes(&self) -> Vec<u8> {
+        match &self.key {
+            SigningKey::Rsa(kp) => kp.public_key().as_ref().to_vec(),
+            SigningKey::EcdsaP256(kp) => kp.public_key().as_ref().to_vec(),
+        }
+    }
+
+    /// Signs the SHA256 digest of the message, with PKCS#1 v1.5 for RSA
+    /// keys or ASN.1-encoded ECDSA for P-256 keys.
+    /// ref. "crypto.Signer.Sign(rand.Reader, hashing.ComputeHash256(msg), crypto.SHA256)"
+    pub fn sign(&self, msg: &[u8]) -> io::Result<Vec<u8>> {
+        match &self.key {
+            SigningKey::Rsa(kp) => {
+                let mut sig = vec![0u8; kp.public_modulus_len()];
+                kp.sign(&signature::RSA_PKCS1_SHA256, &self.rng, msg, &mut sig)
+                    .map_err(|e| {
+                        Error::new(ErrorKind::Other, format!("failed RSA sign '{}'", e))
+                    })?;
+                Ok(sig)
+            }
+            SigningKey::EcdsaP256(kp) => {
+                let sig = kp.sign(&self.rng, msg).map_err(|e| {
+                    Error::new(ErrorKind::Other, format!("failed ECDSA sign '{}'", e))
+                })?;
+                Ok(sig.as_ref().to_vec())
+            }
+        }
+    }
+}
+
+/// Verifies the signature of the message against the public key of the
+/// DER-encoded peer certificate.
+/// ref. "node/staking.CheckSignature"
+pub fn verify(cert_der: &[u8], msg: &[u8], sig: &[u8]) -> io::Result<()> {
+    let (_, cert) = parse_cert(cert_der)?;
+    let spki = cert.public_key();
+    let public_key = spki.subject_public_key.data.as_ref();
+
+    let algo = &spki.algorithm.algorithm;
+    let verify_algo: &dyn signature::VerificationAlgorithm = if *algo == OID_PKCS1_RSAENCRYPTION {
+        &signature::RSA_PKCS1_2048_8192_SHA256
+    } else if *algo == OID_KEY_TYPE_EC_PUBLIC_KEY {
+        let curve = spki
+            .algorithm
+            .parameters
+            .as_ref()
+            .and_then(|p| p.as_oid().ok());
+        if curve != Some(OID_EC_P256) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("unsupported elliptic curve {:?}", curve),
+            ));
+        }
+        &signature::ECDSA_P256_SHA256_ASN1
+    } else {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("unsupported public key algorithm {}", algo),
+        ));
+    };
+
+    signature::UnparsedPublicKey::new(verify_algo, public_key)
+        .verify(msg, sig)
+        .map_err(|_| Error::new(ErrorKind::InvalidData, "invalid staking signature"))
+}
+
+fn parse_cert(
+    cert_der: &[u8],
+) -> io::Result<(&[u8], x509_parser::certificate::X509Certificate<'_>)> {
+    x509_parser::parse_x509_certificate(cert_der).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("failed parse_x509_certificate '{}'", e),
+        )
+    })
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features staking -- staking::test_signer --exact --show-output
+#[test]
+fn test_signer() {
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .is_test(true)
+        .try_init();
+
+    // RSA (PKCS#1)
+    let rsa = Signer::from_pem_files(
+        "./artifacts/staker1.insecure.key",
+        "./artifacts/staker1.insecure.crt",
+    )
+    .unwrap();
+    assert_eq!(rsa.key_type(), "rsa");
+    assert_eq!(
+        rsa.node_id().unwrap(),
+        ids::node::Id::from_cert_pem_file("./artifacts/staker1.insecure.crt").unwrap()
+    );
+    let sig = rsa.sign(b"hello").unwrap();
+    verify(rsa.cert_der(), b"hello", &sig).unwrap();
+    assert!(verify(rsa.cert_der(), b"world", &sig).is_err());
+
+    // ECDSA P-256 (PKCS#8)
+    let (key, cert) = cert_manager::x509::generate_der(Some(
+        cert_manager::x509::default_params(Some("PKCS_ECDSA_P256_SHA256".to_string()), None, false)
+            .unwrap(),
+    ))
+    .unwrap();
+    let ecdsa = Signer::from_der(&key.0, &cert.0).unwrap();
+    assert_eq!(ecdsa.key_type(), "ecdsa-p256");
+    let sig = ecdsa.sign(b"hello").unwrap();
+    verify(&cert.0, b"hello", &sig).unwrap();
+    assert!(verify(&cert.0, b"world", &sig).is_err());
+
+    // signed by other key
+    assert!(verify(rsa.cert_der(), b"hello", &sig).is_err());
+    // key does not match the certificate
+    assert!(Signer::from_der(&key.0, rsa.cert_der()).is_err());
+}
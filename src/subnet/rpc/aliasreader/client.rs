@@ -0,0 +1,66 @@
+use std::io::{Error, ErrorKind, Result};
+
+use crate::{
+    ids,
+    proto::pb::aliasreader::{alias_reader_client, Alias, Id},
+};
+use prost::bytes::Bytes;
+use tonic::transport::Channel;
+
+/// A gRPC client which resolves the aliases of the node.
+///
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/ids/galiasreader#Client>
+#[derive(Clone, Debug)]
+pub struct AliasReaderClient {
+    inner: alias_reader_client::AliasReaderClient<Channel>,
+}
+
+impl AliasReaderClient {
+    pub fn new(client_conn: Channel) -> Self {
+        Self {
+            inner: alias_reader_client::AliasReaderClient::new(client_conn),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl super::AliasReader for AliasReaderClient {
+    async fn lookup(&self, alias: &str) -> Result<ids::Id> {
+        let mut client = self.inner.clone();
+        let resp = client
+            .lookup(Alias {
+                alias: alias.to_string(),
+            })
+            .await
+            .map_err(|e| Error::new(ErrorKind::Other, format!("lookup failed: {e}")))?
+            .into_inner();
+
+        Ok(ids::Id::from_slice(&resp.id))
+    }
+
+    async fn primary_alias(&self, id: &ids::Id) -> Result<String> {
+        let mut client = self.inner.clone();
+        let resp = client
+            .primary_alias(Id {
+                id: Bytes::from(id.to_vec()),
+            })
+            .await
+            .map_err(|e| Error::new(ErrorKind::Other, format!("primary_alias failed: {e}")))?
+            .into_inner();
+
+        Ok(resp.alias)
+    }
+
+    async fn aliases(&self, id: &ids::Id) -> Result<Vec<String>> {
+        let mut client = self.inner.clone();
+        let resp = client
+            .aliases(Id {
+                id: Bytes::from(id.to_vec()),
+            })
+            .await
+            .map_err(|e| Error::new(ErrorKind::Other, format!("aliases failed: {e}")))?
+            .into_inner();
+
+        Ok(resp.aliases)
+    }
+}
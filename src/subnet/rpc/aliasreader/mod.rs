@@ -0,0 +1,122 @@
+//! Aliases of the chain IDs (e.g., "X" for the X-chain).
+//! ref. <https://pkg.go.dev/github.com/luxfi/node/ids/galiasreader>
+pub mod client;
+pub mod server;
+
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    io::{Error, ErrorKind, Result},
+    sync::{Arc, RwLock},
+};
+
+use crate::ids;
+
+/// Resolves the aliases of the IDs.
+///
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/ids#AliaserReader>
+#[tonic::async_trait]
+pub trait AliasReader: Debug + Send + Sync {
+    /// Returns the ID of the alias.
+    async fn lookup(&self, alias: &str) -> Result<ids::Id>;
+
+    /// Returns the first alias of the ID.
+    async fn primary_alias(&self, id: &ids::Id) -> Result<String>;
+
+    /// Returns all the aliases of the ID, in the order they were added.
+    async fn aliases(&self, id: &ids::Id) -> Result<Vec<String>>;
+}
+
+/// In-memory aliases, to unit test the VMs.
+///
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/ids#NewAliaser>
+#[derive(Debug, Clone, Default)]
+pub struct Aliaser {
+    inner: Arc<RwLock<AliaserInner>>,
+}
+
+#[derive(Debug, Default)]
+struct AliaserInner {
+    dealias: HashMap<String, ids::Id>,
+    aliases: HashMap<ids::Id, Vec<String>>,
+}
+
+impl Aliaser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the alias of the ID. Fails if the alias is already taken.
+    pub fn alias(&self, id: ids::Id, alias: &str) -> Result<()> {
+        let mut inner = self.inner.write().unwrap();
+        if inner.dealias.contains_key(alias) {
+            return Err(Error::new(
+                ErrorKind::AlreadyExists,
+                format!("{alias} is already used as an alias for an ID"),
+            ));
+        }
+        inner.dealias.insert(alias.to_string(), id);
+        inner.aliases.entry(id).or_default().push(alias.to_string());
+        Ok(())
+    }
+}
+
+#[tonic::async_trait]
+impl AliasReader for Aliaser {
+    async fn lookup(&self, alias: &str) -> Result<ids::Id> {
+        let inner = self.inner.read().unwrap();
+        inner.dealias.get(alias).copied().ok_or_else(|| {
+            Error::new(
+                ErrorKind::NotFound,
+                format!("there is no ID with alias {alias}"),
+            )
+        })
+    }
+
+    async fn primary_alias(&self, id: &ids::Id) -> Result<String> {
+        let inner = self.inner.read().unwrap();
+        inner
+            .aliases
+            .get(id)
+            .and_then(|aliases| aliases.first())
+            .cloned()
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::NotFound,
+                    format!("there is no alias for ID {id}"),
+                )
+            })
+    }
+
+    async fn aliases(&self, id: &ids::Id) -> Result<Vec<String>> {
+        let inner = self.inner.read().unwrap();
+        Ok(inner.aliases.get(id).cloned().unwrap_or_default())
+    }
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features subnet -- subnet::rpc::aliasreader::test_aliaser --exact --show-output
+#[tokio::test]
+async fn test_aliaser() {
+    let aliaser = Aliaser::new();
+    let x_chain_id = ids::Id::sha256("x");
+    aliaser.alias(x_chain_id, "X").unwrap();
+    aliaser.alias(x_chain_id, "xvm").unwrap();
+    assert_eq!(
+        aliaser.alias(ids::Id::empty(), "X").unwrap_err().kind(),
+        ErrorKind::AlreadyExists
+    );
+
+    assert_eq!(aliaser.lookup("xvm").await.unwrap(), x_chain_id);
+    assert_eq!(aliaser.primary_alias(&x_chain_id).await.unwrap(), "X");
+    assert_eq!(
+        aliaser.aliases(&x_chain_id).await.unwrap(),
+        vec!["X", "xvm"]
+    );
+
+    assert_eq!(
+        aliaser.lookup("P").await.unwrap_err().kind(),
+        ErrorKind::NotFound
+    );
+    assert!(aliaser.primary_alias(&ids::Id::empty()).await.is_err());
+    assert!(aliaser.aliases(&ids::Id::empty()).await.unwrap().is_empty());
+}
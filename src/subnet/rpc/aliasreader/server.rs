@@ -0,0 +1,55 @@
+use std::sync::Arc;
+
+use crate::{
+    ids,
+    proto::pb::{
+        self,
+        aliasreader::{Alias, AliasList, Id},
+    },
+};
+use prost::bytes::Bytes;
+use tonic::{Request, Response, Status};
+
+/// A gRPC server which wraps a subnet::rpc::aliasreader::AliasReader impl allowing client control over RPC.
+///
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/ids/galiasreader#Server>
+#[derive(Clone)]
+pub struct Server<A> {
+    inner: Arc<A>,
+}
+
+impl<A> Server<A>
+where
+    A: super::AliasReader + 'static,
+{
+    pub fn new(reader: A) -> Self {
+        Self {
+            inner: Arc::new(reader),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl<A> pb::aliasreader::alias_reader_server::AliasReader for Server<A>
+where
+    A: super::AliasReader + 'static,
+{
+    async fn lookup(&self, request: Request<Alias>) -> Result<Response<Id>, Status> {
+        let id = self.inner.lookup(&request.into_inner().alias).await?;
+        Ok(Response::new(Id {
+            id: Bytes::from(id.to_vec()),
+        }))
+    }
+
+    async fn primary_alias(&self, request: Request<Id>) -> Result<Response<Alias>, Status> {
+        let id = ids::Id::from_slice(&request.into_inner().id);
+        let alias = self.inner.primary_alias(&id).await?;
+        Ok(Response::new(Alias { alias }))
+    }
+
+    async fn aliases(&self, request: Request<Id>) -> Result<Response<AliasList>, Status> {
+        let id = ids::Id::from_slice(&request.into_inner().id);
+        let aliases = self.inner.aliases(&id).await?;
+        Ok(Response::new(AliasList { aliases }))
+    }
+}
@@ -0,0 +1,128 @@
+use std::{
+    collections::BTreeMap,
+    io::{Error, ErrorKind, Result},
+};
+
+use crate::{
+    ids,
+    proto::pb::sharedmemory::{
+        self, shared_memory_client, ApplyRequest, AtomicRequest, BatchDelete, BatchPut, GetRequest,
+        IndexedRequest,
+    },
+};
+use prost::bytes::Bytes;
+use tonic::transport::Channel;
+
+use super::{Batch, BatchOp, Indexed, Requests};
+
+/// A gRPC client which accesses the shared memory of the node.
+///
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/chains/atomic/gsharedmemory#Client>
+#[derive(Clone, Debug)]
+pub struct SharedMemoryClient {
+    inner: shared_memory_client::SharedMemoryClient<Channel>,
+}
+
+impl SharedMemoryClient {
+    pub fn new(client_conn: Channel) -> Self {
+        Self {
+            inner: shared_memory_client::SharedMemoryClient::new(client_conn),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl super::SharedMemory for SharedMemoryClient {
+    async fn get(&self, peer_chain_id: &ids::Id, keys: &[Vec<u8>]) -> Result<Vec<Vec<u8>>> {
+        let mut client = self.inner.clone();
+        let resp = client
+            .get(GetRequest {
+                peer_chain_id: Bytes::from(peer_chain_id.to_vec()),
+                keys: keys.iter().map(|k| Bytes::from(k.clone())).collect(),
+            })
+            .await
+            .map_err(|e| Error::new(ErrorKind::Other, format!("get failed: {e}")))?
+            .into_inner();
+
+        Ok(resp.values.into_iter().map(|v| v.to_vec()).collect())
+    }
+
+    async fn indexed(
+        &self,
+        peer_chain_id: &ids::Id,
+        traits: &[Vec<u8>],
+        start_trait: &[u8],
+        start_key: &[u8],
+        limit: usize,
+    ) -> Result<Indexed> {
+        let mut client = self.inner.clone();
+        let resp = client
+            .indexed(IndexedRequest {
+                peer_chain_id: Bytes::from(peer_chain_id.to_vec()),
+                traits: traits.iter().map(|t| Bytes::from(t.clone())).collect(),
+                start_trait: Bytes::copy_from_slice(start_trait),
+                start_key: Bytes::copy_from_slice(start_key),
+                limit: i32::try_from(limit).unwrap_or(i32::MAX),
+            })
+            .await
+            .map_err(|e| Error::new(ErrorKind::Other, format!("indexed failed: {e}")))?
+            .into_inner();
+
+        Ok(Indexed {
+            values: resp.values.into_iter().map(|v| v.to_vec()).collect(),
+            last_trait: resp.last_trait.to_vec(),
+            last_key: resp.last_key.to_vec(),
+        })
+    }
+
+    /// The node writes the puts of each batch before its deletes.
+    async fn apply(
+        &self,
+        requests: BTreeMap<ids::Id, Requests>,
+        batches: Vec<Batch>,
+    ) -> Result<()> {
+        let requests = requests
+            .into_iter()
+            .map(|(peer_chain_id, req)| AtomicRequest {
+                remove_requests: req.remove_requests.into_iter().map(Bytes::from).collect(),
+                put_requests: req
+                    .put_requests
+                    .into_iter()
+                    .map(|e| sharedmemory::Element {
+                        key: Bytes::from(e.key),
+                        value: Bytes::from(e.value),
+                        traits: e.traits.into_iter().map(Bytes::from).collect(),
+                    })
+                    .collect(),
+                peer_chain_id: Bytes::from(peer_chain_id.to_vec()),
+            })
+            .collect();
+
+        let batches = batches
+            .into_iter()
+            .map(|batch| {
+                let mut b = sharedmemory::Batch::default();
+                for op in batch.ops {
+                    match op {
+                        BatchOp::Put { key, value } => b.puts.push(BatchPut {
+                            key: Bytes::from(key),
+                            value: Bytes::from(value),
+                        }),
+                        BatchOp::Delete { key } => b.deletes.push(BatchDelete {
+                            key: Bytes::from(key),
+                        }),
+                    }
+                }
+                b
+            })
+            .collect();
+
+        let mut client = self.inner.clone();
+        client
+            .apply(ApplyRequest { requests, batches })
+            .await
+            .map_err(|e| Error::new(ErrorKind::Other, format!("apply failed: {e}")))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,350 @@
+//! In-memory shared memory, to unit test the atomic transfers of the VMs.
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    io::{Error, ErrorKind, Result},
+    sync::Arc,
+};
+
+use tokio::sync::RwLock;
+
+use crate::{
+    ids,
+    subnet::rpc::database::{memdb, BoxedDatabase},
+};
+
+use super::{Batch, BatchOp, Element, Indexed, Requests};
+
+/// Values one chain put for its peer chain.
+#[derive(Debug, Default)]
+struct State {
+    values: BTreeMap<Vec<u8>, Element>,
+    /// Keys removed by the peer chain before they were put, so that the
+    /// late puts are dropped.
+    removed: BTreeSet<Vec<u8>>,
+}
+
+/// Shared memory of all the chains, from which each chain gets its
+/// [`SharedMemory`](super::SharedMemory) by [`Memory::new_shared_memory`].
+///
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/chains/atomic#Memory>
+#[derive(Clone)]
+pub struct Memory {
+    /// Keyed by the chain that put the values, and the chain that reads them.
+    states: Arc<RwLock<HashMap<(ids::Id, ids::Id), State>>>,
+    db: BoxedDatabase,
+}
+
+impl Default for Memory {
+    fn default() -> Self {
+        Self::new(memdb::Database::new())
+    }
+}
+
+impl Memory {
+    /// Creates the shared memory whose batches are written to the database.
+    pub fn new(db: BoxedDatabase) -> Self {
+        Self {
+            states: Arc::new(RwLock::new(HashMap::new())),
+            db,
+        }
+    }
+
+    /// Returns the database the batches are written to.
+    pub fn database(&self) -> BoxedDatabase {
+        self.db.clone()
+    }
+
+    /// Returns the shared memory of the chain.
+    pub fn new_shared_memory(&self, chain_id: ids::Id) -> LocalSharedMemory {
+        LocalSharedMemory {
+            memory: self.clone(),
+            chain_id,
+        }
+    }
+}
+
+/// Shared memory of a chain backed by [`Memory`].
+#[derive(Clone)]
+pub struct LocalSharedMemory {
+    memory: Memory,
+    chain_id: ids::Id,
+}
+
+impl std::fmt::Debug for LocalSharedMemory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalSharedMemory")
+            .field("chain_id", &self.chain_id)
+            .finish()
+    }
+}
+
+#[tonic::async_trait]
+impl super::SharedMemory for LocalSharedMemory {
+    async fn get(&self, peer_chain_id: &ids::Id, keys: &[Vec<u8>]) -> Result<Vec<Vec<u8>>> {
+        let states = self.memory.states.read().await;
+        let state = states.get(&(*peer_chain_id, self.chain_id));
+        keys.iter()
+            .map(|key| {
+                state
+                    .and_then(|s| s.values.get(key))
+                    .map(|e| e.value.clone())
+                    .ok_or_else(|| Error::new(ErrorKind::NotFound, "not found"))
+            })
+            .collect()
+    }
+
+    async fn indexed(
+        &self,
+        peer_chain_id: &ids::Id,
+        traits: &[Vec<u8>],
+        start_trait: &[u8],
+        start_key: &[u8],
+        limit: usize,
+    ) -> Result<Indexed> {
+        let mut indexed = Indexed {
+            values: Vec::new(),
+            last_trait: start_trait.to_vec(),
+            last_key: start_key.to_vec(),
+        };
+
+        let states = self.memory.states.read().await;
+        let state = match states.get(&(*peer_chain_id, self.chain_id)) {
+            Some(state) => state,
+            None => return Ok(indexed),
+        };
+
+        let traits: BTreeSet<&Vec<u8>> = traits.iter().collect();
+        for t in traits.iter().copied() {
+            if indexed.values.len() >= limit {
+                break;
+            }
+            if t.as_slice() < start_trait {
+                // already paginated past the trait
+                continue;
+            }
+            let start_key = if t.as_slice() == start_trait {
+                start_key
+            } else {
+                &[]
+            };
+
+            for (key, e) in state.values.iter() {
+                if indexed.values.len() >= limit {
+                    break;
+                }
+                if (!start_key.is_empty() && key.as_slice() <= start_key) || !e.traits.contains(t) {
+                    continue;
+                }
+                // only returned for the first of its traits, across the pages
+                if traits
+                    .iter()
+                    .take_while(|prev| **prev < t)
+                    .any(|prev| e.traits.contains(prev))
+                {
+                    continue;
+                }
+                indexed.values.push(e.value.clone());
+                indexed.last_trait = t.clone();
+                indexed.last_key = key.clone();
+            }
+        }
+        Ok(indexed)
+    }
+
+    async fn apply(
+        &self,
+        requests: BTreeMap<ids::Id, Requests>,
+        batches: Vec<Batch>,
+    ) -> Result<()> {
+        let mut states = self.memory.states.write().await;
+
+        // validates all the requests before any write, to apply all or nothing
+        for (peer_chain_id, req) in requests.iter() {
+            let inbound = states.get(&(*peer_chain_id, self.chain_id));
+            let mut removed = HashSet::new();
+            for key in req.remove_requests.iter() {
+                // removing a key twice is rejected, as is removing a key
+                // that was already removed before it was put
+                if !removed.insert(key) || inbound.map_or(false, |s| s.removed.contains(key)) {
+                    return Err(duplicated_operation(peer_chain_id));
+                }
+            }
+
+            let outbound = states.get(&(self.chain_id, *peer_chain_id));
+            let mut put = HashSet::new();
+            for e in req.put_requests.iter() {
+                if !put.insert(&e.key) || outbound.map_or(false, |s| s.values.contains_key(&e.key))
+                {
+                    return Err(duplicated_operation(peer_chain_id));
+                }
+            }
+        }
+
+        let mut db = self.memory.db.clone();
+        for batch in batches {
+            for op in batch.ops {
+                match op {
+                    BatchOp::Put { key, value } => db.put(&key, &value).await?,
+                    BatchOp::Delete { key } => db.delete(&key).await?,
+                }
+            }
+        }
+
+        for (peer_chain_id, req) in requests {
+            let inbound = states.entry((peer_chain_id, self.chain_id)).or_default();
+            for key in req.remove_requests {
+                if inbound.values.remove(&key).is_none() {
+                    inbound.removed.insert(key);
+                }
+            }
+
+            let outbound = states.entry((self.chain_id, peer_chain_id)).or_default();
+            for e in req.put_requests {
+                if !outbound.removed.remove(&e.key) {
+                    outbound.values.insert(e.key.clone(), e);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the error of the operation repeated on a key of the peer chain.
+///
+/// ref. "chains/atomic.errDuplicatedOperation"
+fn duplicated_operation(peer_chain_id: &ids::Id) -> Error {
+    Error::new(
+        ErrorKind::AlreadyExists,
+        format!("duplicated operation on the peer chain {peer_chain_id}"),
+    )
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features subnet -- subnet::rpc::atomic::memory::test_shared_memory --exact --show-output
+#[tokio::test]
+async fn test_shared_memory() {
+    use super::SharedMemory;
+
+    let memory = Memory::default();
+    let x_chain_id = ids::Id::sha256("x");
+    let p_chain_id = ids::Id::sha256("p");
+    let x_chain = memory.new_shared_memory(x_chain_id);
+    let p_chain = memory.new_shared_memory(p_chain_id);
+
+    // X-chain exports to P-chain
+    let mut reqs = Requests::new();
+    reqs.put(b"utxo1", b"v1", vec![b"addr1".to_vec()])
+        .put(b"utxo2", b"v2", vec![b"addr1".to_vec(), b"addr2".to_vec()])
+        .put(b"utxo3", b"v3", vec![b"addr2".to_vec()]);
+    let mut batch = Batch::new();
+    batch.put(b"tx", b"accepted");
+    x_chain
+        .apply(BTreeMap::from([(p_chain_id, reqs)]), vec![batch])
+        .await
+        .unwrap();
+    assert_eq!(memory.database().get(b"tx").await.unwrap(), b"accepted");
+
+    assert_eq!(
+        p_chain
+            .get(&x_chain_id, &[b"utxo2".to_vec(), b"utxo1".to_vec()])
+            .await
+            .unwrap(),
+        vec![b"v2".to_vec(), b"v1".to_vec()]
+    );
+    // only readable by the peer chain
+    assert_eq!(
+        x_chain
+            .get(&p_chain_id, &[b"utxo1".to_vec()])
+            .await
+            .unwrap_err()
+            .kind(),
+        ErrorKind::NotFound
+    );
+
+    // paginates by trait then key, without duplicates
+    let traits = vec![b"addr2".to_vec(), b"addr1".to_vec()];
+    let page = p_chain
+        .indexed(&x_chain_id, &traits, &[], &[], 2)
+        .await
+        .unwrap();
+    assert_eq!(page.values, vec![b"v1".to_vec(), b"v2".to_vec()]);
+    assert_eq!(page.last_trait, b"addr1");
+    assert_eq!(page.last_key, b"utxo2");
+    let page = p_chain
+        .indexed(&x_chain_id, &traits, &page.last_trait, &page.last_key, 2)
+        .await
+        .unwrap();
+    assert_eq!(page.values, vec![b"v3".to_vec()]);
+
+    // duplicated put fails without writing anything
+    let mut reqs = Requests::new();
+    reqs.put(b"utxo4", b"v4", vec![])
+        .put(b"utxo1", b"v1", vec![]);
+    let err = x_chain
+        .apply(BTreeMap::from([(p_chain_id, reqs)]), vec![])
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+    assert!(p_chain
+        .get(&x_chain_id, &[b"utxo4".to_vec()])
+        .await
+        .is_err());
+
+    // duplicated keys within a request fail without writing anything
+    let mut reqs = Requests::new();
+    reqs.put(b"utxo4", b"v4", vec![])
+        .put(b"utxo4", b"v4", vec![]);
+    let mut batch = Batch::new();
+    batch.put(b"tx2", b"accepted");
+    let err = x_chain
+        .apply(BTreeMap::from([(p_chain_id, reqs)]), vec![batch])
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+    assert!(p_chain
+        .get(&x_chain_id, &[b"utxo4".to_vec()])
+        .await
+        .is_err());
+    assert!(memory.database().get(b"tx2").await.is_err());
+
+    let mut reqs = Requests::new();
+    reqs.remove(b"utxo1").remove(b"utxo1");
+    let err = p_chain
+        .apply(BTreeMap::from([(x_chain_id, reqs)]), vec![])
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+    assert!(p_chain.get(&x_chain_id, &[b"utxo1".to_vec()]).await.is_ok());
+
+    // P-chain imports
+    let mut reqs = Requests::new();
+    reqs.remove(b"utxo1").remove(b"utxo5");
+    p_chain
+        .apply(BTreeMap::from([(x_chain_id, reqs)]), vec![])
+        .await
+        .unwrap();
+    assert!(p_chain
+        .get(&x_chain_id, &[b"utxo1".to_vec()])
+        .await
+        .is_err());
+
+    // a key removed before it was put can not be removed again
+    let mut reqs = Requests::new();
+    reqs.remove(b"utxo5");
+    let err = p_chain
+        .apply(BTreeMap::from([(x_chain_id, reqs)]), vec![])
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+
+    // removed before put
+    let mut reqs = Requests::new();
+    reqs.put(b"utxo5", b"v5", vec![]);
+    x_chain
+        .apply(BTreeMap::from([(p_chain_id, reqs)]), vec![])
+        .await
+        .unwrap();
+    assert!(p_chain
+        .get(&x_chain_id, &[b"utxo5".to_vec()])
+        .await
+        .is_err());
+}
@@ -0,0 +1,124 @@
+//! Shared memory between the chains, for the atomic (cross-chain) transfers.
+//! ref. <https://pkg.go.dev/github.com/luxfi/node/chains/atomic>
+pub mod client;
+pub mod memory;
+pub mod server;
+
+use std::{collections::BTreeMap, fmt::Debug, io::Result};
+
+use crate::ids;
+
+/// Value written to the shared memory of the peer chain, indexed by its
+/// traits (e.g., the addresses that can spend the UTXO).
+///
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/chains/atomic#Element>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Element {
+    pub key: Vec<u8>,
+    pub value: Vec<u8>,
+    pub traits: Vec<Vec<u8>>,
+}
+
+/// Atomic operations against the shared memory with a peer chain.
+///
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/chains/atomic#Requests>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Requests {
+    /// Keys of the values the peer chain put for this chain, to be removed
+    /// (e.g., the imported UTXOs).
+    pub remove_requests: Vec<Vec<u8>>,
+    /// Values to be put for the peer chain (e.g., the exported UTXOs).
+    pub put_requests: Vec<Element>,
+}
+
+impl Requests {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Puts the value for the peer chain.
+    pub fn put(&mut self, key: &[u8], value: &[u8], traits: Vec<Vec<u8>>) -> &mut Self {
+        self.put_requests.push(Element {
+            key: key.to_vec(),
+            value: value.to_vec(),
+            traits,
+        });
+        self
+    }
+
+    /// Removes the value the peer chain put for this chain.
+    pub fn remove(&mut self, key: &[u8]) -> &mut Self {
+        self.remove_requests.push(key.to_vec());
+        self
+    }
+}
+
+/// Database operation written atomically with the shared memory requests.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchOp {
+    Put { key: Vec<u8>, value: Vec<u8> },
+    Delete { key: Vec<u8> },
+}
+
+/// Writes to the chain database, applied in the order they were written and
+/// atomically with the shared memory requests (e.g., to mark the atomic
+/// transaction accepted).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Batch {
+    pub ops: Vec<BatchOp>,
+}
+
+impl Batch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn put(&mut self, key: &[u8], value: &[u8]) -> &mut Self {
+        self.ops.push(BatchOp::Put {
+            key: key.to_vec(),
+            value: value.to_vec(),
+        });
+        self
+    }
+
+    pub fn delete(&mut self, key: &[u8]) -> &mut Self {
+        self.ops.push(BatchOp::Delete { key: key.to_vec() });
+        self
+    }
+}
+
+/// Values returned by [`SharedMemory::indexed`], with the position to resume
+/// the pagination from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Indexed {
+    pub values: Vec<Vec<u8>>,
+    pub last_trait: Vec<u8>,
+    pub last_key: Vec<u8>,
+}
+
+/// Shared memory of the chain with its peer chains.
+///
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/chains/atomic#SharedMemory>
+#[tonic::async_trait]
+pub trait SharedMemory: Debug + Send + Sync {
+    /// Returns the values the peer chain put for this chain, in the order of
+    /// the keys. Fails if any of the keys is not found.
+    async fn get(&self, peer_chain_id: &ids::Id, keys: &[Vec<u8>]) -> Result<Vec<Vec<u8>>>;
+
+    /// Returns up to "limit" values the peer chain put for this chain that
+    /// have any of the traits, ordered by trait and then key, starting after
+    /// "start_trait" and "start_key" (empty to start from the beginning).
+    async fn indexed(
+        &self,
+        peer_chain_id: &ids::Id,
+        traits: &[Vec<u8>],
+        start_trait: &[u8],
+        start_key: &[u8],
+        limit: usize,
+    ) -> Result<Indexed>;
+
+    /// Applies the requests keyed by the peer chain ID and writes the batches,
+    /// all atomically.
+    async fn apply(&self, requests: BTreeMap<ids::Id, Requests>, batches: Vec<Batch>)
+        -> Result<()>;
+}
@@ -0,0 +1,114 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use crate::{
+    ids,
+    proto::pb::{
+        self,
+        sharedmemory::{
+            ApplyRequest, ApplyResponse, GetRequest, GetResponse, IndexedRequest, IndexedResponse,
+        },
+    },
+};
+use prost::bytes::Bytes;
+use tonic::{Request, Response, Status};
+
+use super::{Batch, Element, Requests};
+
+/// A gRPC server which wraps a subnet::rpc::atomic::SharedMemory impl allowing client control over RPC.
+///
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/chains/atomic/gsharedmemory#Server>
+#[derive(Clone)]
+pub struct Server<M> {
+    inner: Arc<M>,
+}
+
+impl<M> Server<M>
+where
+    M: super::SharedMemory + 'static,
+{
+    pub fn new(shared_memory: M) -> Self {
+        Self {
+            inner: Arc::new(shared_memory),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl<M> pb::sharedmemory::shared_memory_server::SharedMemory for Server<M>
+where
+    M: super::SharedMemory + 'static,
+{
+    async fn get(&self, request: Request<GetRequest>) -> Result<Response<GetResponse>, Status> {
+        let req = request.into_inner();
+        let keys: Vec<Vec<u8>> = req.keys.iter().map(|k| k.to_vec()).collect();
+        let values = self
+            .inner
+            .get(&ids::Id::from_slice(&req.peer_chain_id), &keys)
+            .await?;
+        Ok(Response::new(GetResponse {
+            values: values.into_iter().map(Bytes::from).collect(),
+        }))
+    }
+
+    async fn indexed(
+        &self,
+        request: Request<IndexedRequest>,
+    ) -> Result<Response<IndexedResponse>, Status> {
+        let req = request.into_inner();
+        let traits: Vec<Vec<u8>> = req.traits.iter().map(|t| t.to_vec()).collect();
+        let indexed = self
+            .inner
+            .indexed(
+                &ids::Id::from_slice(&req.peer_chain_id),
+                &traits,
+                &req.start_trait,
+                &req.start_key,
+                usize::try_from(req.limit).unwrap_or_default(),
+            )
+            .await?;
+        Ok(Response::new(IndexedResponse {
+            values: indexed.values.into_iter().map(Bytes::from).collect(),
+            last_trait: Bytes::from(indexed.last_trait),
+            last_key: Bytes::from(indexed.last_key),
+        }))
+    }
+
+    async fn apply(
+        &self,
+        request: Request<ApplyRequest>,
+    ) -> Result<Response<ApplyResponse>, Status> {
+        let req = request.into_inner();
+        let mut requests: BTreeMap<ids::Id, Requests> = BTreeMap::new();
+        for r in req.requests {
+            let reqs = requests
+                .entry(ids::Id::from_slice(&r.peer_chain_id))
+                .or_default();
+            reqs.remove_requests
+                .extend(r.remove_requests.iter().map(|k| k.to_vec()));
+            reqs.put_requests
+                .extend(r.put_requests.into_iter().map(|e| Element {
+                    key: e.key.to_vec(),
+                    value: e.value.to_vec(),
+                    traits: e.traits.iter().map(|t| t.to_vec()).collect(),
+                }));
+        }
+
+        let batches = req
+            .batches
+            .into_iter()
+            .map(|b| {
+                let mut batch = Batch::new();
+                for put in b.puts.iter() {
+                    batch.put(&put.key, &put.value);
+                }
+                for delete in b.deletes.iter() {
+                    batch.delete(&delete.key);
+                }
+                batch
+            })
+            .collect();
+
+        self.inner.apply(requests, batches).await?;
+        Ok(Response::new(ApplyResponse {}))
+    }
+}
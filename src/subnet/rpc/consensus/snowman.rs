@@ -1,6 +1,6 @@
 use std::io::Result;
 
-use crate::{choices::status::Status, ids::Id};
+use crate::{choices::status::Status, ids::Id, subnet::rpc::snowman::block::WithVerifyContext};
 
 /// ref. <https://pkg.go.dev/github.com/luxfi/node/snow/consensus/snowman#Block>
 #[tonic::async_trait]
@@ -25,6 +25,13 @@ pub trait Block: Decidable + Send + Sync {
 
     /// Returns error if the block can not be verified.
     async fn verify(&mut self) -> Result<()>;
+
+    /// Returns the capability of this block to verify itself with the
+    /// P-Chain height, or None if the block is always verified with
+    /// [`verify`](Block::verify).
+    fn with_verify_context(&self) -> Option<&dyn WithVerifyContext> {
+        None
+    }
 }
 
 /// ref. <https://pkg.go.dev/github.com/luxfi/node/snow/choices#Decidable>
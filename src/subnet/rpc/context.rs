@@ -1,16 +1,11 @@
 //! Snow Context.
-use crate::{
-    ids::node::Id as NodeId,
-    ids::Id,
-    proto::pb::{
-        aliasreader::alias_reader_client::AliasReaderClient,
-        keystore::keystore_client::KeystoreClient,
-        sharedmemory::shared_memory_client::SharedMemoryClient,
-    },
-};
-use tonic::transport::Channel;
+use std::sync::Arc;
+
+use crate::{ids::node::Id as NodeId, ids::Id};
 
-use super::snow::validators;
+use super::{
+    aliasreader::AliasReader, atomic::SharedMemory, keystore::Keystore, snow::validators, warp,
+};
 
 /// ref. <https://pkg.go.dev/github.com/luxfi/node/snow#Context>
 #[derive(Debug, Clone)]
@@ -22,10 +17,15 @@ pub struct Context<S: validators::State> {
     pub x_chain_id: Id,
     pub c_chain_id: Id,
     pub lux_asset_id: Id,
-    pub keystore: KeystoreClient<Channel>,
-    pub shared_memory: SharedMemoryClient<Channel>,
-    pub bc_lookup: AliasReaderClient<Channel>,
+    pub keystore: Arc<dyn Keystore>,
+    pub shared_memory: Arc<dyn SharedMemory>,
+    pub bc_lookup: Arc<dyn AliasReader>,
     pub chain_data_dir: String,
     pub validator_state: S,
-    // TODO metrics
+    pub warp_signer: Arc<dyn warp::Signer>,
+    /// Registry of the VM metrics, gathered by the node along with the
+    /// process metrics.
+    #[cfg(feature = "subnet_metrics")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subnet_metrics")))]
+    pub metrics: prometheus::Registry,
 }
@@ -72,6 +72,21 @@ pub fn from_i32(err: i32) -> io::Result<()> {
     }
 }
 
+/// Returns the error code of the common error, or "None" if the error is
+/// not one of them.
+pub fn to_i32(error: &io::Error) -> Option<i32> {
+    [
+        Error::DatabaseClosed,
+        Error::NotFound,
+        Error::HeightIndexedVMNotImplemented,
+        Error::IndexIncomplete,
+        Error::StateSyncableVMNotImplemented,
+    ]
+    .iter()
+    .find(|e| error.to_string() == e.as_str())
+    .map(|e| e.to_i32())
+}
+
 /// Accepts an error and returns a corruption error if the original error is not "database closed"
 /// or "not found".
 pub fn is_corruptible(error: &io::Error) -> bool {
@@ -100,3 +115,19 @@ pub fn from_status(status: Status) -> io::Error {
         _ => io::Error::new(io::ErrorKind::Other, status.message()),
     }
 }
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features subnet -- subnet::rpc::errors::test_to_i32 --exact --show-output
+#[test]
+fn test_to_i32() {
+    for e in [
+        Error::DatabaseClosed,
+        Error::NotFound,
+        Error::HeightIndexedVMNotImplemented,
+        Error::IndexIncomplete,
+        Error::StateSyncableVMNotImplemented,
+    ] {
+        assert_eq!(to_i32(&e.to_err()), Some(e.to_i32()));
+        assert!(from_i32(e.to_i32()).is_err());
+    }
+    assert_eq!(to_i32(&from_string("ohh snap!".to_string())), None);
+}
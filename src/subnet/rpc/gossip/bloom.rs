@@ -0,0 +1,134 @@
+//! Bloom filter of the IDs a node already has, which the node sends in the
+//! pull gossip requests so that the peers only respond with the missing items.
+use std::io::{Error, ErrorKind, Result};
+
+use crate::ids::{self, Id};
+
+/// Maximum number of hash functions a filter may use.
+pub const MAX_HASHES: u8 = 16;
+
+/// Length of the marshalled filter header: the number of hashes and the salt.
+const HEADER_LEN: usize = 1 + ids::LEN;
+
+/// Salted bloom filter over the [`Id`]s, whose salt is random per filter so
+/// that the same false positives do not repeat across the requests.
+///
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/utils/bloom>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    hashes: u8,
+    salt: Id,
+    bits: Vec<u8>,
+}
+
+impl Filter {
+    /// Creates the filter sized for the number of items at the false
+    /// positive probability (e.g., 0.01 for 1%).
+    pub fn new(max_items: usize, false_positive_probability: f64) -> Self {
+        let n = max_items.max(1) as f64;
+        let p = false_positive_probability.clamp(f64::MIN_POSITIVE, 0.5);
+        let ln2 = std::f64::consts::LN_2;
+
+        let num_bits = (-n * p.ln() / (ln2 * ln2)).ceil().max(8.0) as usize;
+        let hashes = ((num_bits as f64 / n) * ln2).round() as u8;
+        Self {
+            hashes: hashes.clamp(1, MAX_HASHES),
+            salt: Id::from_slice(&rand::random::<[u8; ids::LEN]>()),
+            bits: vec![0; (num_bits + 7) / 8],
+        }
+    }
+
+    pub fn add(&mut self, id: &Id) {
+        for i in self.indices(id) {
+            self.bits[i / 8] |= 1 << (i % 8);
+        }
+    }
+
+    /// Returns true if the ID may have been added, or false if it was not.
+    pub fn contains(&self, id: &Id) -> bool {
+        self.indices(id)
+            .into_iter()
+            .all(|i| self.bits[i / 8] & (1 << (i % 8)) != 0)
+    }
+
+    /// Returns the bit positions of the ID, each from its own 4 bytes of
+    /// the hashes of the salted ID, so that the positions are independent
+    /// even in the small filters.
+    fn indices(&self, id: &Id) -> Vec<usize> {
+        let num_bits = (self.bits.len() * 8) as u64;
+        let hashes = self.hashes as usize;
+        let mut salted = self.salt.to_vec();
+        salted.extend_from_slice(id.as_ref());
+        salted.push(0);
+
+        let mut digest = Vec::with_capacity(hashes * 4 + ids::LEN);
+        while digest.len() < hashes * 4 {
+            *salted.last_mut().unwrap() = (digest.len() / ids::LEN) as u8;
+            digest.extend_from_slice(Id::sha256(&salted).as_ref());
+        }
+        digest
+            .chunks_exact(4)
+            .take(hashes)
+            .map(|b| (u32::from_be_bytes(b.try_into().unwrap()) as u64 % num_bits) as usize)
+            .collect()
+    }
+
+    /// Marshals the filter as the number of hashes, the salt and the bits.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut b = Vec::with_capacity(HEADER_LEN + self.bits.len());
+        b.push(self.hashes);
+        b.extend_from_slice(self.salt.as_ref());
+        b.extend_from_slice(&self.bits);
+        b
+    }
+
+    pub fn from_bytes(b: &[u8]) -> Result<Self> {
+        if b.len() <= HEADER_LEN {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("bloom filter too short ({} bytes)", b.len()),
+            ));
+        }
+        if b[0] == 0 || b[0] > MAX_HASHES {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("invalid number of bloom filter hashes {}", b[0]),
+            ));
+        }
+        Ok(Self {
+            hashes: b[0],
+            salt: Id::from_slice(&b[1..HEADER_LEN]),
+            bits: b[HEADER_LEN..].to_vec(),
+        })
+    }
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features subnet -- subnet::rpc::gossip::bloom::test_filter --exact --show-output
+#[test]
+fn test_filter() {
+    let mut filter = Filter::new(1000, 0.01);
+    let ids: Vec<Id> = (0..1000u32).map(|i| Id::sha256(i.to_be_bytes())).collect();
+    for id in ids.iter() {
+        filter.add(id);
+    }
+    assert!(ids.iter().all(|id| filter.contains(id)));
+
+    let false_positives = (1000..11000u32)
+        .filter(|i| filter.contains(&Id::sha256(i.to_be_bytes())))
+        .count();
+    assert!(false_positives < 300, "{false_positives} false positives");
+
+    // the positions do not collapse in the small filters
+    let mut small = Filter::new(1, 1e-9);
+    small.add(&ids[0]);
+    assert!(ids[1..].iter().all(|id| !small.contains(id)));
+
+    let parsed = Filter::from_bytes(&filter.to_bytes()).unwrap();
+    assert_eq!(parsed, filter);
+    assert!(ids.iter().all(|id| parsed.contains(id)));
+
+    assert!(Filter::from_bytes(&[1; HEADER_LEN]).is_err());
+    let mut b = filter.to_bytes();
+    b[0] = MAX_HASHES + 1;
+    assert!(Filter::from_bytes(&b).is_err());
+}
@@ -0,0 +1,710 @@
+//! Push and pull gossip of the items (e.g., transactions) between the VMs,
+//! either over the [`AppSender`] and [`NetworkAppHandler`] of the VM, or as
+//! a [`p2p::Handler`] of the [`p2p::Network`] next to the other protocols.
+//!
+//! The VM pushes its new items to the network with [`Gossiper::add_local`],
+//! and periodically pulls the items it misses from its peers with
+//! [`Gossiper::pull`], by sending a bloom filter of the items it has.
+//!
+//! ref. <https://pkg.go.dev/github.com/luxfi/node/network/p2p/gossip>
+pub mod bloom;
+
+use std::{
+    collections::HashMap,
+    io::{self, Error, ErrorKind},
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicU32, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use crate::{
+    ids,
+    packer::Packer,
+    subnet::rpc::{
+        p2p,
+        snow::engine::common::{appsender::AppSender, engine::NetworkAppHandler, vm::Connector},
+    },
+};
+use chrono::{DateTime, Utc};
+use lru::LruCache;
+
+/// Item which is gossiped between the peers.
+pub trait Gossipable: Clone + Send + Sync + 'static {
+    fn gossip_id(&self) -> ids::Id;
+
+    fn bytes(&self) -> &[u8];
+
+    fn parse(bytes: &[u8]) -> io::Result<Self>;
+}
+
+/// Set of the gossiped items the node has (e.g., the mempool).
+pub trait Set: Send + Sync {
+    type Item: Gossipable;
+
+    fn add(&self, item: Self::Item) -> io::Result<()>;
+
+    fn has(&self, id: &ids::Id) -> bool;
+
+    /// Calls the function on the items until it returns false.
+    fn iterate(&self, f: &mut dyn FnMut(&Self::Item) -> bool);
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Message between the gossipers of the peers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    /// Items pushed to the peers.
+    Push(Vec<Vec<u8>>),
+    /// Marshalled [`bloom::Filter`] of the items the requester has.
+    PullRequest(Vec<u8>),
+    /// Items the requester does not have.
+    PullResponse(Vec<Vec<u8>>),
+}
+
+const PUSH: u8 = 0;
+const PULL_REQUEST: u8 = 1;
+const PULL_RESPONSE: u8 = 2;
+
+/// Maximum size of the message to unpack.
+const MAX_MESSAGE_SIZE: usize = 2 * 1024 * 1024;
+
+impl Message {
+    /// Marshals the message as the type byte followed by its items.
+    pub fn to_bytes(&self) -> io::Result<Vec<u8>> {
+        let packer = Packer::new(MAX_MESSAGE_SIZE, 0);
+        match self {
+            Message::Push(items) => packer
+                .pack_byte(PUSH)
+                .and_then(|_| packer.pack_2d_bytes_with_header(items.clone())),
+            Message::PullRequest(filter) => packer
+                .pack_byte(PULL_REQUEST)
+                .and_then(|_| packer.pack_bytes_with_header(filter)),
+            Message::PullResponse(items) => packer
+                .pack_byte(PULL_RESPONSE)
+                .and_then(|_| packer.pack_2d_bytes_with_header(items.clone())),
+        }
+        .map_err(|e| Error::new(ErrorKind::InvalidInput, e.message()))?;
+        Ok(packer.take_bytes().to_vec())
+    }
+
+    pub fn from_bytes(b: &[u8]) -> io::Result<Self> {
+        let packer = Packer::load_bytes_for_unpack(MAX_MESSAGE_SIZE, b);
+        let msg = match packer.unpack_byte() {
+            Ok(PUSH) => packer.unpack_2d_bytes_with_header().map(Message::Push),
+            Ok(PULL_REQUEST) => packer.unpack_bytes_with_header().map(Message::PullRequest),
+            Ok(PULL_RESPONSE) => packer
+                .unpack_2d_bytes_with_header()
+                .map(Message::PullResponse),
+            Ok(typ) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("unknown gossip message type {typ}"),
+                ))
+            }
+            Err(e) => Err(e),
+        }
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e.message()))?;
+        if packer.get_offset() != b.len() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "trailing bytes in gossip message",
+            ));
+        }
+        Ok(msg)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Config {
+    /// Maximum total size of the items in a push message or pull response.
+    pub max_message_bytes: usize,
+    /// False positive probability of the bloom filter of the pull requests.
+    pub false_positive_probability: f64,
+    /// Number of the item IDs to remember per peer, as seen by the peer.
+    pub seen_cache_size: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            max_message_bytes: 512 * 1024,
+            false_positive_probability: 0.01,
+            seen_cache_size: 4096,
+        }
+    }
+}
+
+/// Sends the messages of the [`Gossiper`].
+enum Sender {
+    /// Sends over the [`AppSender`] of the VM, whose responses are handled
+    /// by the [`NetworkAppHandler`] of the gossiper.
+    AppSender(Box<dyn AppSender + Send + Sync>),
+    /// Sends to the gossip handler on the peers, whose responses are
+    /// routed back by the [`p2p::Network`].
+    Client(p2p::Client),
+}
+
+/// Pushes and pulls the items of the set, and handles the gossip of the
+/// peers. The VM delegates its [`Connector`] calls to the gossiper, and
+/// either its [`NetworkAppHandler`] calls, or registers the gossiper as the
+/// [`p2p::Handler`] of the client the gossiper is created with.
+pub struct Gossiper<S: Set> {
+    set: Arc<S>,
+    sender: Sender,
+    config: Config,
+
+    /// Connected peers to pull from, in turns.
+    peers: Mutex<Vec<ids::node::Id>>,
+    next_peer: AtomicUsize,
+    /// Item IDs each peer is known to have, which are not sent to the peer.
+    seen: Mutex<HashMap<ids::node::Id, LruCache<ids::Id, ()>>>,
+
+    request_id: AtomicU32,
+    /// Peers of the outstanding pull requests.
+    requests: Mutex<HashMap<u32, ids::node::Id>>,
+}
+
+impl<S: Set> Gossiper<S> {
+    pub fn new(set: Arc<S>, app_sender: Box<dyn AppSender + Send + Sync>, config: Config) -> Self {
+        Self::with_sender(set, Sender::AppSender(app_sender), config)
+    }
+
+    /// Creates the gossiper which sends with the client, so that it shares
+    /// the [`AppSender`] of the VM with the other protocols. The gossiper
+    /// must be registered as the handler of the client's handler ID.
+    pub fn with_client(set: Arc<S>, client: p2p::Client, config: Config) -> Self {
+        Self::with_sender(set, Sender::Client(client), config)
+    }
+
+    fn with_sender(set: Arc<S>, sender: Sender, config: Config) -> Self {
+        Self {
+            set,
+            sender,
+            config,
+            peers: Mutex::new(Vec::new()),
+            next_peer: AtomicUsize::new(0),
+            seen: Mutex::new(HashMap::new()),
+            request_id: AtomicU32::new(0),
+            requests: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn set(&self) -> &Arc<S> {
+        &self.set
+    }
+
+    /// Adds the item issued locally to the set, and pushes it to the peers.
+    pub async fn add_local(&self, item: S::Item) -> io::Result<()> {
+        self.set.add(item.clone())?;
+        self.push(&[item]).await
+    }
+
+    /// Pushes the items to all the peers, in as many messages as needed.
+    pub async fn push(&self, items: &[S::Item]) -> io::Result<()> {
+        for batch in self.batches(items.iter().map(|item| item.bytes().to_vec())) {
+            let msg = Message::Push(batch).to_bytes()?;
+            match &self.sender {
+                Sender::AppSender(app_sender) => app_sender.send_app_gossip(msg).await?,
+                Sender::Client(client) => client.send_gossip(&msg).await?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Requests the items the node misses from the next connected peer.
+    /// Returns false if no peer is connected. With the client, the items
+    /// are added once the peer responds, or an error is returned if the
+    /// request fails or times out.
+    pub async fn pull(&self) -> io::Result<bool> {
+        let peer = {
+            let peers = self.peers.lock().unwrap();
+            if peers.is_empty() {
+                return Ok(false);
+            }
+            peers[self.next_peer.fetch_add(1, Ordering::Relaxed) % peers.len()]
+        };
+
+        let mut filter = bloom::Filter::new(self.set.len(), self.config.false_positive_probability);
+        self.set.iterate(&mut |item| {
+            filter.add(&item.gossip_id());
+            true
+        });
+        let msg = Message::PullRequest(filter.to_bytes()).to_bytes()?;
+
+        let app_sender = match &self.sender {
+            Sender::AppSender(app_sender) => app_sender,
+            Sender::Client(client) => {
+                let response = client.send_request(peer, &msg).await?;
+                self.handle_pull_response(&peer, &response);
+                return Ok(true);
+            }
+        };
+        let request_id = self.request_id.fetch_add(1, Ordering::Relaxed);
+        self.requests.lock().unwrap().insert(request_id, peer);
+        let node_ids = ids::node::Set::from([peer]);
+        if let Err(e) = app_sender.send_app_request(node_ids, request_id, msg).await {
+            self.requests.lock().unwrap().remove(&request_id);
+            return Err(e);
+        }
+        Ok(true)
+    }
+
+    /// Splits the items into the batches within the message size limit.
+    fn batches(&self, items: impl Iterator<Item = Vec<u8>>) -> Vec<Vec<Vec<u8>>> {
+        let mut batches = Vec::new();
+        let mut batch = Vec::new();
+        let mut size = 0;
+        for item in items {
+            if !batch.is_empty() && size + item.len() > self.config.max_message_bytes {
+                batches.push(std::mem::take(&mut batch));
+                size = 0;
+            }
+            size += item.len();
+            batch.push(item);
+        }
+        if !batch.is_empty() {
+            batches.push(batch);
+        }
+        batches
+    }
+
+    /// Records that the peer has the item.
+    fn mark_seen(&self, node_id: &ids::node::Id, id: ids::Id) {
+        let mut seen = self.seen.lock().unwrap();
+        seen.entry(*node_id)
+            .or_insert_with(|| {
+                LruCache::new(NonZeroUsize::new(self.config.seen_cache_size.max(1)).unwrap())
+            })
+            .put(id, ());
+    }
+
+    fn has_seen(&self, node_id: &ids::node::Id, id: &ids::Id) -> bool {
+        self.seen
+            .lock()
+            .unwrap()
+            .get(node_id)
+            .map_or(false, |seen| seen.contains(id))
+    }
+
+    /// Adds the items of the peer to the set. The invalid items and the
+    /// items the set rejects are dropped.
+    fn add_remote(&self, node_id: &ids::node::Id, items: Vec<Vec<u8>>) {
+        for b in items {
+            let item = match S::Item::parse(&b) {
+                Ok(item) => item,
+                Err(e) => {
+                    log::debug!("dropping invalid gossip from {node_id}: {e}");
+                    continue;
+                }
+            };
+            let id = item.gossip_id();
+            self.mark_seen(node_id, id);
+            if self.set.has(&id) {
+                continue;
+            }
+            if let Err(e) = self.set.add(item) {
+                log::debug!("dropping gossiped item {id} from {node_id}: {e}");
+            }
+        }
+    }
+
+    /// Returns the pull response of the items which are not in the bloom
+    /// filter of the peer's pull request.
+    fn pull_response(&self, node_id: &ids::node::Id, request: &[u8]) -> io::Result<Vec<u8>> {
+        let filter = match Message::from_bytes(request)? {
+            Message::PullRequest(filter) => bloom::Filter::from_bytes(&filter)?,
+            msg => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("unexpected request {msg:?}"),
+                ))
+            }
+        };
+
+        let mut items = Vec::new();
+        let mut size = 0;
+        self.set.iterate(&mut |item| {
+            let id = item.gossip_id();
+            if filter.contains(&id) || self.has_seen(node_id, &id) {
+                return true;
+            }
+            if size + item.bytes().len() > self.config.max_message_bytes {
+                return false;
+            }
+            size += item.bytes().len();
+            items.push((id, item.bytes().to_vec()));
+            true
+        });
+        let mut response = Vec::with_capacity(items.len());
+        for (id, b) in items {
+            self.mark_seen(node_id, id);
+            response.push(b);
+        }
+
+        Message::PullResponse(response).to_bytes()
+    }
+
+    /// Adds the items of the peer's pull response to the set.
+    fn handle_pull_response(&self, node_id: &ids::node::Id, response: &[u8]) {
+        match Message::from_bytes(response) {
+            Ok(Message::PullResponse(items)) => self.add_remote(node_id, items),
+            Ok(msg) => log::debug!("dropping unexpected response {msg:?} from {node_id}"),
+            Err(e) => log::debug!("dropping response from {node_id}: {e}"),
+        }
+    }
+
+    /// Adds the items the peer pushed to the set.
+    fn handle_push(&self, node_id: &ids::node::Id, msg: &[u8]) {
+        match Message::from_bytes(msg) {
+            Ok(Message::Push(items)) => self.add_remote(node_id, items),
+            Ok(msg) => log::debug!("dropping unexpected gossip {msg:?} from {node_id}"),
+            Err(e) => log::debug!("dropping gossip from {node_id}: {e}"),
+        }
+    }
+}
+
+/// Handles the gossip of the peers. Malformed messages are dropped, since
+/// the handlers only return fatal errors.
+#[tonic::async_trait]
+impl<S: Set> NetworkAppHandler for Gossiper<S> {
+    /// Responds with the items which are not in the bloom filter of the peer.
+    async fn app_request(
+        &self,
+        node_id: &ids::node::Id,
+        request_id: u32,
+        deadline: DateTime<Utc>,
+        request: &[u8],
+    ) -> io::Result<()> {
+        if deadline < Utc::now() {
+            log::debug!("dropping expired pull request {request_id} from {node_id}");
+            return Ok(());
+        }
+        let app_sender = match &self.sender {
+            Sender::AppSender(app_sender) => app_sender,
+            // the requests to the client's gossiper are handled by the network
+            Sender::Client(_) => {
+                log::debug!("dropping pull request {request_id} from {node_id} of no handler");
+                return Ok(());
+            }
+        };
+        match self.pull_response(node_id, request) {
+            Ok(msg) => {
+                app_sender
+                    .send_app_response(*node_id, request_id, msg)
+                    .await
+            }
+            Err(e) => {
+                log::debug!("dropping pull request {request_id} from {node_id}: {e}");
+                Ok(())
+            }
+        }
+    }
+
+    async fn app_request_failed(&self, node_id: &ids::node::Id, request_id: u32) -> io::Result<()> {
+        log::debug!("pull request {request_id} to {node_id} failed");
+        self.requests.lock().unwrap().remove(&request_id);
+        Ok(())
+    }
+
+    /// Adds the items the peer responded with to the set.
+    async fn app_response(
+        &self,
+        node_id: &ids::node::Id,
+        request_id: u32,
+        response: &[u8],
+    ) -> io::Result<()> {
+        {
+            let mut requests = self.requests.lock().unwrap();
+            if requests.get(&request_id) != Some(node_id) {
+                log::debug!("dropping unrequested response {request_id} from {node_id}");
+                return Ok(());
+            }
+            requests.remove(&request_id);
+        }
+        self.handle_pull_response(node_id, response);
+        Ok(())
+    }
+
+    /// Adds the items the peer pushed to the set.
+    async fn app_gossip(&self, node_id: &ids::node::Id, msg: &[u8]) -> io::Result<()> {
+        self.handle_push(node_id, msg);
+        Ok(())
+    }
+}
+
+/// Handles the gossip of the peers routed by the [`p2p::Network`]. The
+/// malformed pull requests fail, so that the network drops them.
+#[tonic::async_trait]
+impl<S: Set> p2p::Handler for Gossiper<S> {
+    /// Adds the items the peer pushed to the set.
+    async fn app_gossip(&self, node_id: &ids::node::Id, gossip: &[u8]) -> io::Result<()> {
+        self.handle_push(node_id, gossip);
+        Ok(())
+    }
+
+    /// Responds with the items which are not in the bloom filter of the peer.
+    async fn app_request(
+        &self,
+        node_id: &ids::node::Id,
+        _deadline: DateTime<Utc>,
+        request: &[u8],
+    ) -> io::Result<Vec<u8>> {
+        self.pull_response(node_id, request)
+    }
+}
+
+#[tonic::async_trait]
+impl<S: Set> Connector for Gossiper<S> {
+    async fn connected(&self, id: &ids::node::Id) -> io::Result<()> {
+        let mut peers = self.peers.lock().unwrap();
+        if !peers.contains(id) {
+            peers.push(*id);
+        }
+        Ok(())
+    }
+
+    async fn disconnected(&self, id: &ids::node::Id) -> io::Result<()> {
+        self.peers.lock().unwrap().retain(|peer| peer != id);
+        self.seen.lock().unwrap().remove(id);
+        Ok(())
+    }
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features subnet -- subnet::rpc::gossip::test_message --exact --show-output
+#[test]
+fn test_message() {
+    for msg in [
+        Message::Push(vec![vec![1, 2, 3], vec![]]),
+        Message::PullRequest(vec![4; 40]),
+        Message::PullResponse(Vec::new()),
+    ] {
+        let b = msg.to_bytes().unwrap();
+        assert_eq!(Message::from_bytes(&b).unwrap(), msg);
+
+        let mut trailing = b.clone();
+        trailing.push(0);
+        assert!(Message::from_bytes(&trailing).is_err());
+    }
+    assert!(Message::from_bytes(&[]).is_err());
+    assert!(Message::from_bytes(&[3]).is_err());
+    assert!(Message::from_bytes(&[PUSH, 0, 0, 0, 1]).is_err());
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features subnet -- subnet::rpc::gossip::test_gossiper --exact --show-output
+#[tokio::test]
+async fn test_gossiper() {
+    use crate::subnet::rpc::{
+        harness::{appsender::AppSender as Recorder, SentMessage},
+        mempool::{self, Mempool, TestTx},
+    };
+
+    let node_a = ids::node::Id::from_slice(&[1; ids::node::LEN]);
+    let node_b = ids::node::Id::from_slice(&[2; ids::node::LEN]);
+    let (to_engine, _rx) = tokio::sync::mpsc::channel(1);
+    let (sent_a, sent_b) = (Recorder::new(), Recorder::new());
+    let gossiper_a = Gossiper::new(
+        Arc::new(Mempool::new(mempool::Config::default(), to_engine.clone())),
+        Box::new(sent_a.clone()),
+        // no false positives in the pull requests of the test
+        Config {
+            false_positive_probability: 1e-9,
+            ..Default::default()
+        },
+    );
+    let gossiper_b = Gossiper::new(
+        Arc::new(Mempool::new(mempool::Config::default(), to_engine)),
+        Box::new(sent_b.clone()),
+        Config::default(),
+    );
+
+    // the local tx is pushed to the peers
+    let tx1 = TestTx::new(1, b"tx1");
+    gossiper_a.add_local(tx1.clone()).await.unwrap();
+    let push = match sent_a.take().as_slice() {
+        [SentMessage::AppGossip { msg }] => msg.clone(),
+        sent => panic!("unexpected {sent:?}"),
+    };
+    gossiper_b.app_gossip(&node_a, &push).await.unwrap();
+    assert!(gossiper_b.set().has(&tx1.gossip_id()));
+
+    // the pull only returns the txs missing from the requester
+    let tx2 = TestTx::new(2, b"tx2");
+    gossiper_b.set().add(tx2.clone()).unwrap();
+    assert!(!gossiper_a.pull().await.unwrap());
+    gossiper_a.connected(&node_b).await.unwrap();
+    assert!(gossiper_a.pull().await.unwrap());
+    let (request_id, request) = match sent_a.take().as_slice() {
+        [SentMessage::AppRequest {
+            node_ids,
+            request_id,
+            request,
+        }] => {
+            assert_eq!(node_ids, &ids::node::Set::from([node_b]));
+            (*request_id, request.clone())
+        }
+        sent => panic!("unexpected {sent:?}"),
+    };
+    let deadline = Utc::now() + chrono::Duration::seconds(10);
+    gossiper_b
+        .app_request(&node_a, request_id, deadline, &request)
+        .await
+        .unwrap();
+    let response = match sent_b.take().as_slice() {
+        [SentMessage::AppResponse {
+            node_id, response, ..
+        }] => {
+            assert_eq!(node_id, &node_a);
+            response.clone()
+        }
+        sent => panic!("unexpected {sent:?}"),
+    };
+    assert_eq!(
+        Message::from_bytes(&response).unwrap(),
+        Message::PullResponse(vec![tx2.bytes().to_vec()])
+    );
+
+    // the response is only handled once, from the requested peer
+    gossiper_a
+        .app_response(&node_a, request_id, &response)
+        .await
+        .unwrap();
+    assert!(!gossiper_a.set().has(&tx2.gossip_id()));
+    gossiper_a
+        .app_response(&node_b, request_id, &response)
+        .await
+        .unwrap();
+    assert!(gossiper_a.set().has(&tx2.gossip_id()));
+
+    // the txs the peer has seen are not sent to the peer again
+    let tx3 = TestTx::new(3, b"tx3");
+    gossiper_b.set().add(tx3.clone()).unwrap();
+    let request = Message::PullRequest(bloom::Filter::new(1, 0.01).to_bytes())
+        .to_bytes()
+        .unwrap();
+    gossiper_b
+        .app_request(&node_a, request_id + 1, deadline, &request)
+        .await
+        .unwrap();
+    match sent_b.take().as_slice() {
+        [SentMessage::AppResponse { response, .. }] => assert_eq!(
+            Message::from_bytes(response).unwrap(),
+            Message::PullResponse(vec![tx3.bytes().to_vec()])
+        ),
+        sent => panic!("unexpected {sent:?}"),
+    }
+
+    // the malformed gossip is dropped
+    gossiper_b.app_gossip(&node_a, &[0xff]).await.unwrap();
+    gossiper_a.disconnected(&node_b).await.unwrap();
+    assert!(!gossiper_a.pull().await.unwrap());
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features subnet -- subnet::rpc::gossip::test_gossiper_p2p --exact --show-output
+#[tokio::test]
+async fn test_gossiper_p2p() {
+    use std::time::Duration;
+
+    use crate::subnet::rpc::{
+        harness::{appsender::AppSender as Recorder, SentMessage},
+        mempool::{self, Mempool, TestTx},
+    };
+
+    const HANDLER_ID: u8 = 0;
+
+    /// Waits for the message the network sends.
+    async fn next(sent: &Recorder) -> SentMessage {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                if let Some(msg) = sent.take().pop() {
+                    return msg;
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("no message sent")
+    }
+
+    let node_a = ids::node::Id::from_slice(&[1; ids::node::LEN]);
+    let node_b = ids::node::Id::from_slice(&[2; ids::node::LEN]);
+    let (to_engine, _rx) = tokio::sync::mpsc::channel(1);
+    let (sent_a, sent_b) = (Recorder::new(), Recorder::new());
+    let network_a = p2p::Network::new(Box::new(sent_a.clone()), p2p::Config::default());
+    let network_b = p2p::Network::new(Box::new(sent_b.clone()), p2p::Config::default());
+    let gossiper_a = Arc::new(Gossiper::with_client(
+        Arc::new(Mempool::new(mempool::Config::default(), to_engine.clone())),
+        network_a.new_client(HANDLER_ID),
+        // no false positives in the pull requests of the test
+        Config {
+            false_positive_probability: 1e-9,
+            ..Default::default()
+        },
+    ));
+    let gossiper_b = Arc::new(Gossiper::with_client(
+        Arc::new(Mempool::new(mempool::Config::default(), to_engine)),
+        network_b.new_client(HANDLER_ID),
+        Config::default(),
+    ));
+    network_a
+        .add_handler(HANDLER_ID, gossiper_a.clone())
+        .unwrap();
+    network_b
+        .add_handler(HANDLER_ID, gossiper_b.clone())
+        .unwrap();
+
+    // the push is prefixed with the handler ID, and routed to the gossiper
+    let tx1 = TestTx::new(1, b"tx1");
+    gossiper_a.add_local(tx1.clone()).await.unwrap();
+    let push = match next(&sent_a).await {
+        SentMessage::AppGossip { msg } => msg,
+        msg => panic!("unexpected {msg:?}"),
+    };
+    assert_eq!(push[0], HANDLER_ID);
+    network_b.app_gossip(&node_a, &push).await.unwrap();
+    assert!(gossiper_b.set().has(&tx1.gossip_id()));
+
+    // the pull request uses the request ID of the network, and completes
+    // once the peer's network responds
+    let tx2 = TestTx::new(2, b"tx2");
+    gossiper_b.set().add(tx2.clone()).unwrap();
+    gossiper_a.connected(&node_b).await.unwrap();
+    let g = gossiper_a.clone();
+    let pull = tokio::spawn(async move { g.pull().await });
+    let (request_id, request) = match next(&sent_a).await {
+        SentMessage::AppRequest {
+            request_id,
+            request,
+            ..
+        } => (request_id, request),
+        msg => panic!("unexpected {msg:?}"),
+    };
+    let deadline = Utc::now() + chrono::Duration::seconds(10);
+    network_b
+        .app_request(&node_a, request_id, deadline, &request)
+        .await
+        .unwrap();
+    let response = match next(&sent_b).await {
+        SentMessage::AppResponse { response, .. } => response,
+        msg => panic!("unexpected {msg:?}"),
+    };
+    network_a
+        .app_response(&node_b, request_id, &response)
+        .await
+        .unwrap();
+    assert!(pull.await.unwrap().unwrap());
+    assert!(gossiper_a.set().has(&tx2.gossip_id()));
+
+    // the malformed pull request is not responded
+    network_b
+        .app_request(&node_a, request_id + 1, deadline, &[HANDLER_ID, 0xff])
+        .await
+        .unwrap();
+    assert!(sent_b.take().is_empty());
+}
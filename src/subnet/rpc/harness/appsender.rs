@@ -0,0 +1,124 @@
+//! App sender which records the messages the VM sends to its peers.
+use std::{
+    io::Result,
+    sync::{Arc, Mutex},
+};
+
+use crate::ids;
+
+/// Message the VM sent through the [`AppSender`](crate::subnet::rpc::snow::engine::common::appsender::AppSender).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SentMessage {
+    AppRequest {
+        node_ids: ids::node::Set,
+        request_id: u32,
+        request: Vec<u8>,
+    },
+    AppResponse {
+        node_id: ids::node::Id,
+        request_id: u32,
+        response: Vec<u8>,
+    },
+    AppGossip {
+        msg: Vec<u8>,
+    },
+    AppGossipSpecific {
+        node_ids: ids::node::Set,
+        msg: Vec<u8>,
+    },
+    CrossChainAppRequest {
+        chain_id: ids::Id,
+        request_id: u32,
+        request: Vec<u8>,
+    },
+    CrossChainAppResponse {
+        chain_id: ids::Id,
+        request_id: u32,
+        response: Vec<u8>,
+    },
+}
+
+/// Records the sent messages in order, instead of sending them to the network.
+#[derive(Debug, Clone, Default)]
+pub struct AppSender {
+    sent: Arc<Mutex<Vec<SentMessage>>>,
+}
+
+impl AppSender {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the messages sent since the last call.
+    pub fn take(&self) -> Vec<SentMessage> {
+        std::mem::take(&mut *self.sent.lock().unwrap())
+    }
+
+    fn record(&self, msg: SentMessage) -> Result<()> {
+        self.sent.lock().unwrap().push(msg);
+        Ok(())
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::snow::engine::common::appsender::AppSender for AppSender {
+    async fn send_app_request(
+        &self,
+        node_ids: ids::node::Set,
+        request_id: u32,
+        request: Vec<u8>,
+    ) -> Result<()> {
+        self.record(SentMessage::AppRequest {
+            node_ids,
+            request_id,
+            request,
+        })
+    }
+
+    async fn send_app_response(
+        &self,
+        node_id: ids::node::Id,
+        request_id: u32,
+        response: Vec<u8>,
+    ) -> Result<()> {
+        self.record(SentMessage::AppResponse {
+            node_id,
+            request_id,
+            response,
+        })
+    }
+
+    async fn send_app_gossip(&self, msg: Vec<u8>) -> Result<()> {
+        self.record(SentMessage::AppGossip { msg })
+    }
+
+    async fn send_app_gossip_specific(&self, node_ids: ids::node::Set, msg: Vec<u8>) -> Result<()> {
+        self.record(SentMessage::AppGossipSpecific { node_ids, msg })
+    }
+
+    async fn send_cross_chain_app_request(
+        &self,
+        chain_id: ids::Id,
+        request_id: u32,
+        request: Vec<u8>,
+    ) -> Result<()> {
+        self.record(SentMessage::CrossChainAppRequest {
+            chain_id,
+            request_id,
+            request,
+        })
+    }
+
+    async fn send_cross_chain_app_response(
+        &self,
+        chain_id: ids::Id,
+        request_id: u32,
+        response: Vec<u8>,
+    ) -> Result<()> {
+        self.record(SentMessage::CrossChainAppResponse {
+            chain_id,
+            request_id,
+            response,
+        })
+    }
+}
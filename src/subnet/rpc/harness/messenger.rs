@@ -0,0 +1,37 @@
+//! Messenger which receives the notifications of the VM to the consensus engine.
+use crate::{
+    proto::pb::{
+        self,
+        messenger::{NotifyRequest, NotifyResponse},
+    },
+    subnet::rpc::snow::engine::common::message::Message,
+};
+use tokio::sync::mpsc;
+use tonic::{Request, Response, Status};
+
+/// Forwards the notifications (e.g., [`Message::PendingTxs`]) to the channel.
+#[derive(Debug, Clone)]
+pub struct Messenger {
+    tx: mpsc::UnboundedSender<Message>,
+}
+
+impl Messenger {
+    pub fn new(tx: mpsc::UnboundedSender<Message>) -> Self {
+        Self { tx }
+    }
+}
+
+#[tonic::async_trait]
+impl pb::messenger::messenger_server::Messenger for Messenger {
+    async fn notify(
+        &self,
+        request: Request<NotifyRequest>,
+    ) -> Result<Response<NotifyResponse>, Status> {
+        let msg = Message::try_from(request.into_inner().message as u32)
+            .map_err(Status::invalid_argument)?;
+        self.tx
+            .send(msg)
+            .map_err(|_| Status::unavailable("harness dropped"))?;
+        Ok(Response::new(NotifyResponse {}))
+    }
+}
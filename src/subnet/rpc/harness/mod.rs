@@ -0,0 +1,1369 @@
+//! In-process harness which drives a [`ChainVm`] the way the node does, to
+//! test the VMs hermetically without running the node.
+//!
+//! The harness serves the node services the VM connects to at initialize
+//! (messenger, app sender, validator state, shared memory, aliases, warp
+//! signer and the memdb-backed rpcdb), and calls the VM through
+//! [`Server`] as the node would over gRPC.
+pub mod appsender;
+pub mod messenger;
+pub mod runtime;
+pub mod validators;
+
+use std::{
+    io::{self, Error, ErrorKind},
+    net::SocketAddr,
+    time::Duration,
+};
+
+use crate::{
+    ids,
+    key::bls,
+    proto::pb::{
+        aliasreader::alias_reader_server::AliasReaderServer,
+        appsender::app_sender_server::AppSenderServer,
+        google::protobuf::Empty,
+        messenger::messenger_server::MessengerServer,
+        rpcdb::database_server::DatabaseServer,
+        sharedmemory::shared_memory_server::SharedMemoryServer,
+        validatorstate::validator_state_server::ValidatorStateServer,
+        vm::{self, vm_server::Vm},
+        warp::signer_server::SignerServer,
+    },
+    subnet::rpc::{
+        aliasreader::{self, Aliaser},
+        atomic::{self, memory::Memory},
+        database::{
+            manager::DatabaseManager, memdb, rpcdb::server::Server as DatabaseService,
+            BoxedDatabase,
+        },
+        snow::{
+            self,
+            engine::common::{
+                appsender::{client::AppSenderClient, server::Server as AppSenderService},
+                message::Message,
+            },
+            validators::{self as snow_validators, client::ValidatorStateClient},
+        },
+        snowman::block::ChainVm,
+        utils::grpc::{default_server, timestamp_from_time},
+        vm::server::Server,
+        warp,
+    },
+};
+use chrono::{DateTime, Utc};
+use prost::bytes::Bytes;
+use tokio::{
+    net::TcpListener,
+    sync::{broadcast, mpsc, Mutex},
+    task::JoinHandle,
+};
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::{transport::server::Router, Request};
+
+pub use self::{appsender::SentMessage, validators::ValidatorState};
+
+/// Version of the database served to the VM.
+pub const DATABASE_VERSION: &str = "v1.4.5";
+
+/// Drives the VM under test, and records what it sends to the node.
+pub struct Harness<V> {
+    /// Serves the VM as the node sees it over gRPC.
+    pub server: Server<V>,
+
+    pub network_id: u32,
+    pub subnet_id: ids::Id,
+    pub chain_id: ids::Id,
+    pub node_id: ids::node::Id,
+    pub x_chain_id: ids::Id,
+    pub c_chain_id: ids::Id,
+    pub lux_asset_id: ids::Id,
+
+    db: BoxedDatabase,
+    memory: Memory,
+    aliaser: Aliaser,
+    validator_state: ValidatorState,
+    app_sender: appsender::AppSender,
+    messages: Mutex<mpsc::UnboundedReceiver<Message>>,
+
+    node_addr: SocketAddr,
+    db_addr: SocketAddr,
+    services: Vec<JoinHandle<()>>,
+    /// Keeps the stop channel open, for the VM to notify its servers at shutdown.
+    _stop_rx: broadcast::Receiver<()>,
+}
+
+impl<V> Harness<V>
+where
+    V: ChainVm<
+            DatabaseManager = DatabaseManager,
+            AppSender = AppSenderClient,
+            ValidatorState = ValidatorStateClient,
+        > + Send
+        + Sync
+        + 'static,
+{
+    /// Starts serving the node services on the localhost, for the VM to
+    /// connect to at [`Harness::initialize`].
+    pub async fn new(vm: V) -> io::Result<Self> {
+        let network_id = 1337;
+        let subnet_id = ids::Id::empty();
+        let chain_id = ids::Id::sha256("chain");
+        let node_id = ids::node::Id::from_slice(&[1; ids::node::LEN]);
+        let x_chain_id = ids::Id::sha256("X");
+        let c_chain_id = ids::Id::sha256("C");
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let memory = Memory::default();
+        let aliaser = Aliaser::new();
+        aliaser.alias(chain_id, &chain_id.to_string())?;
+        aliaser.alias(x_chain_id, "X")?;
+        aliaser.alias(c_chain_id, "C")?;
+        let validator_state = ValidatorState::new();
+        validator_state.set_subnet_id(chain_id, subnet_id);
+        let app_sender = appsender::AppSender::new();
+        let signer =
+            warp::LocalSigner::new(bls::private_key::Key::generate()?, network_id, chain_id);
+
+        let (node_addr, node_service) = spawn(
+            default_server()
+                .add_service(MessengerServer::new(messenger::Messenger::new(tx)))
+                .add_service(AppSenderServer::new(AppSenderService::new(Box::new(
+                    app_sender.clone(),
+                ))))
+                .add_service(ValidatorStateServer::new(
+                    snow_validators::server::Server::new(validator_state.clone()),
+                ))
+                .add_service(SharedMemoryServer::new(atomic::server::Server::new(
+                    memory.new_shared_memory(chain_id),
+                )))
+                .add_service(AliasReaderServer::new(aliasreader::server::Server::new(
+                    aliaser.clone(),
+                )))
+                .add_service(SignerServer::new(warp::server::Server::new(signer))),
+        )
+        .await?;
+
+        let db = memdb::Database::new();
+        let (db_addr, db_service) = spawn(
+            default_server().add_service(DatabaseServer::new(DatabaseService::new(db.clone()))),
+        )
+        .await?;
+
+        let (stop_ch, stop_rx) = broadcast::channel(1);
+        Ok(Self {
+            server: Server::new(vm, stop_ch),
+            network_id,
+            subnet_id,
+            chain_id,
+            node_id,
+            x_chain_id,
+            c_chain_id,
+            lux_asset_id: ids::Id::sha256("LUX"),
+            db,
+            memory,
+            aliaser,
+            validator_state,
+            app_sender,
+            messages: Mutex::new(rx),
+            node_addr,
+            db_addr,
+            services: vec![node_service, db_service],
+            _stop_rx: stop_rx,
+        })
+    }
+
+    /// Returns the database served to the VM, to assert its state.
+    pub fn database(&self) -> BoxedDatabase {
+        self.db.clone()
+    }
+
+    /// Returns the shared memory of all the chains, whose shared memory
+    /// of [`Harness::chain_id`] is served to the VM.
+    pub fn shared_memory(&self) -> &Memory {
+        &self.memory
+    }
+
+    /// Returns the aliases served to the VM.
+    pub fn aliaser(&self) -> &Aliaser {
+        &self.aliaser
+    }
+
+    /// Returns the validator state served to the VM.
+    pub fn validator_state(&self) -> &ValidatorState {
+        &self.validator_state
+    }
+
+    /// Returns the messages the VM sent to its peers since the last call.
+    pub fn sent_app_messages(&self) -> Vec<SentMessage> {
+        self.app_sender.take()
+    }
+
+    /// Initializes the VM with the genesis, and empty upgrade and config.
+    pub async fn initialize(&self, genesis_bytes: &[u8]) -> io::Result<vm::InitializeResponse> {
+        self.initialize_with(genesis_bytes, &[], &[]).await
+    }
+
+    pub async fn initialize_with(
+        &self,
+        genesis_bytes: &[u8],
+        upgrade_bytes: &[u8],
+        config_bytes: &[u8],
+    ) -> io::Result<vm::InitializeResponse> {
+        let req = vm::InitializeRequest {
+            network_id: self.network_id,
+            subnet_id: Bytes::from(self.subnet_id.to_vec()),
+            chain_id: Bytes::from(self.chain_id.to_vec()),
+            node_id: Bytes::from(self.node_id.to_vec()),
+            public_key: Bytes::new(),
+            x_chain_id: Bytes::from(self.x_chain_id.to_vec()),
+            c_chain_id: Bytes::from(self.c_chain_id.to_vec()),
+            lux_asset_id: Bytes::from(self.lux_asset_id.to_vec()),
+            chain_data_dir: String::new(),
+            genesis_bytes: Bytes::copy_from_slice(genesis_bytes),
+            upgrade_bytes: Bytes::copy_from_slice(upgrade_bytes),
+            config_bytes: Bytes::copy_from_slice(config_bytes),
+            db_servers: vec![vm::VersionedDbServer {
+                version: DATABASE_VERSION.to_string(),
+                server_addr: self.db_addr.to_string(),
+            }],
+            server_addr: self.node_addr.to_string(),
+        };
+        call("initialize", self.server.initialize(Request::new(req))).await
+    }
+
+    pub async fn set_state(&self, state: snow::State) -> io::Result<vm::SetStateResponse> {
+        let req = vm::SetStateRequest {
+            state: state as i32,
+        };
+        call("set_state", self.server.set_state(Request::new(req))).await
+    }
+
+    /// Waits for the next message the VM sends to the consensus engine.
+    pub async fn recv_message(&self, timeout: Duration) -> io::Result<Message> {
+        let mut messages = self.messages.lock().await;
+        match tokio::time::timeout(timeout, messages.recv()).await {
+            Ok(Some(msg)) => Ok(msg),
+            Ok(None) => Err(Error::new(ErrorKind::BrokenPipe, "messenger closed")),
+            Err(_) => Err(Error::new(
+                ErrorKind::TimedOut,
+                "no message from the VM before the timeout",
+            )),
+        }
+    }
+
+    /// Waits for the VM to notify the pending transactions, and builds the
+    /// block as the consensus engine would.
+    pub async fn build_block_on_pending_txs(
+        &self,
+        timeout: Duration,
+    ) -> io::Result<vm::BuildBlockResponse> {
+        match self.recv_message(timeout).await? {
+            Message::PendingTxs => self.build_block().await,
+            msg => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("expected pending txs, got {msg:?}"),
+            )),
+        }
+    }
+
+    pub async fn build_block(&self) -> io::Result<vm::BuildBlockResponse> {
+        let req = vm::BuildBlockRequest {
+            p_chain_height: None,
+        };
+        call("build_block", self.server.build_block(Request::new(req))).await
+    }
+
+    pub async fn build_block_with_context(
+        &self,
+        p_chain_height: u64,
+    ) -> io::Result<vm::BuildBlockResponse> {
+        let req = vm::BuildBlockRequest {
+            p_chain_height: Some(p_chain_height),
+        };
+        call("build_block", self.server.build_block(Request::new(req))).await
+    }
+
+    pub async fn parse_block(&self, bytes: &[u8]) -> io::Result<vm::ParseBlockResponse> {
+        let req = vm::ParseBlockRequest {
+            bytes: Bytes::copy_from_slice(bytes),
+        };
+        call("parse_block", self.server.parse_block(Request::new(req))).await
+    }
+
+    pub async fn get_block(&self, id: &ids::Id) -> io::Result<vm::GetBlockResponse> {
+        let req = vm::GetBlockRequest {
+            id: Bytes::from(id.to_vec()),
+        };
+        call("get_block", self.server.get_block(Request::new(req))).await
+    }
+
+    pub async fn verify(&self, bytes: &[u8]) -> io::Result<vm::BlockVerifyResponse> {
+        let req = vm::BlockVerifyRequest {
+            bytes: Bytes::copy_from_slice(bytes),
+            p_chain_height: None,
+        };
+        call("block_verify", self.server.block_verify(Request::new(req))).await
+    }
+
+    pub async fn verify_with_context(
+        &self,
+        bytes: &[u8],
+        p_chain_height: u64,
+    ) -> io::Result<vm::BlockVerifyResponse> {
+        let req = vm::BlockVerifyRequest {
+            bytes: Bytes::copy_from_slice(bytes),
+            p_chain_height: Some(p_chain_height),
+        };
+        call("block_verify", self.server.block_verify(Request::new(req))).await
+    }
+
+    pub async fn accept(&self, id: &ids::Id) -> io::Result<()> {
+        let req = vm::BlockAcceptRequest {
+            id: Bytes::from(id.to_vec()),
+        };
+        call("block_accept", self.server.block_accept(Request::new(req))).await?;
+        Ok(())
+    }
+
+    pub async fn reject(&self, id: &ids::Id) -> io::Result<()> {
+        let req = vm::BlockRejectRequest {
+            id: Bytes::from(id.to_vec()),
+        };
+        call("block_reject", self.server.block_reject(Request::new(req))).await?;
+        Ok(())
+    }
+
+    pub async fn set_preference(&self, id: &ids::Id) -> io::Result<()> {
+        let req = vm::SetPreferenceRequest {
+            id: Bytes::from(id.to_vec()),
+        };
+        call(
+            "set_preference",
+            self.server.set_preference(Request::new(req)),
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub async fn connected(&self, node_id: &ids::node::Id, version: &str) -> io::Result<()> {
+        let req = vm::ConnectedRequest {
+            node_id: Bytes::from(node_id.to_vec()),
+            version: version.to_string(),
+        };
+        call("connected", self.server.connected(Request::new(req))).await?;
+        Ok(())
+    }
+
+    pub async fn disconnected(&self, node_id: &ids::node::Id) -> io::Result<()> {
+        let req = vm::DisconnectedRequest {
+            node_id: Bytes::from(node_id.to_vec()),
+        };
+        call("disconnected", self.server.disconnected(Request::new(req))).await?;
+        Ok(())
+    }
+
+    /// Delivers the request of the peer to the VM.
+    pub async fn app_request(
+        &self,
+        node_id: &ids::node::Id,
+        request_id: u32,
+        deadline: DateTime<Utc>,
+        request: &[u8],
+    ) -> io::Result<()> {
+        let req = vm::AppRequestMsg {
+            node_id: Bytes::from(node_id.to_vec()),
+            request_id,
+            deadline: Some(timestamp_from_time(&deadline)),
+            request: Bytes::copy_from_slice(request),
+        };
+        call("app_request", self.server.app_request(Request::new(req))).await?;
+        Ok(())
+    }
+
+    pub async fn app_request_failed(
+        &self,
+        node_id: &ids::node::Id,
+        request_id: u32,
+    ) -> io::Result<()> {
+        let req = vm::AppRequestFailedMsg {
+            node_id: Bytes::from(node_id.to_vec()),
+            request_id,
+        };
+        call(
+            "app_request_failed",
+            self.server.app_request_failed(Request::new(req)),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Delivers the response of the peer to the VM.
+    pub async fn app_response(
+        &self,
+        node_id: &ids::node::Id,
+        request_id: u32,
+        response: &[u8],
+    ) -> io::Result<()> {
+        let req = vm::AppResponseMsg {
+            node_id: Bytes::from(node_id.to_vec()),
+            request_id,
+            response: Bytes::copy_from_slice(response),
+        };
+        call("app_response", self.server.app_response(Request::new(req))).await?;
+        Ok(())
+    }
+
+    /// Delivers the gossip of the peer to the VM.
+    pub async fn app_gossip(&self, node_id: &ids::node::Id, msg: &[u8]) -> io::Result<()> {
+        let req = vm::AppGossipMsg {
+            node_id: Bytes::from(node_id.to_vec()),
+            msg: Bytes::copy_from_slice(msg),
+        };
+        call("app_gossip", self.server.app_gossip(Request::new(req))).await?;
+        Ok(())
+    }
+
+    pub async fn state_sync_enabled(&self) -> io::Result<vm::StateSyncEnabledResponse> {
+        call(
+            "state_sync_enabled",
+            self.server.state_sync_enabled(Request::new(Empty {})),
+        )
+        .await
+    }
+
+    pub async fn get_ongoing_sync_state_summary(
+        &self,
+    ) -> io::Result<vm::GetOngoingSyncStateSummaryResponse> {
+        call(
+            "get_ongoing_sync_state_summary",
+            self.server
+                .get_ongoing_sync_state_summary(Request::new(Empty {})),
+        )
+        .await
+    }
+
+    pub async fn get_last_state_summary(&self) -> io::Result<vm::GetLastStateSummaryResponse> {
+        call(
+            "get_last_state_summary",
+            self.server.get_last_state_summary(Request::new(Empty {})),
+        )
+        .await
+    }
+
+    pub async fn parse_state_summary(
+        &self,
+        bytes: &[u8],
+    ) -> io::Result<vm::ParseStateSummaryResponse> {
+        let req = vm::ParseStateSummaryRequest {
+            bytes: Bytes::copy_from_slice(bytes),
+        };
+        call(
+            "parse_state_summary",
+            self.server.parse_state_summary(Request::new(req)),
+        )
+        .await
+    }
+
+    pub async fn get_state_summary(&self, height: u64) -> io::Result<vm::GetStateSummaryResponse> {
+        let req = vm::GetStateSummaryRequest { height };
+        call(
+            "get_state_summary",
+            self.server.get_state_summary(Request::new(req)),
+        )
+        .await
+    }
+
+    /// Accepts the state summary, for the VM to start syncing to it.
+    pub async fn state_summary_accept(
+        &self,
+        bytes: &[u8],
+    ) -> io::Result<vm::StateSummaryAcceptResponse> {
+        let req = vm::StateSummaryAcceptRequest {
+            bytes: Bytes::copy_from_slice(bytes),
+        };
+        call(
+            "state_summary_accept",
+            self.server.state_summary_accept(Request::new(req)),
+        )
+        .await
+    }
+
+    pub async fn health(&self) -> io::Result<vm::HealthResponse> {
+        call("health", self.server.health(Request::new(Empty {}))).await
+    }
+
+    /// Shuts down the VM and stops serving the node services.
+    pub async fn shutdown(mut self) -> io::Result<()> {
+        call("shutdown", self.server.shutdown(Request::new(Empty {}))).await?;
+        for service in self.services.drain(..) {
+            service.abort();
+        }
+        Ok(())
+    }
+}
+
+impl<V> Drop for Harness<V> {
+    fn drop(&mut self) {
+        for service in self.services.iter() {
+            service.abort();
+        }
+    }
+}
+
+/// Serves the services on a localhost port, until the returned task is aborted.
+async fn spawn(router: Router) -> io::Result<(SocketAddr, JoinHandle<()>)> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let service = tokio::spawn(async move {
+        if let Err(e) = router
+            .serve_with_incoming(TcpListenerStream::new(listener))
+            .await
+        {
+            log::warn!("harness service failed: {e}");
+        }
+    });
+    Ok((addr, service))
+}
+
+/// Returns the response of the VM RPC, or the error it failed with.
+async fn call<T>(
+    method: &str,
+    resp: impl std::future::Future<Output = Result<tonic::Response<T>, tonic::Status>>,
+) -> io::Result<T> {
+    resp.await.map(|resp| resp.into_inner()).map_err(|e| {
+        Error::new(
+            ErrorKind::Other,
+            format!("{method} failed: {}", e.message()),
+        )
+    })
+}
+
+/// Chain of opaque payloads, whose blocks are built from the gossiped payloads.
+#[cfg(test)]
+pub(crate) mod testvm {
+    use std::{
+        collections::{BTreeMap, HashMap, VecDeque},
+        io::{Error, ErrorKind, Result},
+        sync::Arc,
+        time::Duration,
+    };
+
+    use crate::{
+        choices::status::Status,
+        ids,
+        proto::http::Element,
+        subnet::rpc::{
+            consensus::snowman::{Block, Decidable},
+            context::Context,
+            database::{
+                manager::{DatabaseManager, Manager},
+                BoxedDatabase,
+            },
+            errors,
+            health::Checkable,
+            http::handle::Handle,
+            snow::{
+                engine::common::{
+                    appsender::{client::AppSenderClient, AppSender},
+                    engine::{AppHandler, CrossChainAppHandler, NetworkAppHandler},
+                    http_handler::HttpHandler,
+                    message::Message,
+                    vm::{CommonVm, Connector, Fx},
+                },
+                validators::client::ValidatorStateClient,
+                State,
+            },
+            snowman::{
+                block::{
+                    self, BatchedChainVm, BuildBlockWithContextChainVM, ChainVm, Getter,
+                    HeightIndexedChainVm, Parser, StateSummary, StateSyncMode, StateSyncableVm,
+                    WithVerifyContext,
+                },
+                height_index::HeightIndex,
+            },
+        },
+    };
+    use bytes::Bytes;
+    use chrono::{DateTime, Utc};
+    use tokio::sync::{mpsc::Sender, RwLock};
+
+    pub const LAST_ACCEPTED_KEY: &[u8] = b"last_accepted";
+
+    #[derive(Default)]
+    struct Inner {
+        db: Option<BoxedDatabase>,
+        to_engine: Option<Sender<Message>>,
+        app_sender: Option<AppSenderClient>,
+        blocks: HashMap<ids::Id, (Vec<u8>, Status)>,
+        preferred: ids::Id,
+        last_accepted: ids::Id,
+        mempool: VecDeque<Vec<u8>>,
+        /// P-chain heights the blocks were built and verified with.
+        p_chain_heights: Vec<u64>,
+    }
+
+    #[derive(Clone, Default)]
+    pub struct Vm {
+        inner: Arc<RwLock<Inner>>,
+        with_context: bool,
+        height_index: Option<HeightIndex>,
+        state_sync: Option<StateSync>,
+    }
+
+    impl Vm {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Returns the VM which builds and verifies the blocks with the
+        /// P-chain height.
+        pub fn with_context() -> Self {
+            Self {
+                with_context: true,
+                ..Self::default()
+            }
+        }
+
+        /// Returns the P-chain heights the blocks were built and verified with.
+        pub async fn p_chain_heights(&self) -> Vec<u64> {
+            self.inner.read().await.p_chain_heights.clone()
+        }
+
+        /// Returns the VM which serves the height index queries from the index.
+        pub fn with_height_index(height_index: HeightIndex) -> Self {
+            Self {
+                height_index: Some(height_index),
+                ..Self::default()
+            }
+        }
+
+        /// Returns the VM which serves the summaries of the payloads at the
+        /// heights, and syncs to them in the mode.
+        pub fn with_state_sync(mode: StateSyncMode, summaries: &[(u64, &[u8])]) -> Self {
+            let summaries = summaries
+                .iter()
+                .map(|(height, payload)| (*height, encode_summary(*height, payload)))
+                .collect();
+            Self {
+                state_sync: Some(StateSync {
+                    summaries: Arc::new(summaries),
+                    mode,
+                }),
+                ..Self::default()
+            }
+        }
+
+        async fn block(&self, bytes: Vec<u8>) -> TestBlock {
+            let id = ids::Id::sha256(&bytes);
+            let status = self
+                .inner
+                .read()
+                .await
+                .blocks
+                .get(&id)
+                .map_or(Status::Processing, |(_, status)| status.clone());
+            TestBlock {
+                id,
+                bytes,
+                status,
+                vm: self.clone(),
+            }
+        }
+    }
+
+    /// Encodes the block as the parent id, the big-endian height and the payload.
+    fn encode(parent: &ids::Id, height: u64, payload: &[u8]) -> Vec<u8> {
+        let mut bytes = parent.to_vec();
+        bytes.extend_from_slice(&height.to_be_bytes());
+        bytes.extend_from_slice(payload);
+        bytes
+    }
+
+    /// Encodes the state summary as the big-endian height and the payload.
+    pub fn encode_summary(height: u64, payload: &[u8]) -> Vec<u8> {
+        let mut bytes = height.to_be_bytes().to_vec();
+        bytes.extend_from_slice(payload);
+        bytes
+    }
+
+    /// Serves the state summaries by height, and syncs to the served ones.
+    #[derive(Clone)]
+    struct StateSync {
+        summaries: Arc<BTreeMap<u64, Vec<u8>>>,
+        mode: StateSyncMode,
+    }
+
+    impl StateSync {
+        fn summary(&self, bytes: Vec<u8>) -> Box<dyn StateSummary> {
+            Box::new(TestSummary {
+                id: ids::Id::sha256(&bytes),
+                height: u64::from_be_bytes(bytes[..8].try_into().unwrap()),
+                bytes,
+                state_sync: self.clone(),
+            })
+        }
+    }
+
+    #[tonic::async_trait]
+    impl StateSyncableVm for StateSync {
+        async fn state_sync_enabled(&self) -> Result<bool> {
+            Ok(true)
+        }
+
+        async fn get_ongoing_sync_state_summary(&self) -> Result<Box<dyn StateSummary>> {
+            Err(errors::Error::NotFound.to_err())
+        }
+
+        async fn get_last_state_summary(&self) -> Result<Box<dyn StateSummary>> {
+            match self.summaries.values().next_back() {
+                Some(bytes) => Ok(self.summary(bytes.clone())),
+                None => Err(errors::Error::NotFound.to_err()),
+            }
+        }
+
+        async fn parse_state_summary(&self, bytes: &[u8]) -> Result<Box<dyn StateSummary>> {
+            if bytes.len() < 8 {
+                return Err(Error::new(ErrorKind::InvalidData, "summary too short"));
+            }
+            Ok(self.summary(bytes.to_vec()))
+        }
+
+        async fn get_state_summary(&self, height: u64) -> Result<Box<dyn StateSummary>> {
+            match self.summaries.get(&height) {
+                Some(bytes) => Ok(self.summary(bytes.clone())),
+                None => Err(errors::Error::NotFound.to_err()),
+            }
+        }
+    }
+
+    struct TestSummary {
+        id: ids::Id,
+        height: u64,
+        bytes: Vec<u8>,
+        state_sync: StateSync,
+    }
+
+    #[tonic::async_trait]
+    impl StateSummary for TestSummary {
+        async fn id(&self) -> ids::Id {
+            self.id
+        }
+
+        async fn height(&self) -> u64 {
+            self.height
+        }
+
+        async fn bytes(&self) -> &[u8] {
+            &self.bytes
+        }
+
+        /// Syncs to the summary only if the VM serves it.
+        async fn accept(&mut self) -> Result<StateSyncMode> {
+            match self.state_sync.summaries.get(&self.height) {
+                Some(bytes) if *bytes == self.bytes => Ok(self.state_sync.mode),
+                _ => Err(errors::Error::NotFound.to_err()),
+            }
+        }
+    }
+
+    pub struct TestBlock {
+        id: ids::Id,
+        bytes: Vec<u8>,
+        status: Status,
+        vm: Vm,
+    }
+
+    impl TestBlock {
+        async fn decide(&mut self, status: Status) -> Result<()> {
+            let mut inner = self.vm.inner.write().await;
+            inner
+                .blocks
+                .insert(self.id, (self.bytes.clone(), status.clone()));
+            if status == Status::Accepted {
+                inner.last_accepted = self.id;
+                let db = inner.db.as_mut().expect("vm not initialized");
+                db.put(LAST_ACCEPTED_KEY, &self.id.to_vec()).await?;
+            }
+            self.status = status;
+            Ok(())
+        }
+    }
+
+    #[tonic::async_trait]
+    impl Decidable for TestBlock {
+        async fn id(&self) -> ids::Id {
+            self.id
+        }
+
+        async fn status(&self) -> Status {
+            self.status.clone()
+        }
+
+        async fn accept(&mut self) -> Result<()> {
+            self.decide(Status::Accepted).await
+        }
+
+        async fn reject(&mut self) -> Result<()> {
+            self.decide(Status::Rejected).await
+        }
+    }
+
+    #[tonic::async_trait]
+    impl Block for TestBlock {
+        async fn bytes(&self) -> &[u8] {
+            &self.bytes
+        }
+
+        async fn height(&self) -> u64 {
+            u64::from_be_bytes(self.bytes[ids::LEN..ids::LEN + 8].try_into().unwrap())
+        }
+
+        async fn timestamp(&self) -> u64 {
+            0
+        }
+
+        async fn parent(&self) -> ids::Id {
+            ids::Id::from_slice(&self.bytes[..ids::LEN])
+        }
+
+        async fn verify(&mut self) -> Result<()> {
+            self.verify_parent().await
+        }
+
+        fn with_verify_context(&self) -> Option<&dyn WithVerifyContext> {
+            if self.vm.with_context {
+                Some(self)
+            } else {
+                None
+            }
+        }
+    }
+
+    #[tonic::async_trait]
+    impl WithVerifyContext for TestBlock {
+        async fn should_verify_with_context(&self) -> Result<bool> {
+            Ok(true)
+        }
+
+        async fn verify_with_context(&self, blk_context: &block::Context) -> Result<()> {
+            self.verify_parent().await?;
+            self.vm
+                .inner
+                .write()
+                .await
+                .p_chain_heights
+                .push(blk_context.p_chain_height);
+            Ok(())
+        }
+    }
+
+    impl TestBlock {
+        async fn verify_parent(&self) -> Result<()> {
+            let parent = self.parent().await;
+            let mut inner = self.vm.inner.write().await;
+            if !inner.blocks.contains_key(&parent) {
+                return Err(Error::new(
+                    ErrorKind::NotFound,
+                    format!("parent {parent} not found"),
+                ));
+            }
+            inner
+                .blocks
+                .entry(self.id)
+                .or_insert_with(|| (self.bytes.clone(), Status::Processing));
+            Ok(())
+        }
+    }
+
+    #[derive(Clone)]
+    pub struct NoHandle;
+
+    #[tonic::async_trait]
+    impl Handle for NoHandle {
+        async fn request(&self, _: &Bytes, _: &[Element]) -> Result<(Bytes, Vec<Element>)> {
+            Err(Error::new(ErrorKind::Unsupported, "no handlers"))
+        }
+    }
+
+    #[tonic::async_trait]
+    impl CommonVm for Vm {
+        type DatabaseManager = DatabaseManager;
+        type AppSender = AppSenderClient;
+        type ChainHandler = NoHandle;
+        type StaticHandler = NoHandle;
+        type ValidatorState = ValidatorStateClient;
+
+        async fn initialize(
+            &mut self,
+            _ctx: Option<Context<Self::ValidatorState>>,
+            db_manager: Self::DatabaseManager,
+            genesis_bytes: &[u8],
+            _upgrade_bytes: &[u8],
+            _config_bytes: &[u8],
+            to_engine: Sender<Message>,
+            _fxs: &[Fx],
+            app_sender: Self::AppSender,
+        ) -> Result<()> {
+            let genesis = encode(&ids::Id::empty(), 0, genesis_bytes);
+            let genesis_id = ids::Id::sha256(&genesis);
+
+            let mut inner = self.inner.write().await;
+            let mut db = db_manager.current().await?.db;
+            db.put(LAST_ACCEPTED_KEY, &genesis_id.to_vec()).await?;
+            inner.db = Some(db);
+            inner.to_engine = Some(to_engine);
+            inner.app_sender = Some(app_sender);
+            inner.blocks.insert(genesis_id, (genesis, Status::Accepted));
+            inner.preferred = genesis_id;
+            inner.last_accepted = genesis_id;
+            Ok(())
+        }
+
+        async fn set_state(&self, _state: State) -> Result<()> {
+            Ok(())
+        }
+
+        async fn shutdown(&self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn version(&self) -> Result<String> {
+            Ok("0.0.0".to_string())
+        }
+
+        async fn create_static_handlers(
+            &mut self,
+        ) -> Result<HashMap<String, HttpHandler<Self::StaticHandler>>> {
+            Ok(HashMap::new())
+        }
+
+        async fn create_handlers(
+            &mut self,
+        ) -> Result<HashMap<String, HttpHandler<Self::ChainHandler>>> {
+            Ok(HashMap::new())
+        }
+    }
+
+    #[tonic::async_trait]
+    impl NetworkAppHandler for Vm {
+        /// Echoes the request back to the peer.
+        async fn app_request(
+            &self,
+            node_id: &ids::node::Id,
+            request_id: u32,
+            _deadline: DateTime<Utc>,
+            request: &[u8],
+        ) -> Result<()> {
+            let inner = self.inner.read().await;
+            let app_sender = inner.app_sender.as_ref().expect("vm not initialized");
+            app_sender
+                .send_app_response(*node_id, request_id, request.to_vec())
+                .await
+        }
+
+        async fn app_request_failed(&self, _: &ids::node::Id, _: u32) -> Result<()> {
+            Ok(())
+        }
+
+        async fn app_response(&self, _: &ids::node::Id, _: u32, _: &[u8]) -> Result<()> {
+            Ok(())
+        }
+
+        /// Adds the gossiped payload to the mempool.
+        async fn app_gossip(&self, _node_id: &ids::node::Id, msg: &[u8]) -> Result<()> {
+            let mut inner = self.inner.write().await;
+            inner.mempool.push_back(msg.to_vec());
+            let to_engine = inner.to_engine.as_ref().expect("vm not initialized");
+            to_engine
+                .send(Message::PendingTxs)
+                .await
+                .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))
+        }
+    }
+
+    #[tonic::async_trait]
+    impl CrossChainAppHandler for Vm {
+        async fn cross_chain_app_request(
+            &self,
+            _: &ids::Id,
+            _: u32,
+            _: DateTime<Utc>,
+            _: &[u8],
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        async fn cross_chain_app_request_failed(&self, _: &ids::Id, _: u32) -> Result<()> {
+            Ok(())
+        }
+
+        async fn cross_chain_app_response(&self, _: &ids::Id, _: u32, _: &[u8]) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    impl AppHandler for Vm {}
+
+    #[tonic::async_trait]
+    impl Connector for Vm {
+        async fn connected(&self, _id: &ids::node::Id) -> Result<()> {
+            Ok(())
+        }
+
+        async fn disconnected(&self, _id: &ids::node::Id) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tonic::async_trait]
+    impl Checkable for Vm {
+        async fn health_check(&self) -> Result<Vec<u8>> {
+            Ok(b"ok".to_vec())
+        }
+    }
+
+    #[tonic::async_trait]
+    impl Getter for Vm {
+        type Block = TestBlock;
+
+        async fn get_block(&self, id: ids::Id) -> Result<TestBlock> {
+            let bytes = match self.inner.read().await.blocks.get(&id) {
+                Some((bytes, _)) => bytes.clone(),
+                None => {
+                    return Err(Error::new(
+                        ErrorKind::NotFound,
+                        format!("block {id} not found"),
+                    ))
+                }
+            };
+            Ok(self.block(bytes).await)
+        }
+    }
+
+    #[tonic::async_trait]
+    impl Parser for Vm {
+        type Block = TestBlock;
+
+        async fn parse_block(&self, bytes: &[u8]) -> Result<TestBlock> {
+            if bytes.len() < ids::LEN + 8 {
+                return Err(Error::new(ErrorKind::InvalidData, "block too short"));
+            }
+            Ok(self.block(bytes.to_vec()).await)
+        }
+    }
+
+    #[tonic::async_trait]
+    impl BatchedChainVm for Vm {
+        type Block = TestBlock;
+
+        async fn get_ancestors(
+            &self,
+            _block_id: ids::Id,
+            _max_block_num: i32,
+            _max_block_size: i32,
+            _max_block_retrival_time: Duration,
+        ) -> Result<Vec<Bytes>> {
+            Ok(Vec::new())
+        }
+
+        async fn batched_parse_block(&self, blocks: &[Vec<u8>]) -> Result<Vec<TestBlock>> {
+            let mut parsed = Vec::with_capacity(blocks.len());
+            for bytes in blocks {
+                parsed.push(self.parse_block(bytes).await?);
+            }
+            Ok(parsed)
+        }
+    }
+
+    #[tonic::async_trait]
+    impl BuildBlockWithContextChainVM for Vm {
+        type Block = TestBlock;
+
+        async fn build_block_with_context(
+            &self,
+            blk_context: &block::Context,
+        ) -> Result<TestBlock> {
+            let blk = self.build_block().await?;
+            self.inner
+                .write()
+                .await
+                .p_chain_heights
+                .push(blk_context.p_chain_height);
+            Ok(blk)
+        }
+    }
+
+    #[tonic::async_trait]
+    impl ChainVm for Vm {
+        type Block = TestBlock;
+
+        /// Builds the block of the oldest payload in the mempool on the preference.
+        async fn build_block(&self) -> Result<TestBlock> {
+            let bytes = {
+                let mut inner = self.inner.write().await;
+                let payload = inner
+                    .mempool
+                    .pop_front()
+                    .ok_or_else(|| Error::new(ErrorKind::Other, "no pending payloads"))?;
+                let preferred = inner.preferred;
+                let (parent, _) = &inner.blocks[&preferred];
+                let height = u64::from_be_bytes(parent[ids::LEN..ids::LEN + 8].try_into().unwrap());
+                encode(&preferred, height + 1, &payload)
+            };
+            Ok(self.block(bytes).await)
+        }
+
+        async fn issue_tx(&self) -> Result<TestBlock> {
+            Err(Error::new(ErrorKind::Unsupported, "issue_tx not supported"))
+        }
+
+        async fn set_preference(&self, id: ids::Id) -> Result<()> {
+            self.inner.write().await.preferred = id;
+            Ok(())
+        }
+
+        async fn last_accepted(&self) -> Result<ids::Id> {
+            Ok(self.inner.read().await.last_accepted)
+        }
+
+        fn block_builder_with_context(
+            &self,
+        ) -> Option<&dyn BuildBlockWithContextChainVM<Block = TestBlock>> {
+            if self.with_context {
+                Some(self)
+            } else {
+                None
+            }
+        }
+
+        fn height_indexed(&self) -> Option<&dyn HeightIndexedChainVm> {
+            self.height_index
+                .as_ref()
+                .map(|height_index| height_index as &dyn HeightIndexedChainVm)
+        }
+
+        fn state_syncable(&self) -> Option<&dyn StateSyncableVm> {
+            self.state_sync
+                .as_ref()
+                .map(|state_sync| state_sync as &dyn StateSyncableVm)
+        }
+    }
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features subnet -- subnet::rpc::harness::test_harness --exact --show-output
+#[tokio::test]
+async fn test_harness() {
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Debug)
+        .is_test(true)
+        .try_init();
+
+    let harness = Harness::new(testvm::Vm::new()).await.unwrap();
+    let genesis = harness.initialize(b"genesis").await.unwrap();
+    assert_eq!(genesis.height, 0);
+    let genesis_id = ids::Id::from_slice(&genesis.last_accepted_id);
+
+    let db = harness.database();
+    assert_eq!(
+        db.get(testvm::LAST_ACCEPTED_KEY).await.unwrap(),
+        genesis_id.to_vec()
+    );
+
+    // gossiped payloads are built into a block on the pending txs notification
+    let peer = ids::node::Id::from_slice(&[2; ids::node::LEN]);
+    harness.connected(&peer, "lux/1.0.0").await.unwrap();
+    harness.app_gossip(&peer, b"hello").await.unwrap();
+    let blk = harness
+        .build_block_on_pending_txs(Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert_eq!(blk.height, 1);
+    assert_eq!(ids::Id::from_slice(&blk.parent_id), genesis_id);
+    let blk_id = ids::Id::from_slice(&blk.id);
+
+    harness.verify(&blk.bytes).await.unwrap();
+    let parsed = harness.parse_block(&blk.bytes).await.unwrap();
+    assert_eq!(ids::Id::from_slice(&parsed.id), blk_id);
+    // the test VM does not verify the blocks with the P-chain height
+    assert!(!parsed.verify_with_context);
+    let err = harness
+        .verify_with_context(&blk.bytes, 1)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("expected block.WithVerifyContext"));
+
+    harness.set_preference(&blk_id).await.unwrap();
+    harness.accept(&blk_id).await.unwrap();
+    let got = harness.get_block(&blk_id).await.unwrap();
+    assert_eq!(
+        got.status,
+        crate::choices::status::Status::Accepted.to_i32()
+    );
+    assert_eq!(
+        db.get(testvm::LAST_ACCEPTED_KEY).await.unwrap(),
+        blk_id.to_vec()
+    );
+
+    // no payload is pending
+    assert!(harness.build_block().await.is_err());
+    // the test VM builds the blocks without the P-chain height
+    harness.app_gossip(&peer, b"world").await.unwrap();
+    harness.recv_message(Duration::from_secs(5)).await.unwrap();
+    let blk = harness.build_block_with_context(1).await.unwrap();
+    assert_eq!(blk.height, 2);
+    assert!(!blk.verify_with_context);
+    assert_eq!(
+        harness
+            .recv_message(Duration::from_millis(100))
+            .await
+            .unwrap_err()
+            .kind(),
+        ErrorKind::TimedOut
+    );
+
+    // the requests are echoed through the app sender
+    harness
+        .app_request(
+            &peer,
+            7,
+            Utc::now() + chrono::Duration::seconds(10),
+            b"ping",
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        harness.sent_app_messages(),
+        vec![SentMessage::AppResponse {
+            node_id: peer,
+            request_id: 7,
+            response: b"ping".to_vec(),
+        }]
+    );
+    assert!(harness.sent_app_messages().is_empty());
+
+    harness.shutdown().await.unwrap();
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features subnet -- subnet::rpc::harness::test_harness_state_sync --exact --show-output
+#[tokio::test]
+async fn test_harness_state_sync() {
+    use crate::subnet::rpc::{errors, snowman::block::StateSyncMode};
+
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Debug)
+        .is_test(true)
+        .try_init();
+
+    // the VM without the state sync is bootstrapped instead
+    let harness = Harness::new(testvm::Vm::new()).await.unwrap();
+    let not_implemented = errors::Error::StateSyncableVMNotImplemented.to_i32();
+    let resp = harness.state_sync_enabled().await.unwrap();
+    assert!(!resp.enabled);
+    assert_eq!(resp.err, 0);
+    assert_eq!(
+        harness.get_ongoing_sync_state_summary().await.unwrap().err,
+        not_implemented
+    );
+    assert_eq!(
+        harness.get_last_state_summary().await.unwrap().err,
+        not_implemented
+    );
+    let summary = testvm::encode_summary(10, b"ten");
+    assert_eq!(
+        harness.parse_state_summary(&summary).await.unwrap().err,
+        not_implemented
+    );
+    assert_eq!(
+        harness.get_state_summary(10).await.unwrap().err,
+        not_implemented
+    );
+    let resp = harness.state_summary_accept(&summary).await.unwrap();
+    assert_eq!(resp.mode, 0);
+    assert_eq!(resp.err, not_implemented);
+    harness.shutdown().await.unwrap();
+
+    let harness = Harness::new(testvm::Vm::with_state_sync(
+        StateSyncMode::Static,
+        &[(10, b"ten"), (20, b"twenty")],
+    ))
+    .await
+    .unwrap();
+    let not_found = errors::Error::NotFound.to_i32();
+    let resp = harness.state_sync_enabled().await.unwrap();
+    assert!(resp.enabled);
+    assert_eq!(resp.err, 0);
+
+    let last = testvm::encode_summary(20, b"twenty");
+    let resp = harness.get_last_state_summary().await.unwrap();
+    assert_eq!(resp.err, 0);
+    assert_eq!(ids::Id::from_slice(&resp.id), ids::Id::sha256(&last));
+    assert_eq!(resp.height, 20);
+    assert_eq!(resp.bytes.as_ref(), last.as_slice());
+
+    let resp = harness.get_state_summary(10).await.unwrap();
+    assert_eq!(resp.err, 0);
+    assert_eq!(ids::Id::from_slice(&resp.id), ids::Id::sha256(&summary));
+    assert_eq!(resp.bytes.as_ref(), summary.as_slice());
+    assert_eq!(harness.get_state_summary(15).await.unwrap().err, not_found);
+    assert_eq!(
+        harness.get_ongoing_sync_state_summary().await.unwrap().err,
+        not_found
+    );
+
+    let resp = harness.parse_state_summary(&summary).await.unwrap();
+    assert_eq!(resp.err, 0);
+    assert_eq!(ids::Id::from_slice(&resp.id), ids::Id::sha256(&summary));
+    assert_eq!(resp.height, 10);
+    // not one of the common errors, so fails the RPC
+    assert!(harness.parse_state_summary(b"short").await.is_err());
+
+    let resp = harness.state_summary_accept(&summary).await.unwrap();
+    assert_eq!(resp.mode, StateSyncMode::Static.to_i32());
+    assert_eq!(resp.err, 0);
+    // the VM does not serve the summary to sync to
+    let resp = harness
+        .state_summary_accept(&testvm::encode_summary(15, b"fifteen"))
+        .await
+        .unwrap();
+    assert_eq!(resp.mode, 0);
+    assert_eq!(resp.err, not_found);
+
+    harness.shutdown().await.unwrap();
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features subnet -- subnet::rpc::harness::test_harness_with_context --exact --show-output
+#[tokio::test]
+async fn test_harness_with_context() {
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Debug)
+        .is_test(true)
+        .try_init();
+
+    let vm = testvm::Vm::with_context();
+    let harness = Harness::new(vm.clone()).await.unwrap();
+    let genesis = harness.initialize(b"genesis").await.unwrap();
+    let genesis_id = ids::Id::from_slice(&genesis.last_accepted_id);
+
+    let peer = ids::node::Id::from_slice(&[2; ids::node::LEN]);
+    harness.app_gossip(&peer, b"hello").await.unwrap();
+    harness.recv_message(Duration::from_secs(5)).await.unwrap();
+    let blk = harness.build_block_with_context(7).await.unwrap();
+    assert_eq!(ids::Id::from_slice(&blk.parent_id), genesis_id);
+    assert!(blk.verify_with_context);
+    assert_eq!(vm.p_chain_heights().await, vec![7]);
+
+    let parsed = harness.parse_block(&blk.bytes).await.unwrap();
+    assert!(parsed.verify_with_context);
+    harness.verify_with_context(&blk.bytes, 8).await.unwrap();
+    assert_eq!(vm.p_chain_heights().await, vec![7, 8]);
+
+    // the request without the P-chain height is built and verified as is
+    harness.app_gossip(&peer, b"world").await.unwrap();
+    harness.recv_message(Duration::from_secs(5)).await.unwrap();
+    let blk = harness.build_block().await.unwrap();
+    harness.verify(&blk.bytes).await.unwrap();
+    assert_eq!(vm.p_chain_heights().await, vec![7, 8]);
+
+    harness.shutdown().await.unwrap();
+}
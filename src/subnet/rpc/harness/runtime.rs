@@ -0,0 +1,44 @@
+//! Runtime engine which receives the handshake of a VM plugin, to test
+//! [`serve`](crate::subnet::rpc::vm::serve) end to end.
+use crate::proto::{
+    pb::{self, google::protobuf::Empty, vm::runtime::InitializeRequest},
+    PROTOCOL_VERSION,
+};
+use tokio::sync::mpsc;
+use tonic::{Request, Response, Status};
+
+/// Sends the address of the VM server to the channel once the VM plugin
+/// completes the handshake. The plugin finds the runtime by the
+/// "LUX_VM_RUNTIME_ENGINE_ADDR" environment variable.
+///
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/vms/rpcchainvm/runtime/subprocess>
+#[derive(Debug, Clone)]
+pub struct Runtime {
+    tx: mpsc::UnboundedSender<String>,
+}
+
+impl Runtime {
+    pub fn new(tx: mpsc::UnboundedSender<String>) -> Self {
+        Self { tx }
+    }
+}
+
+#[tonic::async_trait]
+impl pb::vm::runtime::runtime_server::Runtime for Runtime {
+    async fn initialize(
+        &self,
+        request: Request<InitializeRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        let req = request.into_inner();
+        if req.protocol_version != PROTOCOL_VERSION {
+            return Err(Status::failed_precondition(format!(
+                "protocol version mismatch: expected {PROTOCOL_VERSION}, got {}",
+                req.protocol_version
+            )));
+        }
+        self.tx
+            .send(req.addr)
+            .map_err(|_| Status::unavailable("harness dropped"))?;
+        Ok(Response::new(Empty {}))
+    }
+}
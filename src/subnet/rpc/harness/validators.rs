@@ -0,0 +1,95 @@
+//! In-memory validator state of the P-chain.
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{Error, ErrorKind, Result},
+    sync::{Arc, RwLock},
+};
+
+use crate::{ids, subnet::rpc::snow::validators::GetValidatorOutput};
+
+#[derive(Debug, Default)]
+struct Inner {
+    minimum_height: u64,
+    current_height: u64,
+    subnet_ids: HashMap<ids::Id, ids::Id>,
+    validator_sets: HashMap<ids::Id, BTreeMap<ids::node::Id, GetValidatorOutput>>,
+}
+
+/// Validator state set by the test, whose validator sets do not change
+/// with the height.
+#[derive(Debug, Clone, Default)]
+pub struct ValidatorState {
+    inner: Arc<RwLock<Inner>>,
+}
+
+impl ValidatorState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_minimum_height(&self, height: u64) {
+        self.inner.write().unwrap().minimum_height = height;
+    }
+
+    pub fn set_current_height(&self, height: u64) {
+        self.inner.write().unwrap().current_height = height;
+    }
+
+    /// Sets the subnet the chain is validated by.
+    pub fn set_subnet_id(&self, chain_id: ids::Id, subnet_id: ids::Id) {
+        self.inner
+            .write()
+            .unwrap()
+            .subnet_ids
+            .insert(chain_id, subnet_id);
+    }
+
+    /// Sets the validators of the subnet, at all heights.
+    pub fn set_validator_set(&self, subnet_id: ids::Id, validators: Vec<GetValidatorOutput>) {
+        self.inner.write().unwrap().validator_sets.insert(
+            subnet_id,
+            validators.into_iter().map(|v| (v.node_id, v)).collect(),
+        );
+    }
+}
+
+#[tonic::async_trait]
+impl crate::subnet::rpc::snow::validators::State for ValidatorState {
+    async fn get_minimum_height(&self) -> Result<u64> {
+        Ok(self.inner.read().unwrap().minimum_height)
+    }
+
+    async fn get_current_height(&self) -> Result<u64> {
+        Ok(self.inner.read().unwrap().current_height)
+    }
+
+    async fn get_subnet_id(&self, chain_id: ids::Id) -> Result<ids::Id> {
+        self.inner
+            .read()
+            .unwrap()
+            .subnet_ids
+            .get(&chain_id)
+            .copied()
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::NotFound,
+                    format!("subnet of chain {chain_id} not found"),
+                )
+            })
+    }
+
+    async fn get_validator_set(
+        &self,
+        _height: u64,
+        subnet_id: ids::Id,
+    ) -> Result<BTreeMap<ids::node::Id, GetValidatorOutput>> {
+        Ok(self
+            .inner
+            .read()
+            .unwrap()
+            .validator_sets
+            .get(&subnet_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+}
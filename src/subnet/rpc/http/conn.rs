@@ -0,0 +1,179 @@
+//! Hijacked HTTP connection of the VM handlers, served by the node over gRPC.
+use std::{
+    io::{self, Error, ErrorKind},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::{
+    proto::pb::{
+        google::protobuf::Empty,
+        http::responsewriter::HijackResponse,
+        io::reader::{reader_client::ReaderClient, ReadRequest},
+        net::conn::{conn_client::ConnClient, WriteRequest},
+    },
+    subnet::rpc::utils::grpc,
+};
+use bytes::Bytes;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, DuplexStream, ReadBuf};
+
+/// Maximum number of bytes read or written per gRPC call.
+const CHUNK_SIZE: usize = 32 * 1024;
+
+/// Error message of the remote reader at the end of the stream.
+/// ref. <https://pkg.go.dev/io#EOF>
+const EOF: &str = "EOF";
+
+/// Connection taken over from the HTTP server of the node, which reads from
+/// and writes to the client as [`AsyncRead`] and [`AsyncWrite`] (e.g., for
+/// the WebSocket streams). Dropping or shutting down the connection closes
+/// the client connection.
+///
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/vms/rpcchainvm/gconn#Client>
+#[derive(Debug)]
+pub struct Conn {
+    local_network: String,
+    local_addr: String,
+    remote_network: String,
+    remote_addr: String,
+    stream: DuplexStream,
+}
+
+impl Conn {
+    /// Connects to the connection, reader and writer services of the
+    /// hijacked connection, and starts relaying the bytes in the background.
+    pub async fn connect(resp: HijackResponse) -> io::Result<Self> {
+        let client_conn = grpc::default_client(&resp.server_addr)?
+            .connect()
+            .await
+            .map_err(|e| {
+                Error::new(
+                    ErrorKind::Other,
+                    format!(
+                        "failed to create client conn from {}: {e}",
+                        resp.server_addr
+                    ),
+                )
+            })?;
+        let mut reader = ReaderClient::new(client_conn.clone());
+        let mut conn = ConnClient::new(client_conn);
+
+        let (stream, relay) = tokio::io::duplex(CHUNK_SIZE);
+        let (mut relay_reader, mut relay_writer) = tokio::io::split(relay);
+
+        // client -> handler, until the client closes the connection
+        // the remote reader also holds the bytes buffered before the hijack
+        tokio::spawn(async move {
+            loop {
+                let resp = match reader
+                    .read(ReadRequest {
+                        length: CHUNK_SIZE as i32,
+                    })
+                    .await
+                {
+                    Ok(resp) => resp.into_inner(),
+                    Err(e) => {
+                        log::debug!("hijacked conn read failed: {e}");
+                        break;
+                    }
+                };
+                if !resp.read.is_empty() && relay_writer.write_all(&resp.read).await.is_err() {
+                    break;
+                }
+                if let Some(e) = resp.error {
+                    if e != EOF {
+                        log::debug!("hijacked conn read failed: {e}");
+                    }
+                    break;
+                }
+            }
+            let _ = relay_writer.shutdown().await;
+        });
+
+        // handler -> client, until the handler closes the connection
+        tokio::spawn(async move {
+            let mut buf = vec![0u8; CHUNK_SIZE];
+            loop {
+                let n = match relay_reader.read(&mut buf).await {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => n,
+                };
+                let resp = conn
+                    .write(WriteRequest {
+                        payload: Bytes::copy_from_slice(&buf[..n]),
+                    })
+                    .await;
+                match resp.map(|resp| resp.into_inner().error) {
+                    Ok(None) => {}
+                    Ok(Some(e)) => {
+                        log::debug!("hijacked conn write failed: {e}");
+                        break;
+                    }
+                    Err(e) => {
+                        log::debug!("hijacked conn write failed: {e}");
+                        break;
+                    }
+                }
+            }
+            if let Err(e) = conn.close(Empty {}).await {
+                log::debug!("hijacked conn close failed: {e}");
+            }
+        });
+
+        Ok(Self {
+            local_network: resp.local_network,
+            local_addr: resp.local_string,
+            remote_network: resp.remote_network,
+            remote_addr: resp.remote_string,
+            stream,
+        })
+    }
+
+    /// Returns the network (e.g., "tcp") of the local address.
+    pub fn local_network(&self) -> &str {
+        &self.local_network
+    }
+
+    /// Returns the local address of the node HTTP server.
+    pub fn local_addr(&self) -> &str {
+        &self.local_addr
+    }
+
+    /// Returns the network (e.g., "tcp") of the remote address.
+    pub fn remote_network(&self) -> &str {
+        &self.remote_network
+    }
+
+    /// Returns the address of the client.
+    pub fn remote_addr(&self) -> &str {
+        &self.remote_addr
+    }
+}
+
+impl AsyncRead for Conn {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for Conn {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.stream).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_shutdown(cx)
+    }
+}
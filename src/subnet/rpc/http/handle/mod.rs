@@ -2,10 +2,29 @@ use std::io;
 
 use bytes::Bytes;
 
-use crate::proto::http::Element;
+use crate::{proto::http::Element, subnet::rpc::http::responsewriter::ResponseWriter};
 
 #[tonic::async_trait]
 pub trait Handle: Send + Sync + Clone {
     /// Provides handling of HTTP requests.
     async fn request(&self, req: &Bytes, headers: &[Element]) -> io::Result<(Bytes, Vec<Element>)>;
+
+    /// Serves the request by writing the response to the writer, to set the
+    /// status code and headers, stream the response or upgrade the connection
+    /// (see [`crate::subnet::rpc::http::websocket::upgrade`]).
+    /// Defaults to writing the response of [`Handle::request`] with "200 OK".
+    ///
+    /// ref. <https://pkg.go.dev/net/http#Handler>
+    async fn serve_http(
+        &self,
+        req: http::Request<Bytes>,
+        w: &mut dyn ResponseWriter,
+    ) -> io::Result<()> {
+        let headers = super::to_proto_headers(req.headers());
+        let (body, headers) = self.request(req.body(), &headers).await?;
+
+        w.header().extend(super::from_proto_headers_lossy(&headers));
+        w.write(&body).await?;
+        Ok(())
+    }
 }
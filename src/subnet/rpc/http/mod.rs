@@ -1,6 +1,13 @@
 pub mod client;
+pub mod conn;
 pub mod handle;
+pub mod responsewriter;
 pub mod server;
+pub mod websocket;
+
+use std::io::{self, Error, ErrorKind};
+
+use crate::proto::pb;
 
 /// ref: <https://pkg.go.dev/net/http#Handler>
 #[tonic::async_trait]
@@ -15,3 +22,114 @@ pub trait Handler {
         req: http::Request<Vec<u8>>,
     ) -> std::io::Result<http::Response<Vec<u8>>>;
 }
+
+/// Converts [http::HeaderMap] to the proto headers, one element per key.
+pub fn to_proto_headers(headers: &http::HeaderMap) -> Vec<pb::http::Element> {
+    headers
+        .keys()
+        .map(|key| pb::http::Element {
+            key: key.to_string(),
+            values: headers
+                .get_all(key)
+                .iter()
+                .map(|v| String::from_utf8_lossy(v.as_bytes()).to_string())
+                .collect(),
+        })
+        .collect()
+}
+
+/// Converts the proto headers to [http::HeaderMap].
+pub fn from_proto_headers(headers: &[pb::http::Element]) -> io::Result<http::HeaderMap> {
+    let mut header_map = http::HeaderMap::with_capacity(headers.len());
+    for element in headers {
+        let key = http::header::HeaderName::from_bytes(element.key.as_bytes()).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("invalid header name '{}': {e}", element.key),
+            )
+        })?;
+        for value in element.values.iter() {
+            let value = http::HeaderValue::from_str(value).map_err(|e| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("invalid header value of '{}': {e}", element.key),
+                )
+            })?;
+            header_map.append(key.clone(), value);
+        }
+    }
+    Ok(header_map)
+}
+
+/// Converts the proto headers to [http::HeaderMap], skipping the headers
+/// whose name or value is not valid in HTTP (e.g., with the control
+/// characters), for the simple requests whose headers are only passed on.
+pub fn from_proto_headers_lossy(headers: &[pb::http::Element]) -> http::HeaderMap {
+    let mut header_map = http::HeaderMap::with_capacity(headers.len());
+    for element in headers {
+        let key = match http::header::HeaderName::from_bytes(element.key.as_bytes()) {
+            Ok(key) => key,
+            Err(e) => {
+                log::warn!("skipping invalid header name '{}': {e}", element.key);
+                continue;
+            }
+        };
+        for value in element.values.iter() {
+            match http::HeaderValue::from_bytes(value.as_bytes()) {
+                Ok(value) => {
+                    header_map.append(key.clone(), value);
+                }
+                Err(e) => log::warn!("skipping invalid header value of '{}': {e}", element.key),
+            }
+        }
+    }
+    header_map
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features subnet -- subnet::rpc::http::test_proto_headers --exact --show-output
+#[test]
+fn test_proto_headers() {
+    let elements = vec![
+        pb::http::Element {
+            key: "Content-Type".to_string(),
+            values: vec!["application/json".to_string()],
+        },
+        pb::http::Element {
+            key: "x-foo".to_string(),
+            values: vec!["a".to_string(), "b".to_string()],
+        },
+    ];
+    let headers = from_proto_headers(&elements).unwrap();
+    assert_eq!(headers.get("content-type").unwrap(), "application/json");
+    assert_eq!(headers.get_all("x-foo").iter().count(), 2);
+
+    let mut converted = to_proto_headers(&headers);
+    converted.sort_by(|a, b| a.key.cmp(&b.key));
+    assert_eq!(converted[0].key, "content-type");
+    assert_eq!(converted[1].values, elements[1].values);
+
+    assert!(from_proto_headers(&[pb::http::Element {
+        key: "bad key".to_string(),
+        values: vec![],
+    }])
+    .is_err());
+
+    // the invalid headers are skipped, and the non-ASCII values are kept
+    let headers = from_proto_headers_lossy(&[
+        elements[0].clone(),
+        pb::http::Element {
+            key: "bad key".to_string(),
+            values: vec!["a".to_string()],
+        },
+        pb::http::Element {
+            key: "x-bar".to_string(),
+            values: vec!["line\nbreak".to_string(), "caf\u{e9}".to_string()],
+        },
+    ]);
+    assert_eq!(headers.len(), 2);
+    assert_eq!(headers.get("content-type").unwrap(), "application/json");
+    assert_eq!(
+        headers.get("x-bar").unwrap().as_bytes(),
+        "caf\u{e9}".as_bytes()
+    );
+}
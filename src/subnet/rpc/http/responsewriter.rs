@@ -0,0 +1,200 @@
+//! Response writers of the VM HTTP handlers.
+use std::io::{self, Error, ErrorKind};
+
+use crate::proto::pb::{
+    self,
+    google::protobuf::Empty,
+    http::responsewriter::{writer_client::WriterClient, Header, WriteHeaderRequest, WriteRequest},
+};
+use bytes::{Bytes, BytesMut};
+use tonic::transport::Channel;
+
+use super::conn::Conn;
+
+/// Constructs the HTTP response of a VM handler, which can set the status
+/// code and headers, stream the response body, or take over the underlying
+/// connection (e.g., to upgrade to WebSocket).
+///
+/// ref. <https://pkg.go.dev/net/http#ResponseWriter>
+#[tonic::async_trait]
+pub trait ResponseWriter: Send {
+    /// Returns the headers to be sent by "write_header" or the first "write".
+    fn header(&mut self) -> &mut http::HeaderMap;
+
+    /// Sends the headers with the status code. If not called explicitly,
+    /// the first "write" sends the headers with "200 OK".
+    async fn write_header(&mut self, status_code: http::StatusCode) -> io::Result<()>;
+
+    /// Writes the payload as the part of the response body, and returns the
+    /// number of bytes written.
+    async fn write(&mut self, payload: &[u8]) -> io::Result<usize>;
+
+    /// Sends any buffered data to the client, to stream the response.
+    async fn flush(&mut self) -> io::Result<()>;
+
+    /// Takes over the connection from the HTTP server, after which the handler
+    /// is responsible for the connection (e.g., WebSocket).
+    ///
+    /// ref. <https://pkg.go.dev/net/http#Hijacker>
+    async fn hijack(&mut self) -> io::Result<Conn>;
+}
+
+/// Writes the response to the HTTP server of the node over gRPC.
+///
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/vms/rpcchainvm/ghttp/gresponsewriter#Client>
+pub struct Client {
+    header: http::HeaderMap,
+    inner: WriterClient<Channel>,
+}
+
+impl Client {
+    pub fn new(header: http::HeaderMap, client_conn: Channel) -> Self {
+        Self {
+            header,
+            inner: WriterClient::new(client_conn),
+        }
+    }
+
+    fn proto_headers(&self) -> Vec<Header> {
+        super::to_proto_headers(&self.header)
+            .into_iter()
+            .map(|e| Header {
+                key: e.key,
+                values: e.values,
+            })
+            .collect()
+    }
+}
+
+#[tonic::async_trait]
+impl ResponseWriter for Client {
+    fn header(&mut self) -> &mut http::HeaderMap {
+        &mut self.header
+    }
+
+    async fn write_header(&mut self, status_code: http::StatusCode) -> io::Result<()> {
+        self.inner
+            .write_header(WriteHeaderRequest {
+                headers: self.proto_headers(),
+                status_code: i32::from(status_code.as_u16()),
+            })
+            .await
+            .map_err(|e| Error::new(ErrorKind::Other, format!("write header failed: {e}")))?;
+        Ok(())
+    }
+
+    async fn write(&mut self, payload: &[u8]) -> io::Result<usize> {
+        let resp = self
+            .inner
+            .write(WriteRequest {
+                headers: self.proto_headers(),
+                payload: Bytes::copy_from_slice(payload),
+            })
+            .await
+            .map_err(|e| Error::new(ErrorKind::Other, format!("write failed: {e}")))?;
+        Ok(resp.into_inner().written as usize)
+    }
+
+    async fn flush(&mut self) -> io::Result<()> {
+        self.inner
+            .flush(Empty {})
+            .await
+            .map_err(|e| Error::new(ErrorKind::Other, format!("flush failed: {e}")))?;
+        Ok(())
+    }
+
+    async fn hijack(&mut self) -> io::Result<Conn> {
+        let resp = self
+            .inner
+            .hijack(Empty {})
+            .await
+            .map_err(|e| Error::new(ErrorKind::Other, format!("hijack failed: {e}")))?
+            .into_inner();
+        Conn::connect(resp).await
+    }
+}
+
+/// Records the response in memory, for the simple (non-streaming) requests
+/// whose response is returned at once.
+#[derive(Debug, Default)]
+pub struct Recorder {
+    header: http::HeaderMap,
+    status_code: Option<http::StatusCode>,
+    body: BytesMut,
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the status code, "200 OK" if never set.
+    pub fn status_code(&self) -> http::StatusCode {
+        self.status_code.unwrap_or(http::StatusCode::OK)
+    }
+
+    /// Returns the response as the simple HTTP response.
+    pub fn to_proto(&self) -> pb::http::HandleSimpleHttpResponse {
+        pb::http::HandleSimpleHttpResponse {
+            code: i32::from(self.status_code().as_u16()),
+            headers: super::to_proto_headers(&self.header),
+            body: Bytes::copy_from_slice(&self.body),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl ResponseWriter for Recorder {
+    fn header(&mut self) -> &mut http::HeaderMap {
+        &mut self.header
+    }
+
+    async fn write_header(&mut self, status_code: http::StatusCode) -> io::Result<()> {
+        // same as "net/http", only the first status code is sent
+        if self.status_code.is_none() {
+            self.status_code = Some(status_code);
+        }
+        Ok(())
+    }
+
+    async fn write(&mut self, payload: &[u8]) -> io::Result<usize> {
+        self.write_header(http::StatusCode::OK).await?;
+        self.body.extend_from_slice(payload);
+        Ok(payload.len())
+    }
+
+    async fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    async fn hijack(&mut self) -> io::Result<Conn> {
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "simple HTTP requests can not be hijacked",
+        ))
+    }
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features subnet -- subnet::rpc::http::responsewriter::test_recorder --exact --show-output
+#[tokio::test]
+async fn test_recorder() {
+    let mut w = Recorder::new();
+    assert_eq!(w.status_code(), http::StatusCode::OK);
+
+    w.header()
+        .insert("content-type", "application/json".parse().unwrap());
+    w.header().append("x-foo", "a".parse().unwrap());
+    w.header().append("x-foo", "b".parse().unwrap());
+    w.write_header(http::StatusCode::NOT_FOUND).await.unwrap();
+    // ignored once the header is written
+    w.write_header(http::StatusCode::OK).await.unwrap();
+    assert_eq!(w.write(b"hello ").await.unwrap(), 6);
+    assert_eq!(w.write(b"world").await.unwrap(), 5);
+    assert_eq!(w.hijack().await.unwrap_err().kind(), ErrorKind::Unsupported);
+
+    let resp = w.to_proto();
+    assert_eq!(resp.code, 404);
+    assert_eq!(resp.body.as_ref(), b"hello world");
+    let foo = resp.headers.iter().find(|h| h.key == "x-foo").unwrap();
+    assert_eq!(foo.values, vec!["a".to_string(), "b".to_string()]);
+}
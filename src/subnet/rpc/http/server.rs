@@ -1,14 +1,22 @@
-use std::sync::Arc;
-
-use crate::proto::pb::{
-    self,
-    google::protobuf::Empty,
-    http::{HandleSimpleHttpRequest, HandleSimpleHttpResponse, HttpRequest},
+use std::{
+    io::{Error, ErrorKind},
+    sync::Arc,
 };
 
-use tonic::codegen::http;
+use crate::{
+    proto::pb::{
+        self,
+        google::protobuf::Empty,
+        http::{HandleSimpleHttpRequest, HandleSimpleHttpResponse, HttpRequest},
+    },
+    subnet::rpc::utils::grpc,
+};
+use prost::bytes::Bytes;
 
-use super::handle::Handle;
+use super::{
+    handle::Handle,
+    responsewriter::{Client as ResponseWriterClient, Recorder},
+};
 
 #[derive(Clone)]
 pub struct Server<T> {
@@ -32,12 +40,41 @@ impl<T> pb::http::http_server::Http for Server<T>
 where
     T: Handle + Send + Sync + 'static,
 {
-    /// handles http requests including websockets
+    /// handles http requests including websockets, writing the response to
+    /// the response writer served by the node
+    ///
+    /// ref. <https://pkg.go.dev/github.com/luxfi/node/vms/rpcchainvm/ghttp#Server.Handle>
     async fn handle(
         &self,
-        _request: tonic::Request<HttpRequest>,
+        request: tonic::Request<HttpRequest>,
     ) -> Result<tonic::Response<Empty>, tonic::Status> {
-        Err(tonic::Status::unimplemented("handle"))
+        let request = request.into_inner();
+        let response_writer = request
+            .response_writer
+            .ok_or_else(|| tonic::Status::invalid_argument("missing response writer"))?;
+        let req = request
+            .request
+            .ok_or_else(|| tonic::Status::invalid_argument("missing request"))?;
+
+        let client_conn = grpc::default_client(&response_writer.server_addr)?
+            .connect()
+            .await
+            .map_err(|e| {
+                tonic::Status::unknown(format!(
+                    "failed to create client conn from {}: {e}",
+                    response_writer.server_addr
+                ))
+            })?;
+        let mut writer = ResponseWriterClient::new(
+            super::from_proto_headers(&response_writer.header)?,
+            client_conn,
+        );
+
+        self.handle
+            .serve_http(get_http_request(req)?, &mut writer)
+            .await?;
+
+        Ok(tonic::Response::new(Empty {}))
     }
 
     /// handles http simple (non web-socket) requests
@@ -47,12 +84,451 @@ where
     ) -> Result<tonic::Response<HandleSimpleHttpResponse>, tonic::Status> {
         let request = request.into_inner();
 
-        let (body, headers) = self.handle.request(&request.body, &request.headers).await?;
+        let mut recorder = Recorder::new();
+        self.handle
+            .serve_http(get_http_simple_request(request)?, &mut recorder)
+            .await?;
+
+        Ok(tonic::Response::new(recorder.to_proto()))
+    }
+}
+
+/// convert from [pb::http::Request] to [http::Request]
+fn get_http_request(req: pb::http::Request) -> std::io::Result<http::Request<Bytes>> {
+    // "request_uri" is the unmodified request-target of the client
+    let uri = if req.request_uri.is_empty() {
+        req.url
+            .map(|url| {
+                if url.raw_query.is_empty() {
+                    url.path
+                } else {
+                    format!("{}?{}", url.path, url.raw_query)
+                }
+            })
+            .unwrap_or_else(|| "/".to_string())
+    } else {
+        req.request_uri
+    };
+
+    let mut headers = super::from_proto_headers(&req.header)?;
+    if !req.host.is_empty() && !headers.contains_key(http::header::HOST) {
+        let host = http::HeaderValue::from_str(&req.host)
+            .map_err(|e| Error::new(ErrorKind::InvalidInput, format!("invalid host: {e}")))?;
+        headers.insert(http::header::HOST, host);
+    }
+
+    new_http_request(&req.method, &uri, headers, req.body)
+}
+
+/// convert from [pb::http::HandleSimpleHttpRequest] to [http::Request]
+///
+/// The simple requests used to be passed on as the body and headers only,
+/// so the headers which are not valid in HTTP are skipped, and the method
+/// and URL which do not parse fall back to the defaults, rather than failing
+/// the request.
+fn get_http_simple_request(req: HandleSimpleHttpRequest) -> std::io::Result<http::Request<Bytes>> {
+    let headers = super::from_proto_headers_lossy(&req.headers);
+    let method = if req.method.is_empty() || http::Method::from_bytes(req.method.as_bytes()).is_ok()
+    {
+        req.method.as_str()
+    } else {
+        log::warn!("invalid method '{}', falling back to POST", req.method);
+        ""
+    };
+    let uri = if req.url.is_empty() || req.url.parse::<http::Uri>().is_ok() {
+        req.url.as_str()
+    } else {
+        log::warn!("invalid URL '{}', falling back to /", req.url);
+        ""
+    };
+    new_http_request(method, uri, headers, req.body)
+}
+
+fn new_http_request(
+    method: &str,
+    uri: &str,
+    headers: http::HeaderMap,
+    body: Bytes,
+) -> std::io::Result<http::Request<Bytes>> {
+    // the method and URL may be left empty (e.g., by the older nodes)
+    let method = if method.is_empty() { "POST" } else { method };
+    let uri = if uri.is_empty() { "/" } else { uri };
+
+    let mut http_req = http::Request::builder()
+        .method(method)
+        .uri(uri)
+        .body(body)
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("failed to generate http request {:?}", e),
+            )
+        })?;
+    *http_req.headers_mut() = headers;
+    Ok(http_req)
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features subnet -- subnet::rpc::http::server::test_handle --exact --show-output
+#[tokio::test]
+async fn test_handle() {
+    use std::{io, net::SocketAddr};
+
+    use crate::{
+        proto::pb::{
+            http::{
+                http_server::Http,
+                responsewriter::{self, writer_server},
+                ResponseWriter,
+            },
+            io::reader::{self, reader_server},
+            net::conn::{self, conn_server},
+        },
+        subnet::rpc::http::websocket,
+    };
+    use futures::{SinkExt, StreamExt};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt, DuplexStream, ReadHalf, WriteHalf},
+        net::TcpListener,
+        sync::Mutex,
+    };
+    use tokio_stream::wrappers::TcpListenerStream;
+    use tokio_tungstenite::{
+        tungstenite::{protocol::Role, Message},
+        WebSocketStream,
+    };
+
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .is_test(true)
+        .try_init();
+
+    #[derive(Clone)]
+    struct TestHandle;
+
+    #[tonic::async_trait]
+    impl Handle for TestHandle {
+        async fn request(
+            &self,
+            _req: &Bytes,
+            _headers: &[pb::http::Element],
+        ) -> io::Result<(Bytes, Vec<pb::http::Element>)> {
+            unreachable!("serve_http is implemented")
+        }
+
+        async fn serve_http(
+            &self,
+            req: http::Request<Bytes>,
+            w: &mut dyn super::responsewriter::ResponseWriter,
+        ) -> io::Result<()> {
+            if websocket::is_upgrade_request(&req) {
+                // echoes the first message
+                let mut ws = websocket::upgrade(&req, w).await?;
+                let msg = ws.next().await.unwrap().unwrap();
+                ws.send(msg).await.unwrap();
+                return Ok(());
+            }
+
+            w.header()
+                .insert("content-type", "text/plain".parse().unwrap());
+            w.write_header(http::StatusCode::CREATED).await?;
+            w.write(req.uri().path().as_bytes()).await?;
+            w.flush().await?;
+            w.write(&req.body()[..]).await?;
+            Ok(())
+        }
+    }
+
+    async fn serve<S>(svc: S) -> SocketAddr
+    where
+        S: tower_service::Service<
+                http::Request<hyper::Body>,
+                Response = http::Response<tonic::body::BoxBody>,
+                Error = std::convert::Infallible,
+            > + tonic::transport::NamedService
+            + Clone
+            + Send
+            + 'static,
+        S::Future: Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(svc)
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+        addr
+    }
+
+    /// Hijacked side of the client connection, served by the node.
+    struct HijackedConn {
+        rd: Mutex<ReadHalf<DuplexStream>>,
+        wr: Mutex<WriteHalf<DuplexStream>>,
+    }
+
+    #[tonic::async_trait]
+    impl reader_server::Reader for Arc<HijackedConn> {
+        async fn read(
+            &self,
+            req: tonic::Request<reader::ReadRequest>,
+        ) -> Result<tonic::Response<reader::ReadResponse>, tonic::Status> {
+            let mut buf = vec![0u8; req.into_inner().length as usize];
+            let n = self.rd.lock().await.read(&mut buf).await?;
+            Ok(tonic::Response::new(reader::ReadResponse {
+                read: Bytes::copy_from_slice(&buf[..n]),
+                error: if n == 0 {
+                    Some("EOF".to_string())
+                } else {
+                    None
+                },
+            }))
+        }
+    }
+
+    #[tonic::async_trait]
+    impl conn_server::Conn for Arc<HijackedConn> {
+        async fn read(
+            &self,
+            _req: tonic::Request<conn::ReadRequest>,
+        ) -> Result<tonic::Response<conn::ReadResponse>, tonic::Status> {
+            unreachable!("reads from the reader service")
+        }
+        async fn write(
+            &self,
+            req: tonic::Request<conn::WriteRequest>,
+        ) -> Result<tonic::Response<conn::WriteResponse>, tonic::Status> {
+            let payload = req.into_inner().payload;
+            self.wr.lock().await.write_all(&payload).await?;
+            Ok(tonic::Response::new(conn::WriteResponse {
+                length: payload.len() as i32,
+                error: None,
+            }))
+        }
+        async fn close(
+            &self,
+            _req: tonic::Request<Empty>,
+        ) -> Result<tonic::Response<Empty>, tonic::Status> {
+            self.wr.lock().await.shutdown().await?;
+            Ok(tonic::Response::new(Empty {}))
+        }
+        async fn set_deadline(
+            &self,
+            _req: tonic::Request<conn::SetDeadlineRequest>,
+        ) -> Result<tonic::Response<Empty>, tonic::Status> {
+            Ok(tonic::Response::new(Empty {}))
+        }
+        async fn set_read_deadline(
+            &self,
+            _req: tonic::Request<conn::SetDeadlineRequest>,
+        ) -> Result<tonic::Response<Empty>, tonic::Status> {
+            Ok(tonic::Response::new(Empty {}))
+        }
+        async fn set_write_deadline(
+            &self,
+            _req: tonic::Request<conn::SetDeadlineRequest>,
+        ) -> Result<tonic::Response<Empty>, tonic::Status> {
+            Ok(tonic::Response::new(Empty {}))
+        }
+    }
+
+    /// Records the response, and hands over the VM side of the connection
+    /// on hijack.
+    #[derive(Default)]
+    struct TestWriter {
+        status_code: Mutex<i32>,
+        headers: Mutex<Vec<responsewriter::Header>>,
+        body: Mutex<Vec<u8>>,
+        flushed: Mutex<usize>,
+        hijacked: Mutex<Option<DuplexStream>>,
+    }
+
+    #[tonic::async_trait]
+    impl writer_server::Writer for Arc<TestWriter> {
+        async fn write(
+            &self,
+            req: tonic::Request<responsewriter::WriteRequest>,
+        ) -> Result<tonic::Response<responsewriter::WriteResponse>, tonic::Status> {
+            let req = req.into_inner();
+            self.body.lock().await.extend_from_slice(&req.payload);
+            Ok(tonic::Response::new(responsewriter::WriteResponse {
+                written: req.payload.len() as i32,
+            }))
+        }
+        async fn write_header(
+            &self,
+            req: tonic::Request<responsewriter::WriteHeaderRequest>,
+        ) -> Result<tonic::Response<Empty>, tonic::Status> {
+            let req = req.into_inner();
+            *self.status_code.lock().await = req.status_code;
+            *self.headers.lock().await = req.headers;
+            Ok(tonic::Response::new(Empty {}))
+        }
+        async fn flush(
+            &self,
+            _req: tonic::Request<Empty>,
+        ) -> Result<tonic::Response<Empty>, tonic::Status> {
+            *self.flushed.lock().await += 1;
+            Ok(tonic::Response::new(Empty {}))
+        }
+        async fn hijack(
+            &self,
+            _req: tonic::Request<Empty>,
+        ) -> Result<tonic::Response<responsewriter::HijackResponse>, tonic::Status> {
+            let stream = self.hijacked.lock().await.take().unwrap();
+            let (rd, wr) = tokio::io::split(stream);
+            let hijacked = Arc::new(HijackedConn {
+                rd: Mutex::new(rd),
+                wr: Mutex::new(wr),
+            });
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            tokio::spawn(
+                tonic::transport::Server::builder()
+                    .add_service(reader_server::ReaderServer::new(hijacked.clone()))
+                    .add_service(conn_server::ConnServer::new(hijacked))
+                    .serve_with_incoming(TcpListenerStream::new(listener)),
+            );
+            Ok(tonic::Response::new(responsewriter::HijackResponse {
+                local_network: "tcp".to_string(),
+                local_string: "127.0.0.1:9650".to_string(),
+                remote_network: "tcp".to_string(),
+                remote_string: "127.0.0.1:12345".to_string(),
+                server_addr: addr.to_string(),
+            }))
+        }
+    }
+
+    let server = Server {
+        handle: Arc::new(TestHandle),
+    };
+
+    // simple request with the status code
+    let resp = server
+        .handle_simple(tonic::Request::new(HandleSimpleHttpRequest {
+            method: "POST".to_string(),
+            url: "/simple".to_string(),
+            headers: vec![],
+            body: Bytes::from_static(b" body"),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(resp.code, 201);
+    assert_eq!(resp.body.as_ref(), b"/simple body");
+    assert_eq!(resp.headers[0].key, "content-type");
 
-        Ok(tonic::Response::new(HandleSimpleHttpResponse {
-            code: http::StatusCode::OK.as_u16() as i32,
-            body,
-            headers,
+    // the invalid headers, method and URL of the simple request do not fail
+    // the handlers which only implement "request"
+    #[derive(Clone)]
+    struct EchoHandle;
+
+    #[tonic::async_trait]
+    impl Handle for EchoHandle {
+        async fn request(
+            &self,
+            req: &Bytes,
+            headers: &[pb::http::Element],
+        ) -> io::Result<(Bytes, Vec<pb::http::Element>)> {
+            Ok((req.clone(), headers.to_vec()))
+        }
+    }
+
+    let echo_server = Server {
+        handle: Arc::new(EchoHandle),
+    };
+    let resp = echo_server
+        .handle_simple(tonic::Request::new(HandleSimpleHttpRequest {
+            method: "BAD METHOD".to_string(),
+            url: "not a url".to_string(),
+            headers: vec![
+                pb::http::Element {
+                    key: "x-foo".to_string(),
+                    values: vec!["bar".to_string()],
+                },
+                pb::http::Element {
+                    key: "bad key".to_string(),
+                    values: vec!["a".to_string()],
+                },
+                pb::http::Element {
+                    key: "x-bad-value".to_string(),
+                    values: vec!["line\nbreak".to_string()],
+                },
+            ],
+            body: Bytes::from_static(b"echo"),
         }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(resp.code, 200);
+    assert_eq!(resp.body.as_ref(), b"echo");
+    assert_eq!(
+        resp.headers,
+        vec![pb::http::Element {
+            key: "x-foo".to_string(),
+            values: vec!["bar".to_string()],
+        }]
+    );
+
+    // streamed response
+    let writer = Arc::new(TestWriter::default());
+    let writer_addr = serve(writer_server::WriterServer::new(writer.clone())).await;
+    let http_request = |path: &str, header: Vec<pb::http::Element>| HttpRequest {
+        response_writer: Some(ResponseWriter {
+            header: vec![],
+            server_addr: writer_addr.to_string(),
+        }),
+        request: Some(pb::http::Request {
+            method: "GET".to_string(),
+            request_uri: path.to_string(),
+            header,
+            body: Bytes::from_static(b" body"),
+            host: "localhost".to_string(),
+            ..Default::default()
+        }),
+    };
+    server
+        .handle(tonic::Request::new(http_request("/stream", vec![])))
+        .await
+        .unwrap();
+    assert_eq!(*writer.status_code.lock().await, 201);
+    assert_eq!(writer.headers.lock().await[0].values, vec!["text/plain"]);
+    assert_eq!(writer.body.lock().await.as_slice(), b"/stream body");
+    assert_eq!(*writer.flushed.lock().await, 1);
+
+    // websocket
+    let (client_stream, vm_stream) = tokio::io::duplex(1024);
+    *writer.hijacked.lock().await = Some(vm_stream);
+    let header = |key: &str, value: &str| pb::http::Element {
+        key: key.to_string(),
+        values: vec![value.to_string()],
+    };
+    let req = http_request(
+        "/ws",
+        vec![
+            header("connection", "Upgrade"),
+            header("upgrade", "websocket"),
+            header("sec-websocket-version", "13"),
+            header("sec-websocket-key", "dGhlIHNhbXBsZSBub25jZQ=="),
+        ],
+    );
+    let handled = tokio::spawn(async move { server.handle(tonic::Request::new(req)).await });
+
+    let mut client_stream = client_stream;
+    let mut handshake = Vec::new();
+    while !handshake.ends_with(b"\r\n\r\n") {
+        let mut b = [0u8; 1];
+        client_stream.read_exact(&mut b).await.unwrap();
+        handshake.push(b[0]);
     }
+    let handshake = String::from_utf8(handshake).unwrap();
+    assert!(handshake.starts_with("HTTP/1.1 101 Switching Protocols\r\n"));
+    assert!(handshake.contains("Sec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo=\r\n"));
+
+    let mut ws = WebSocketStream::from_raw_socket(client_stream, Role::Client, None).await;
+    ws.send(Message::Text("hello".to_string())).await.unwrap();
+    assert_eq!(
+        ws.next().await.unwrap().unwrap(),
+        Message::Text("hello".to_string())
+    );
+    handled.await.unwrap().unwrap();
 }
@@ -0,0 +1,123 @@
+//! WebSocket upgrade of the VM handler requests (e.g., for subscriptions).
+use std::io::{self, Error, ErrorKind};
+
+use bytes::Bytes;
+use tokio::io::AsyncWriteExt;
+use tokio_tungstenite::{
+    tungstenite::{handshake::derive_accept_key, protocol::Role},
+    WebSocketStream,
+};
+
+use super::{conn::Conn, responsewriter::ResponseWriter};
+
+/// Returns true if the request asks to upgrade the connection to WebSocket.
+///
+/// ref. <https://datatracker.ietf.org/doc/html/rfc6455#section-4.2.1>
+pub fn is_upgrade_request<B>(req: &http::Request<B>) -> bool {
+    let has_token = |name: http::header::HeaderName, token: &str| {
+        req.headers().get_all(name).iter().any(|v| {
+            v.to_str()
+                .map(|v| v.split(',').any(|t| t.trim().eq_ignore_ascii_case(token)))
+                .unwrap_or(false)
+        })
+    };
+    req.method() == http::Method::GET
+        && has_token(http::header::CONNECTION, "upgrade")
+        && has_token(http::header::UPGRADE, "websocket")
+}
+
+/// Upgrades the connection of the request to WebSocket, by hijacking it from
+/// the node HTTP server and completing the handshake. Responds "400 Bad
+/// Request" if the request is not a valid WebSocket upgrade request.
+///
+/// ref. <https://pkg.go.dev/github.com/gorilla/websocket#Upgrader.Upgrade>
+pub async fn upgrade(
+    req: &http::Request<Bytes>,
+    w: &mut dyn ResponseWriter,
+) -> io::Result<WebSocketStream<Conn>> {
+    let key = match validate(req) {
+        Ok(key) => key,
+        Err(e) => {
+            w.write_header(http::StatusCode::BAD_REQUEST).await?;
+            w.write(e.to_string().as_bytes()).await?;
+            return Err(e);
+        }
+    };
+
+    let mut conn = w.hijack().await?;
+    let resp = format!(
+        "HTTP/1.1 101 Switching Protocols\r\n\
+         Upgrade: websocket\r\n\
+         Connection: Upgrade\r\n\
+         Sec-WebSocket-Accept: {}\r\n\r\n",
+        derive_accept_key(key.as_bytes())
+    );
+    conn.write_all(resp.as_bytes()).await?;
+    conn.flush().await?;
+
+    Ok(WebSocketStream::from_raw_socket(conn, Role::Server, None).await)
+}
+
+/// Returns the "Sec-WebSocket-Key" of the valid upgrade request.
+fn validate<B>(req: &http::Request<B>) -> io::Result<String> {
+    if !is_upgrade_request(req) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "not a websocket upgrade request",
+        ));
+    }
+
+    let version = req.headers().get(http::header::SEC_WEBSOCKET_VERSION);
+    if version.map(|v| v.as_bytes()) != Some(b"13") {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "unsupported websocket version (expected 13)",
+        ));
+    }
+
+    req.headers()
+        .get(http::header::SEC_WEBSOCKET_KEY)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_string())
+        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "missing Sec-WebSocket-Key"))
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features subnet -- subnet::rpc::http::websocket::test_validate --exact --show-output
+#[test]
+fn test_validate() {
+    let req = http::Request::builder()
+        .method("GET")
+        .uri("/ws")
+        .header("connection", "keep-alive, Upgrade")
+        .header("upgrade", "WebSocket")
+        .header("sec-websocket-version", "13")
+        .header("sec-websocket-key", "dGhlIHNhbXBsZSBub25jZQ==")
+        .body(())
+        .unwrap();
+    assert!(is_upgrade_request(&req));
+    assert_eq!(validate(&req).unwrap(), "dGhlIHNhbXBsZSBub25jZQ==");
+    // ref. <https://datatracker.ietf.org/doc/html/rfc6455#section-1.3>
+    assert_eq!(
+        derive_accept_key(b"dGhlIHNhbXBsZSBub25jZQ=="),
+        "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+    );
+
+    let req = http::Request::builder()
+        .method("POST")
+        .uri("/")
+        .body(())
+        .unwrap();
+    assert!(!is_upgrade_request(&req));
+    assert!(validate(&req).is_err());
+
+    let req = http::Request::builder()
+        .method("GET")
+        .uri("/ws")
+        .header("connection", "upgrade")
+        .header("upgrade", "websocket")
+        .header("sec-websocket-version", "8")
+        .body(())
+        .unwrap();
+    assert!(is_upgrade_request(&req));
+    assert!(validate(&req).is_err());
+}
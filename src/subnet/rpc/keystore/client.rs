@@ -0,0 +1,57 @@
+use std::io::{Error, ErrorKind, Result};
+
+use crate::{
+    proto::pb::keystore::{keystore_client, GetDatabaseRequest},
+    subnet::rpc::{
+        database::{rpcdb::client::DatabaseClient, BoxedDatabase},
+        utils::grpc,
+    },
+};
+use tonic::transport::Channel;
+
+/// A gRPC client which gets the user databases from the keystore of the node.
+///
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/api/keystore/gkeystore#Client>
+#[derive(Clone, Debug)]
+pub struct KeystoreClient {
+    inner: keystore_client::KeystoreClient<Channel>,
+}
+
+impl KeystoreClient {
+    pub fn new(client_conn: Channel) -> Self {
+        Self {
+            inner: keystore_client::KeystoreClient::new(client_conn),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl super::Keystore for KeystoreClient {
+    /// Returns the raw database of the user, whose values the node does not
+    /// encrypt.
+    async fn get_raw_database(&self, username: &str, password: &str) -> Result<BoxedDatabase> {
+        let mut client = self.inner.clone();
+        let resp = client
+            .get_database(GetDatabaseRequest {
+                username: username.to_string(),
+                password: password.to_string(),
+            })
+            .await
+            .map_err(|e| Error::new(ErrorKind::Other, format!("get_database failed: {e}")))?
+            .into_inner();
+
+        let client_conn = grpc::default_client(&resp.server_addr)?
+            .connect()
+            .await
+            .map_err(|e| {
+                Error::new(
+                    ErrorKind::Other,
+                    format!(
+                        "failed to create client conn from {}: {e}",
+                        resp.server_addr
+                    ),
+                )
+            })?;
+        Ok(DatabaseClient::new(client_conn))
+    }
+}
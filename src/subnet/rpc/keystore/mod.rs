@@ -0,0 +1,121 @@
+//! Per-user databases of the chain, kept by the keystore of the node.
+//! ref. <https://pkg.go.dev/github.com/luxfi/node/api/keystore>
+pub mod client;
+
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    io::{Error, ErrorKind, Result},
+    sync::Arc,
+};
+
+use tokio::sync::RwLock;
+
+use crate::subnet::rpc::database::{memdb, BoxedDatabase};
+
+/// Keystore of the chain, which gives access to the database of a user.
+///
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/api/keystore#BlockchainKeystore>
+#[tonic::async_trait]
+pub trait Keystore: Debug + Send + Sync {
+    /// Returns the database of the user for this chain, once the password
+    /// is verified. The values are stored as is, without the encryption of
+    /// the "GetDatabase" of the node, so the VM must encrypt the secrets
+    /// it stores.
+    ///
+    /// ref. <https://pkg.go.dev/github.com/luxfi/node/api/keystore#BlockchainKeystore>
+    async fn get_raw_database(&self, username: &str, password: &str) -> Result<BoxedDatabase>;
+}
+
+/// In-memory keystore, to unit test the VMs.
+#[derive(Clone, Default)]
+pub struct LocalKeystore {
+    /// Maps the username to the password and the database.
+    users: Arc<RwLock<HashMap<String, (String, BoxedDatabase)>>>,
+}
+
+impl Debug for LocalKeystore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalKeystore").finish_non_exhaustive()
+    }
+}
+
+impl LocalKeystore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates the user with an empty database.
+    pub async fn create_user(&self, username: &str, password: &str) -> Result<()> {
+        let mut users = self.users.write().await;
+        if users.contains_key(username) {
+            return Err(Error::new(
+                ErrorKind::AlreadyExists,
+                format!("user {username} already exists"),
+            ));
+        }
+        users.insert(
+            username.to_string(),
+            (password.to_string(), memdb::Database::new()),
+        );
+        Ok(())
+    }
+}
+
+#[tonic::async_trait]
+impl Keystore for LocalKeystore {
+    async fn get_raw_database(&self, username: &str, password: &str) -> Result<BoxedDatabase> {
+        let users = self.users.read().await;
+        match users.get(username) {
+            Some((pw, db)) if pw == password => Ok(db.clone()),
+            Some(_) => Err(Error::new(
+                ErrorKind::PermissionDenied,
+                "incorrect password",
+            )),
+            None => Err(Error::new(
+                ErrorKind::NotFound,
+                format!("user {username} not found"),
+            )),
+        }
+    }
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features subnet -- subnet::rpc::keystore::test_local_keystore --exact --show-output
+#[tokio::test]
+async fn test_local_keystore() {
+    let keystore = LocalKeystore::new();
+    keystore.create_user("alice", "pw").await.unwrap();
+    assert_eq!(
+        keystore
+            .create_user("alice", "pw")
+            .await
+            .unwrap_err()
+            .kind(),
+        ErrorKind::AlreadyExists
+    );
+
+    let mut db = keystore.get_raw_database("alice", "pw").await.unwrap();
+    db.put(b"foo", b"bar").await.unwrap();
+    // same database for the user
+    let db = keystore.get_raw_database("alice", "pw").await.unwrap();
+    assert_eq!(db.get(b"foo").await.unwrap(), b"bar");
+
+    assert_eq!(
+        keystore
+            .get_raw_database("alice", "bad")
+            .await
+            .err()
+            .unwrap()
+            .kind(),
+        ErrorKind::PermissionDenied
+    );
+    assert_eq!(
+        keystore
+            .get_raw_database("bob", "pw")
+            .await
+            .err()
+            .unwrap()
+            .kind(),
+        ErrorKind::NotFound
+    );
+}
@@ -0,0 +1,306 @@
+//! Mempool of the transactions pending to be built into blocks, which
+//! notifies the consensus engine as transactions arrive.
+use std::{
+    collections::{BTreeSet, HashMap},
+    io::{Error, ErrorKind, Result},
+    sync::Mutex,
+};
+
+use crate::{
+    ids,
+    subnet::rpc::{gossip, snow::engine::common::message::Message},
+};
+use tokio::sync::mpsc::{error::TrySendError, Sender};
+
+/// Transaction in the mempool, which is gossiped by its ID and bytes.
+pub trait Tx: gossip::Gossipable {
+    /// Returns the fee by which the transactions are prioritized, where
+    /// the transactions of the lowest fees are evicted first.
+    fn fee(&self) -> u64;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    /// Maximum number of the transactions.
+    pub max_txs: usize,
+    /// Maximum total size of the transactions.
+    pub max_bytes: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            max_txs: 4096,
+            max_bytes: 64 * 1024 * 1024,
+        }
+    }
+}
+
+struct Inner<T> {
+    txs: HashMap<ids::Id, T>,
+    /// Transaction IDs in the order of the fees.
+    by_fee: BTreeSet<(u64, ids::Id)>,
+    bytes: usize,
+}
+
+impl<T: Tx> Inner<T> {
+    fn remove(&mut self, id: &ids::Id) -> Option<T> {
+        let tx = self.txs.remove(id)?;
+        self.by_fee.remove(&(tx.fee(), *id));
+        self.bytes -= tx.bytes().len();
+        Some(tx)
+    }
+}
+
+/// Deduplicates the transactions by their IDs, and keeps them within the
+/// limits by evicting the lowest-fee transactions for the higher-fee ones.
+pub struct Mempool<T> {
+    config: Config,
+    inner: Mutex<Inner<T>>,
+    to_engine: Sender<Message>,
+}
+
+impl<T: Tx> Mempool<T> {
+    /// Creates the mempool which notifies [`Message::PendingTxs`] to the
+    /// engine (i.e., the sender the VM is initialized with).
+    pub fn new(config: Config, to_engine: Sender<Message>) -> Self {
+        Self {
+            config,
+            inner: Mutex::new(Inner {
+                txs: HashMap::new(),
+                by_fee: BTreeSet::new(),
+                bytes: 0,
+            }),
+            to_engine,
+        }
+    }
+
+    /// Adds the transaction, evicting the lower-fee transactions if the
+    /// mempool is full. Fails if the transaction is already in the mempool,
+    /// or if its fee is too low to evict enough transactions.
+    pub fn add(&self, tx: T) -> Result<()> {
+        let id = tx.gossip_id();
+        let size = tx.bytes().len();
+        let fee = tx.fee();
+        if size > self.config.max_bytes {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "tx {id} size {size} exceeds the mempool limit {}",
+                    self.config.max_bytes
+                ),
+            ));
+        }
+
+        {
+            let mut inner = self.inner.lock().unwrap();
+            if inner.txs.contains_key(&id) {
+                return Err(Error::new(
+                    ErrorKind::AlreadyExists,
+                    format!("tx {id} already in mempool"),
+                ));
+            }
+
+            let mut evicted = Vec::new();
+            let (mut count, mut bytes) = (inner.txs.len(), inner.bytes);
+            for (lowest_fee, lowest_id) in inner.by_fee.iter() {
+                if count < self.config.max_txs && bytes + size <= self.config.max_bytes {
+                    break;
+                }
+                if *lowest_fee >= fee {
+                    return Err(Error::new(
+                        ErrorKind::Other,
+                        format!("mempool is full, tx {id} fee {fee} is too low"),
+                    ));
+                }
+                count -= 1;
+                bytes -= inner.txs[lowest_id].bytes().len();
+                evicted.push(*lowest_id);
+            }
+            if count >= self.config.max_txs {
+                // only reachable if the mempool holds no transaction at all
+                return Err(Error::new(ErrorKind::Other, "mempool is full"));
+            }
+            for evicted_id in evicted.iter() {
+                log::debug!("evicting tx {evicted_id} for tx {id}");
+                inner.remove(evicted_id);
+            }
+
+            inner.by_fee.insert((fee, id));
+            inner.bytes += size;
+            inner.txs.insert(id, tx);
+        }
+
+        self.notify();
+        Ok(())
+    }
+
+    /// Notifies the engine of the pending transactions, unless it already
+    /// has a pending notification.
+    fn notify(&self) {
+        match self.to_engine.try_send(Message::PendingTxs) {
+            Ok(()) | Err(TrySendError::Full(_)) => {}
+            Err(TrySendError::Closed(_)) => log::warn!("engine receiver closed"),
+        }
+    }
+
+    pub fn has(&self, id: &ids::Id) -> bool {
+        self.inner.lock().unwrap().txs.contains_key(id)
+    }
+
+    pub fn get(&self, id: &ids::Id) -> Option<T> {
+        self.inner.lock().unwrap().txs.get(id).cloned()
+    }
+
+    /// Removes the transactions (e.g., once they are accepted in a block).
+    pub fn remove(&self, ids: &[ids::Id]) {
+        let mut inner = self.inner.lock().unwrap();
+        for id in ids {
+            inner.remove(id);
+        }
+    }
+
+    /// Removes and returns the transaction of the highest fee, to build
+    /// the block with.
+    pub fn pop(&self) -> Option<T> {
+        let mut inner = self.inner.lock().unwrap();
+        let (_, id) = *inner.by_fee.iter().next_back()?;
+        inner.remove(&id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().txs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the total size of the transactions.
+    pub fn bytes(&self) -> usize {
+        self.inner.lock().unwrap().bytes
+    }
+}
+
+impl<T: Tx> gossip::Set for Mempool<T> {
+    type Item = T;
+
+    fn add(&self, item: T) -> Result<()> {
+        Mempool::add(self, item)
+    }
+
+    fn has(&self, id: &ids::Id) -> bool {
+        Mempool::has(self, id)
+    }
+
+    /// Iterates the transactions from the highest fee.
+    fn iterate(&self, f: &mut dyn FnMut(&T) -> bool) {
+        let inner = self.inner.lock().unwrap();
+        for (_, id) in inner.by_fee.iter().rev() {
+            if !f(&inner.txs[id]) {
+                return;
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        Mempool::len(self)
+    }
+}
+
+/// Transaction of the fee and the payload, for the tests.
+#[cfg(test)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TestTx {
+    bytes: Vec<u8>,
+}
+
+#[cfg(test)]
+impl TestTx {
+    pub(crate) fn new(fee: u64, payload: &[u8]) -> Self {
+        let mut bytes = fee.to_be_bytes().to_vec();
+        bytes.extend_from_slice(payload);
+        Self { bytes }
+    }
+}
+
+#[cfg(test)]
+impl gossip::Gossipable for TestTx {
+    fn gossip_id(&self) -> ids::Id {
+        ids::Id::sha256(&self.bytes)
+    }
+
+    fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    fn parse(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < 8 {
+            return Err(Error::new(ErrorKind::InvalidData, "tx too short"));
+        }
+        Ok(Self {
+            bytes: bytes.to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+impl Tx for TestTx {
+    fn fee(&self) -> u64 {
+        u64::from_be_bytes(self.bytes[..8].try_into().unwrap())
+    }
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features subnet -- subnet::rpc::mempool::test_mempool --exact --show-output
+#[tokio::test]
+async fn test_mempool() {
+    use gossip::Gossipable;
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+    let mempool = Mempool::new(
+        Config {
+            max_txs: 3,
+            max_bytes: 36,
+        },
+        tx,
+    );
+
+    let tx1 = TestTx::new(1, b"a");
+    let tx2 = TestTx::new(2, b"b");
+    let tx3 = TestTx::new(3, b"c");
+    mempool.add(tx1.clone()).unwrap();
+    assert_eq!(rx.recv().await.unwrap(), Message::PendingTxs);
+    let err = mempool.add(tx1.clone()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+    mempool.add(tx2.clone()).unwrap();
+    mempool.add(tx3.clone()).unwrap();
+    assert_eq!(mempool.len(), 3);
+    assert_eq!(mempool.bytes(), 27);
+    // the pending notifications do not block while the engine is busy
+    assert_eq!(rx.recv().await.unwrap(), Message::PendingTxs);
+    assert!(rx.try_recv().is_err());
+
+    // the lowest-fee tx is evicted for the higher-fee tx
+    let tx4 = TestTx::new(4, b"d");
+    mempool.add(tx4.clone()).unwrap();
+    assert!(!mempool.has(&tx1.gossip_id()));
+    assert!(mempool.add(TestTx::new(2, b"e")).is_err());
+    assert!(mempool.has(&tx2.gossip_id()));
+
+    // the large tx evicts as many txs as its size needs
+    let tx5 = TestTx::new(5, &[0; 10]);
+    mempool.add(tx5.clone()).unwrap();
+    assert!(!mempool.has(&tx2.gossip_id()));
+    assert!(mempool.has(&tx3.gossip_id()));
+    assert_eq!(mempool.bytes(), 9 + 9 + 18);
+    let err = mempool.add(TestTx::new(6, &[0; 30])).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    assert_eq!(mempool.get(&tx4.gossip_id()), Some(tx4.clone()));
+    mempool.remove(&[tx4.gossip_id()]);
+    assert_eq!(mempool.pop(), Some(tx5));
+    assert_eq!(mempool.pop(), Some(tx3));
+    assert_eq!(mempool.pop(), None);
+    assert!(mempool.is_empty());
+    assert_eq!(mempool.bytes(), 0);
+}
@@ -1,14 +1,22 @@
+pub mod aliasreader;
+pub mod atomic;
 pub mod consensus;
 pub mod context;
 pub mod database;
 pub mod errors;
+pub mod gossip;
+pub mod harness;
 pub mod health;
 pub mod http;
+pub mod keystore;
+pub mod mempool;
+pub mod p2p;
 pub mod runtime;
 pub mod snow;
 pub mod snowman;
 pub mod utils;
 pub mod vm;
+pub mod warp;
 
 #[cfg(any(doc, feature = "subnet_metrics"))]
 pub mod metrics;
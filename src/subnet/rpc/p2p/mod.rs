@@ -0,0 +1,601 @@
+//! Request and response networking between the VMs over the [`AppSender`],
+//! which multiplexes the protocols of the VM by their handler ID.
+//!
+//! Each message is prefixed with the ID of the handler it is routed to on
+//! the peer. The responses are routed back to the awaiting requests by the
+//! request IDs, which the network allocates for all the requests of the VM.
+//!
+//! ref. <https://pkg.go.dev/github.com/luxfi/node/network/p2p>
+use std::{
+    collections::{BTreeSet, HashMap},
+    io::{self, Error, ErrorKind},
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Mutex, RwLock,
+    },
+    time::Duration,
+};
+
+use crate::{
+    ids,
+    subnet::rpc::snow::engine::common::{
+        appsender::AppSender,
+        engine::{AppHandler, CrossChainAppHandler, NetworkAppHandler},
+        vm::Connector,
+    },
+};
+use chrono::{DateTime, Utc};
+use tokio::sync::oneshot;
+
+/// Handles the messages of a protocol, whose requests are responded with
+/// the returned bytes. The peer request times out if the handler fails.
+///
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/network/p2p#Handler>
+#[tonic::async_trait]
+pub trait Handler: Send + Sync {
+    async fn app_gossip(&self, _node_id: &ids::node::Id, _gossip: &[u8]) -> io::Result<()> {
+        Ok(())
+    }
+
+    async fn app_request(
+        &self,
+        _node_id: &ids::node::Id,
+        _deadline: DateTime<Utc>,
+        _request: &[u8],
+    ) -> io::Result<Vec<u8>> {
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "handler does not support app requests",
+        ))
+    }
+
+    async fn cross_chain_app_request(
+        &self,
+        _chain_id: &ids::Id,
+        _deadline: DateTime<Utc>,
+        _request: &[u8],
+    ) -> io::Result<Vec<u8>> {
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "handler does not support cross-chain app requests",
+        ))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    /// Time the requests wait for their responses.
+    pub request_timeout: Duration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            request_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Recipient of the outstanding request, whose response is only accepted
+/// from the same recipient.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Recipient {
+    Node(ids::node::Id),
+    Chain(ids::Id),
+}
+
+type Response = io::Result<Vec<u8>>;
+
+struct Inner {
+    app_sender: Box<dyn AppSender + Send + Sync>,
+    config: Config,
+    handlers: RwLock<HashMap<u8, Arc<dyn Handler>>>,
+    peers: Mutex<BTreeSet<ids::node::Id>>,
+    request_id: AtomicU32,
+    requests: Mutex<HashMap<u32, (Recipient, oneshot::Sender<Response>)>>,
+}
+
+impl Inner {
+    fn handler(&self, msg: &[u8]) -> Option<(Arc<dyn Handler>, u8)> {
+        let handler_id = *msg.first()?;
+        let handler = self.handlers.read().unwrap().get(&handler_id).cloned()?;
+        Some((handler, handler_id))
+    }
+
+    /// Sends the request with the function, and waits for its response.
+    async fn request<F, Fut>(&self, recipient: Recipient, send: F) -> Response
+    where
+        F: FnOnce(u32) -> Fut,
+        Fut: std::future::Future<Output = io::Result<()>>,
+    {
+        let request_id = self.request_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+        self.requests
+            .lock()
+            .unwrap()
+            .insert(request_id, (recipient, tx));
+
+        if let Err(e) = send(request_id).await {
+            self.requests.lock().unwrap().remove(&request_id);
+            return Err(e);
+        }
+        match tokio::time::timeout(self.config.request_timeout, rx).await {
+            Ok(Ok(resp)) => resp,
+            Ok(Err(_)) => Err(Error::new(ErrorKind::BrokenPipe, "network dropped")),
+            Err(_) => {
+                self.requests.lock().unwrap().remove(&request_id);
+                Err(Error::new(
+                    ErrorKind::TimedOut,
+                    format!("request {request_id} to {recipient:?} timed out"),
+                ))
+            }
+        }
+    }
+
+    /// Completes the outstanding request, if it was sent to the recipient.
+    fn respond(&self, recipient: Recipient, request_id: u32, resp: Response) {
+        let mut requests = self.requests.lock().unwrap();
+        match requests.get(&request_id) {
+            Some((requested, _)) if *requested == recipient => {
+                let (_, tx) = requests.remove(&request_id).unwrap();
+                // the request may have just timed out
+                let _ = tx.send(resp);
+            }
+            _ => log::debug!("dropping unrequested response {request_id} from {recipient:?}"),
+        }
+    }
+}
+
+/// Routes the messages of the peers to the handlers by their prefix, and
+/// the responses to the clients awaiting them. The VM delegates its
+/// [`AppHandler`] and [`Connector`] calls to the network.
+#[derive(Clone)]
+pub struct Network {
+    inner: Arc<Inner>,
+}
+
+impl Network {
+    pub fn new(app_sender: Box<dyn AppSender + Send + Sync>, config: Config) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                app_sender,
+                config,
+                handlers: RwLock::new(HashMap::new()),
+                peers: Mutex::new(BTreeSet::new()),
+                request_id: AtomicU32::new(0),
+                requests: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+
+    /// Registers the handler of the messages prefixed with the handler ID.
+    pub fn add_handler(&self, handler_id: u8, handler: Arc<dyn Handler>) -> io::Result<()> {
+        let mut handlers = self.inner.handlers.write().unwrap();
+        if handlers.contains_key(&handler_id) {
+            return Err(Error::new(
+                ErrorKind::AlreadyExists,
+                format!("handler {handler_id} already registered"),
+            ));
+        }
+        handlers.insert(handler_id, handler);
+        Ok(())
+    }
+
+    /// Returns the client which sends the messages to the handlers of the
+    /// handler ID on the peers.
+    pub fn new_client(&self, handler_id: u8) -> Client {
+        Client {
+            handler_id,
+            inner: Arc::clone(&self.inner),
+        }
+    }
+
+    /// Returns the connected peers.
+    pub fn peers(&self) -> Vec<ids::node::Id> {
+        self.inner.peers.lock().unwrap().iter().copied().collect()
+    }
+}
+
+#[tonic::async_trait]
+impl NetworkAppHandler for Network {
+    /// Responds to the request with its handler, unless it is past the
+    /// deadline or the handler fails.
+    async fn app_request(
+        &self,
+        node_id: &ids::node::Id,
+        request_id: u32,
+        deadline: DateTime<Utc>,
+        request: &[u8],
+    ) -> io::Result<()> {
+        let Some((handler, handler_id)) = self.inner.handler(request) else {
+            log::debug!("dropping request {request_id} from {node_id} of unknown handler");
+            return Ok(());
+        };
+        if deadline < Utc::now() {
+            log::debug!("dropping expired request {request_id} from {node_id}");
+            return Ok(());
+        }
+        match handler.app_request(node_id, deadline, &request[1..]).await {
+            Ok(resp) => {
+                self.inner
+                    .app_sender
+                    .send_app_response(*node_id, request_id, resp)
+                    .await
+            }
+            Err(e) => {
+                log::debug!("handler {handler_id} failed request {request_id} from {node_id}: {e}");
+                Ok(())
+            }
+        }
+    }
+
+    async fn app_request_failed(&self, node_id: &ids::node::Id, request_id: u32) -> io::Result<()> {
+        self.inner.respond(
+            Recipient::Node(*node_id),
+            request_id,
+            Err(Error::new(
+                ErrorKind::Other,
+                format!("request {request_id} to {node_id} failed"),
+            )),
+        );
+        Ok(())
+    }
+
+    async fn app_response(
+        &self,
+        node_id: &ids::node::Id,
+        request_id: u32,
+        response: &[u8],
+    ) -> io::Result<()> {
+        self.inner
+            .respond(Recipient::Node(*node_id), request_id, Ok(response.to_vec()));
+        Ok(())
+    }
+
+    async fn app_gossip(&self, node_id: &ids::node::Id, msg: &[u8]) -> io::Result<()> {
+        match self.inner.handler(msg) {
+            Some((handler, handler_id)) => {
+                if let Err(e) = handler.app_gossip(node_id, &msg[1..]).await {
+                    log::debug!("handler {handler_id} failed gossip from {node_id}: {e}");
+                }
+            }
+            None => log::debug!("dropping gossip from {node_id} of unknown handler"),
+        }
+        Ok(())
+    }
+}
+
+#[tonic::async_trait]
+impl CrossChainAppHandler for Network {
+    async fn cross_chain_app_request(
+        &self,
+        chain_id: &ids::Id,
+        request_id: u32,
+        deadline: DateTime<Utc>,
+        request: &[u8],
+    ) -> io::Result<()> {
+        let Some((handler, handler_id)) = self.inner.handler(request) else {
+            log::debug!("dropping request {request_id} from chain {chain_id} of unknown handler");
+            return Ok(());
+        };
+        if deadline < Utc::now() {
+            log::debug!("dropping expired request {request_id} from chain {chain_id}");
+            return Ok(());
+        }
+        match handler
+            .cross_chain_app_request(chain_id, deadline, &request[1..])
+            .await
+        {
+            Ok(resp) => {
+                self.inner
+                    .app_sender
+                    .send_cross_chain_app_response(*chain_id, request_id, resp)
+                    .await
+            }
+            Err(e) => {
+                log::debug!(
+                    "handler {handler_id} failed request {request_id} from chain {chain_id}: {e}"
+                );
+                Ok(())
+            }
+        }
+    }
+
+    async fn cross_chain_app_request_failed(
+        &self,
+        chain_id: &ids::Id,
+        request_id: u32,
+    ) -> io::Result<()> {
+        self.inner.respond(
+            Recipient::Chain(*chain_id),
+            request_id,
+            Err(Error::new(
+                ErrorKind::Other,
+                format!("request {request_id} to chain {chain_id} failed"),
+            )),
+        );
+        Ok(())
+    }
+
+    async fn cross_chain_app_response(
+        &self,
+        chain_id: &ids::Id,
+        request_id: u32,
+        response: &[u8],
+    ) -> io::Result<()> {
+        self.inner.respond(
+            Recipient::Chain(*chain_id),
+            request_id,
+            Ok(response.to_vec()),
+        );
+        Ok(())
+    }
+}
+
+impl AppHandler for Network {}
+
+#[tonic::async_trait]
+impl Connector for Network {
+    async fn connected(&self, id: &ids::node::Id) -> io::Result<()> {
+        self.inner.peers.lock().unwrap().insert(*id);
+        Ok(())
+    }
+
+    async fn disconnected(&self, id: &ids::node::Id) -> io::Result<()> {
+        self.inner.peers.lock().unwrap().remove(id);
+        Ok(())
+    }
+}
+
+/// Sends the messages of a protocol to its handlers on the peers.
+///
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/network/p2p#Client>
+#[derive(Clone)]
+pub struct Client {
+    handler_id: u8,
+    inner: Arc<Inner>,
+}
+
+impl Client {
+    fn prefixed(&self, msg: &[u8]) -> Vec<u8> {
+        let mut b = Vec::with_capacity(1 + msg.len());
+        b.push(self.handler_id);
+        b.extend_from_slice(msg);
+        b
+    }
+
+    /// Sends the request to the node, and returns its response. Fails if
+    /// the request fails or times out.
+    pub async fn send_request(
+        &self,
+        node_id: ids::node::Id,
+        request: &[u8],
+    ) -> io::Result<Vec<u8>> {
+        let request = self.prefixed(request);
+        self.inner
+            .request(Recipient::Node(node_id), |request_id| {
+                self.inner.app_sender.send_app_request(
+                    ids::node::Set::from([node_id]),
+                    request_id,
+                    request,
+                )
+            })
+            .await
+    }
+
+    /// Sends the request to the chain on this node, and returns its response.
+    pub async fn send_cross_chain_request(
+        &self,
+        chain_id: ids::Id,
+        request: &[u8],
+    ) -> io::Result<Vec<u8>> {
+        let request = self.prefixed(request);
+        self.inner
+            .request(Recipient::Chain(chain_id), |request_id| {
+                self.inner
+                    .app_sender
+                    .send_cross_chain_app_request(chain_id, request_id, request)
+            })
+            .await
+    }
+
+    /// Gossips the message to the peers.
+    pub async fn send_gossip(&self, gossip: &[u8]) -> io::Result<()> {
+        self.inner
+            .app_sender
+            .send_app_gossip(self.prefixed(gossip))
+            .await
+    }
+
+    /// Gossips the message to the nodes.
+    pub async fn send_gossip_specific(
+        &self,
+        node_ids: ids::node::Set,
+        gossip: &[u8],
+    ) -> io::Result<()> {
+        self.inner
+            .app_sender
+            .send_app_gossip_specific(node_ids, self.prefixed(gossip))
+            .await
+    }
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features subnet -- subnet::rpc::p2p::test_network --exact --show-output
+#[tokio::test]
+async fn test_network() {
+    use crate::subnet::rpc::harness::{appsender::AppSender as Recorder, SentMessage};
+
+    /// Echoes the requests, and records the gossip.
+    #[derive(Default)]
+    struct EchoHandler {
+        gossip: Mutex<Vec<Vec<u8>>>,
+    }
+
+    #[tonic::async_trait]
+    impl Handler for EchoHandler {
+        async fn app_gossip(&self, _node_id: &ids::node::Id, gossip: &[u8]) -> io::Result<()> {
+            self.gossip.lock().unwrap().push(gossip.to_vec());
+            Ok(())
+        }
+        async fn app_request(
+            &self,
+            _node_id: &ids::node::Id,
+            _deadline: DateTime<Utc>,
+            request: &[u8],
+        ) -> io::Result<Vec<u8>> {
+            Ok(request.to_vec())
+        }
+        async fn cross_chain_app_request(
+            &self,
+            _chain_id: &ids::Id,
+            _deadline: DateTime<Utc>,
+            request: &[u8],
+        ) -> io::Result<Vec<u8>> {
+            Ok(request.to_vec())
+        }
+    }
+
+    /// Waits for the next message the network sends.
+    async fn next(sent: &Recorder) -> SentMessage {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                if let Some(msg) = sent.take().pop() {
+                    return msg;
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("no message sent")
+    }
+
+    let node_a = ids::node::Id::from_slice(&[1; ids::node::LEN]);
+    let node_b = ids::node::Id::from_slice(&[2; ids::node::LEN]);
+    let chain_a = ids::Id::sha256("a");
+    let chain_b = ids::Id::sha256("b");
+    let (sent_a, sent_b) = (Recorder::new(), Recorder::new());
+    let network_a = Network::new(
+        Box::new(sent_a.clone()),
+        Config {
+            request_timeout: Duration::from_millis(500),
+        },
+    );
+    let network_b = Network::new(Box::new(sent_b.clone()), Config::default());
+    let handler = Arc::new(EchoHandler::default());
+    network_b.add_handler(1, handler.clone()).unwrap();
+    let err = network_b.add_handler(1, handler.clone()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+    let deadline = Utc::now() + chrono::Duration::seconds(10);
+
+    // the request is routed to the handler, and its response back to the client
+    let client = network_a.new_client(1);
+    let c = client.clone();
+    let resp = tokio::spawn(async move { c.send_request(node_b, b"ping").await });
+    let (request_id, request) = match next(&sent_a).await {
+        SentMessage::AppRequest {
+            node_ids,
+            request_id,
+            request,
+        } => {
+            assert_eq!(node_ids, ids::node::Set::from([node_b]));
+            assert_eq!(request, b"\x01ping".to_vec());
+            (request_id, request)
+        }
+        msg => panic!("unexpected {msg:?}"),
+    };
+    network_b
+        .app_request(&node_a, request_id, deadline, &request)
+        .await
+        .unwrap();
+    let response = match next(&sent_b).await {
+        SentMessage::AppResponse {
+            node_id, response, ..
+        } => {
+            assert_eq!(node_id, node_a);
+            response
+        }
+        msg => panic!("unexpected {msg:?}"),
+    };
+    // the response of the other node is dropped
+    network_a
+        .app_response(&node_a, request_id, b"spoofed")
+        .await
+        .unwrap();
+    network_a
+        .app_response(&node_b, request_id, &response)
+        .await
+        .unwrap();
+    assert_eq!(resp.await.unwrap().unwrap(), b"ping".to_vec());
+
+    // the failed request resolves to an error
+    let c = client.clone();
+    let resp = tokio::spawn(async move { c.send_request(node_b, b"ping").await });
+    let request_id = match next(&sent_a).await {
+        SentMessage::AppRequest { request_id, .. } => request_id,
+        msg => panic!("unexpected {msg:?}"),
+    };
+    network_a
+        .app_request_failed(&node_b, request_id)
+        .await
+        .unwrap();
+    assert_eq!(resp.await.unwrap().unwrap_err().kind(), ErrorKind::Other);
+
+    // the request without response times out
+    let err = client.send_request(node_b, b"ping").await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TimedOut);
+    sent_a.take();
+
+    // the cross-chain request is routed the same way
+    let c = client.clone();
+    let resp = tokio::spawn(async move { c.send_cross_chain_request(chain_b, b"pong").await });
+    let (request_id, request) = match next(&sent_a).await {
+        SentMessage::CrossChainAppRequest {
+            chain_id,
+            request_id,
+            request,
+        } => {
+            assert_eq!(chain_id, chain_b);
+            (request_id, request)
+        }
+        msg => panic!("unexpected {msg:?}"),
+    };
+    network_b
+        .cross_chain_app_request(&chain_a, request_id, deadline, &request)
+        .await
+        .unwrap();
+    let response = match next(&sent_b).await {
+        SentMessage::CrossChainAppResponse {
+            chain_id, response, ..
+        } => {
+            assert_eq!(chain_id, chain_a);
+            response
+        }
+        msg => panic!("unexpected {msg:?}"),
+    };
+    network_a
+        .cross_chain_app_response(&chain_b, request_id, &response)
+        .await
+        .unwrap();
+    assert_eq!(resp.await.unwrap().unwrap(), b"pong".to_vec());
+
+    // the gossip is routed by its prefix, and unknown prefixes are dropped
+    client.send_gossip(b"hello").await.unwrap();
+    let gossip = match next(&sent_a).await {
+        SentMessage::AppGossip { msg } => msg,
+        msg => panic!("unexpected {msg:?}"),
+    };
+    network_b.app_gossip(&node_a, &gossip).await.unwrap();
+    network_b.app_gossip(&node_a, b"\x02hello").await.unwrap();
+    network_b.app_gossip(&node_a, &[]).await.unwrap();
+    network_b
+        .app_request(&node_a, 0, deadline, b"\x02ping")
+        .await
+        .unwrap();
+    assert!(sent_b.take().is_empty());
+    assert_eq!(*handler.gossip.lock().unwrap(), vec![b"hello".to_vec()]);
+
+    network_a.connected(&node_b).await.unwrap();
+    assert_eq!(network_a.peers(), vec![node_b]);
+    network_a.disconnected(&node_b).await.unwrap();
+    assert!(network_a.peers().is_empty());
+}
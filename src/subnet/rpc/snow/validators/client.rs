@@ -86,7 +86,7 @@ impl super::State for ValidatorStateClient {
             let node_id = ids::node::Id::from_slice(&validator.node_id);
 
             let mut public_key: Option<Key> = None;
-            if !resp.validators.is_empty() {
+            if !validator.public_key.is_empty() {
                 public_key = Some(Key::from_bytes(&validator.public_key)?);
             }
             validators.insert(
@@ -1,4 +1,5 @@
 pub mod client;
+pub mod server;
 
 use std::{collections::BTreeMap, fmt::Debug, io};
 
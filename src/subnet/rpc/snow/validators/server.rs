@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use crate::{
+    ids,
+    proto::pb::{
+        self,
+        google::protobuf::Empty,
+        validatorstate::{
+            GetCurrentHeightResponse, GetMinimumHeightResponse, GetSubnetIdRequest,
+            GetSubnetIdResponse, GetValidatorSetRequest, GetValidatorSetResponse, Validator,
+        },
+    },
+};
+use prost::bytes::Bytes;
+use tonic::{Request, Response, Status};
+
+/// A gRPC server which wraps a subnet::rpc::snow::validators::State impl allowing client control over RPC.
+///
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/snow/validators/gvalidators#Server>
+#[derive(Clone)]
+pub struct Server<S> {
+    inner: Arc<S>,
+}
+
+impl<S> Server<S>
+where
+    S: super::State + Send + Sync + 'static,
+{
+    pub fn new(state: S) -> Self {
+        Self {
+            inner: Arc::new(state),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl<S> pb::validatorstate::validator_state_server::ValidatorState for Server<S>
+where
+    S: super::State + Send + Sync + 'static,
+{
+    async fn get_minimum_height(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<GetMinimumHeightResponse>, Status> {
+        let height = self.inner.get_minimum_height().await?;
+        Ok(Response::new(GetMinimumHeightResponse { height }))
+    }
+
+    async fn get_current_height(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<GetCurrentHeightResponse>, Status> {
+        let height = self.inner.get_current_height().await?;
+        Ok(Response::new(GetCurrentHeightResponse { height }))
+    }
+
+    async fn get_subnet_id(
+        &self,
+        request: Request<GetSubnetIdRequest>,
+    ) -> Result<Response<GetSubnetIdResponse>, Status> {
+        let req = request.into_inner();
+        let subnet_id = self
+            .inner
+            .get_subnet_id(ids::Id::from_slice(&req.chain_id))
+            .await?;
+        Ok(Response::new(GetSubnetIdResponse {
+            subnet_id: Bytes::from(subnet_id.to_vec()),
+        }))
+    }
+
+    async fn get_validator_set(
+        &self,
+        request: Request<GetValidatorSetRequest>,
+    ) -> Result<Response<GetValidatorSetResponse>, Status> {
+        let req = request.into_inner();
+        let validators = self
+            .inner
+            .get_validator_set(req.height, ids::Id::from_slice(&req.subnet_id))
+            .await?
+            .into_values()
+            .map(|v| Validator {
+                node_id: Bytes::from(v.node_id.to_vec()),
+                weight: v.weight,
+                public_key: v
+                    .public_key
+                    .map(|pk| Bytes::from(pk.to_compressed_bytes().to_vec()))
+                    .unwrap_or_default(),
+            })
+            .collect();
+        Ok(Response::new(GetValidatorSetResponse { validators }))
+    }
+}
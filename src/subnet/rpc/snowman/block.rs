@@ -16,6 +16,16 @@ pub trait ChainVm: CommonVm + BatchedChainVm + Getter + Parser {
     /// Returns either a block or an error
     async fn build_block(&self) -> Result<<Self as ChainVm>::Block>;
 
+    /// Returns the capability of this VM to build the blocks with the
+    /// P-Chain height, or None if the VM does not consider the P-Chain
+    /// height, in which case [`build_block`](ChainVm::build_block) is
+    /// called instead.
+    fn block_builder_with_context(
+        &self,
+    ) -> Option<&dyn BuildBlockWithContextChainVM<Block = <Self as ChainVm>::Block>> {
+        None
+    }
+
     /// Issues a transaction to the chain
     async fn issue_tx(&self) -> Result<<Self as ChainVm>::Block>;
 
@@ -25,6 +35,19 @@ pub trait ChainVm: CommonVm + BatchedChainVm + Getter + Parser {
     /// Returns the ID of the last accepted block.
     /// If no blocks have been accepted, this should return the genesis block
     async fn last_accepted(&self) -> Result<Id>;
+
+    /// Returns the height index capability of this VM, or None if the VM
+    /// does not index the accepted blocks by height.
+    fn height_indexed(&self) -> Option<&dyn HeightIndexedChainVm> {
+        None
+    }
+
+    /// Returns the state sync capability of this VM, or None if the VM does
+    /// not support the state sync, in which case the engine bootstraps the
+    /// chain instead.
+    fn state_syncable(&self) -> Option<&dyn StateSyncableVm> {
+        None
+    }
 }
 
 /// ref. <https://pkg.go.dev/github.com/luxfi/node/snow/engine/snowman/block#Getter>
@@ -47,6 +70,7 @@ pub trait Parser {
 /// to an underlying vm.
 ///
 /// ref. <https://pkg.go.dev/github.com/luxfi/node/snow/engine/snowman/block#Context>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Context {
     /// Height that this block will use to verify it's state.  In the
     /// proposervm, blocks verify the proposer based on the P-chain height
@@ -58,33 +82,39 @@ pub struct Context {
     pub p_chain_height: u64,
 }
 
-/// Defines the trait a [`ChainVm`] can optionally implement to consider the
-/// P-Chain height when building blocks.
+/// Defines the capability a [`ChainVm`] exposes through
+/// [`ChainVm::block_builder_with_context`] to consider the P-Chain height
+/// when building blocks.
 ///
 /// ref. <https://pkg.go.dev/github.com/luxfi/node/snow/engine/snowman/block#BuildBlockWithContextChainVM>
 #[tonic::async_trait]
-pub trait BuildBlockWithContextChainVM {
+pub trait BuildBlockWithContextChainVM: Send + Sync {
     type Block: snowman::Block;
 
     /// Attempt to build a new block given that the P-Chain height is
     /// [block_ctx.p_chain_height].
     ///
     /// This method will be called if and only if the proposervm is activated.
-    /// Otherwise [build_block] will be called.
+    /// Otherwise [`ChainVm::build_block`] will be called.
     async fn build_block_with_context(
         &self,
         blk_context: &Context,
     ) -> Result<<Self as BuildBlockWithContextChainVM>::Block>;
 }
 
+/// Defines the capability a [`Block`](snowman::Block) exposes through
+/// [`Block::with_verify_context`](snowman::Block::with_verify_context) to
+/// verify itself with the P-Chain height.
+///
 /// ref. <https://pkg.go.dev/github.com/luxfi/node/snow/engine/snowman/block#WithVerifyContext>
 #[tonic::async_trait]
-pub trait WithVerifyContext {
-    /// Returns true if [`verify_with_context`] should be called.
-    /// Returns false if [verify] should be called.
+pub trait WithVerifyContext: Send + Sync {
+    /// Returns true if [`verify_with_context`](WithVerifyContext::verify_with_context)
+    /// should be called. Returns false if [`verify`](snowman::Block::verify)
+    /// should be called.
     ///
     /// This method will be called if and only if the proposervm is activated.
-    /// Otherwise [verify] will be called.
+    /// Otherwise [`verify`](snowman::Block::verify) will be called.
     async fn should_verify_with_context(&self) -> Result<bool>;
 
     /// Verify that the state transition this block would make if accepted is
@@ -118,3 +148,125 @@ pub trait BatchedChainVm {
     /// Attempts to batch parse_block requests.
     async fn batched_parse_block(&self, blocks: &[Vec<u8>]) -> Result<Vec<Self::Block>>;
 }
+
+/// Defines the capability a [`ChainVm`] exposes through
+/// [`ChainVm::height_indexed`] to look up the accepted blocks by height,
+/// which the proposervm uses to serve the blocks by height when other nodes
+/// are fast bootstrapping.
+/// See [`HeightIndex`](super::height_index::HeightIndex) to store the index.
+///
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/snow/engine/snowman/block#HeightIndexedChainVM>
+#[tonic::async_trait]
+pub trait HeightIndexedChainVm: Send + Sync {
+    /// Returns Ok if the height index is available, or the index
+    /// incomplete error if it is still being built.
+    async fn verify_height_index(&self) -> Result<()>;
+
+    /// Returns the ID of the accepted block at the height, or the not found
+    /// error if there is no accepted block at the height.
+    async fn get_block_id_at_height(&self, height: u64) -> Result<Id>;
+}
+
+/// Defines the capability a [`ChainVm`] exposes through
+/// [`ChainVm::state_syncable`] to sync its state from the state summaries of
+/// the other nodes, instead of replaying the whole chain.
+///
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/snow/engine/snowman/block#StateSyncableVM>
+#[tonic::async_trait]
+pub trait StateSyncableVm: Send + Sync {
+    /// Returns true if the state sync is enabled for this VM.
+    /// If false is returned, the engine bootstraps the chain instead.
+    async fn state_sync_enabled(&self) -> Result<bool>;
+
+    /// Returns the state summary that was being synced before the VM was
+    /// shut down, if any, so that the sync can resume.
+    /// Returns the not found error if there is no ongoing sync.
+    async fn get_ongoing_sync_state_summary(&self) -> Result<Box<dyn StateSummary>>;
+
+    /// Returns the latest state summary that this VM can serve to the
+    /// syncing peers.
+    async fn get_last_state_summary(&self) -> Result<Box<dyn StateSummary>>;
+
+    /// Parses the state summary from the bytes received from the peers.
+    async fn parse_state_summary(&self, bytes: &[u8]) -> Result<Box<dyn StateSummary>>;
+
+    /// Returns the state summary at the height, or the not found error if
+    /// the VM does not have it.
+    async fn get_state_summary(&self, height: u64) -> Result<Box<dyn StateSummary>>;
+}
+
+/// Represents the state of the VM at a given height, which a syncing node
+/// accepts to sync its state to.
+///
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/snow/engine/snowman/block#StateSummary>
+#[tonic::async_trait]
+pub trait StateSummary: Send + Sync {
+    /// Returns the unique ID of this summary.
+    async fn id(&self) -> Id;
+
+    /// Returns the height of the block this summary refers to.
+    async fn height(&self) -> u64;
+
+    /// Returns the binary representation of this summary.
+    async fn bytes(&self) -> &[u8];
+
+    /// Triggers the VM to start syncing its state to this summary.
+    /// Returns how the VM syncs, or [`StateSyncMode::Skipped`] if the VM
+    /// decides not to sync to this summary.
+    async fn accept(&mut self) -> Result<StateSyncMode>;
+}
+
+/// Defines what the VM does with the accepted state summary.
+///
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/snow/engine/snowman/block#StateSyncMode>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateSyncMode {
+    /// The VM skipped the state sync, and the engine bootstraps the chain.
+    Skipped,
+    /// The VM syncs the state in the background, and the engine waits for
+    /// the "StateSyncDone" message before bootstrapping the rest of the chain.
+    Static,
+    /// The VM syncs the state in the background, while the engine may start
+    /// bootstrapping from the summary height right away.
+    Dynamic,
+}
+
+impl StateSyncMode {
+    /// Returns the protobuf enum value of the mode.
+    pub fn to_i32(&self) -> i32 {
+        match self {
+            StateSyncMode::Skipped => 1,
+            StateSyncMode::Static => 2,
+            StateSyncMode::Dynamic => 3,
+        }
+    }
+}
+
+impl TryFrom<i32> for StateSyncMode {
+    type Error = ();
+
+    fn try_from(mode: i32) -> std::result::Result<Self, Self::Error> {
+        match mode {
+            1 => Ok(StateSyncMode::Skipped),
+            2 => Ok(StateSyncMode::Static),
+            3 => Ok(StateSyncMode::Dynamic),
+            _ => Err(()),
+        }
+    }
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features subnet -- subnet::rpc::snowman::block::test_state_sync_mode --exact --show-output
+#[test]
+fn test_state_sync_mode() {
+    use crate::proto::pb::vm::state_summary_accept_response::Mode;
+
+    for (mode, pb_mode) in [
+        (StateSyncMode::Skipped, Mode::Skipped),
+        (StateSyncMode::Static, Mode::Static),
+        (StateSyncMode::Dynamic, Mode::Dynamic),
+    ] {
+        assert_eq!(mode.to_i32(), pb_mode as i32);
+        assert_eq!(StateSyncMode::try_from(pb_mode as i32), Ok(mode));
+    }
+    assert!(StateSyncMode::try_from(Mode::Unspecified as i32).is_err());
+}
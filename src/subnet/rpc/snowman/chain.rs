@@ -0,0 +1,584 @@
+//! Chain state which caches the blocks of a [`ChainVm`](super::block::ChainVm),
+//! so that the VMs only implement how to get, parse and build their blocks.
+use std::{
+    collections::HashMap,
+    io::{Error, ErrorKind, Result},
+    num::NonZeroUsize,
+    sync::{Arc, Mutex, Weak},
+};
+
+use crate::{
+    choices::status::Status,
+    ids::Id,
+    subnet::rpc::{
+        consensus::snowman::{Block, Decidable},
+        errors,
+        snowman::block::{BuildBlockWithContextChainVM, Context, WithVerifyContext},
+    },
+};
+use lru::LruCache;
+
+/// Functions of the VM the [`State`] wraps.
+#[tonic::async_trait]
+pub trait Backend: Send + Sync {
+    type Block: Block;
+
+    /// Returns the block from the database, or an error of
+    /// [`ErrorKind::NotFound`] if the block does not exist.
+    async fn get_block(&self, id: Id) -> Result<Self::Block>;
+
+    async fn parse_block(&self, bytes: &[u8]) -> Result<Self::Block>;
+
+    async fn build_block(&self) -> Result<Self::Block>;
+
+    /// Returns the capability of the VM to build the blocks with the
+    /// P-Chain height, or None if the VM does not consider it.
+    fn block_builder_with_context(
+        &self,
+    ) -> Option<&dyn BuildBlockWithContextChainVM<Block = Self::Block>> {
+        None
+    }
+}
+
+/// Sizes of the caches of the [`State`], each of which is at least one.
+///
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/vms/components/chain#Config>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    /// Number of the accepted or rejected blocks to cache.
+    pub decided_cache_size: usize,
+    /// Number of the IDs of the blocks which do not exist to cache.
+    pub missing_cache_size: usize,
+    /// Number of the parsed but not yet verified blocks to cache.
+    pub unverified_cache_size: usize,
+    /// Number of the block bytes to block IDs mappings to cache.
+    pub bytes_to_id_cache_size: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            decided_cache_size: 2048,
+            missing_cache_size: 2048,
+            unverified_cache_size: 2048,
+            bytes_to_id_cache_size: 2048,
+        }
+    }
+}
+
+fn cache_size(size: usize) -> NonZeroUsize {
+    NonZeroUsize::new(size.max(1)).unwrap()
+}
+
+struct Caches<B> {
+    /// Blocks which are verified but not yet decided, which consensus
+    /// may need at any time so are never evicted.
+    verified: HashMap<Id, BlockWrapper<B>>,
+    decided: LruCache<Id, BlockWrapper<B>>,
+    unverified: LruCache<Id, BlockWrapper<B>>,
+    missing: LruCache<Id, ()>,
+    bytes_to_id: LruCache<Vec<u8>, Id>,
+    last_accepted: BlockWrapper<B>,
+}
+
+impl<B> Caches<B> {
+    /// Returns the block if it is verified or cached.
+    fn get(&mut self, id: &Id) -> Option<BlockWrapper<B>> {
+        if let Some(blk) = self.verified.get(id) {
+            return Some(blk.clone());
+        }
+        if let Some(blk) = self.decided.get(id) {
+            return Some(blk.clone());
+        }
+        self.unverified.get(id).cloned()
+    }
+}
+
+/// Wraps the block of the VM, to keep the caches of the [`State`] up to
+/// date as the block is verified and decided.
+///
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/vms/components/chain#BlockWrapper>
+pub struct BlockWrapper<B> {
+    inner: Arc<tokio::sync::Mutex<B>>,
+    id: Id,
+    bytes: Vec<u8>,
+    height: u64,
+    timestamp: u64,
+    parent: Id,
+    /// Whether the block of the VM verifies itself with the P-Chain height.
+    with_verify_context: bool,
+    caches: Weak<Mutex<Caches<B>>>,
+}
+
+impl<B> Clone for BlockWrapper<B> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+            id: self.id,
+            bytes: self.bytes.clone(),
+            height: self.height,
+            timestamp: self.timestamp,
+            parent: self.parent,
+            with_verify_context: self.with_verify_context,
+            caches: Weak::clone(&self.caches),
+        }
+    }
+}
+
+impl<B: Block> BlockWrapper<B> {
+    async fn new(blk: B, caches: Weak<Mutex<Caches<B>>>) -> Self {
+        Self {
+            id: blk.id().await,
+            bytes: blk.bytes().await.to_vec(),
+            height: blk.height().await,
+            timestamp: blk.timestamp().await,
+            parent: blk.parent().await,
+            with_verify_context: blk.with_verify_context().is_some(),
+            inner: Arc::new(tokio::sync::Mutex::new(blk)),
+            caches,
+        }
+    }
+
+    /// Returns the block of the VM.
+    pub fn inner(&self) -> Arc<tokio::sync::Mutex<B>> {
+        Arc::clone(&self.inner)
+    }
+
+    /// Moves the block from the unverified to the verified blocks.
+    fn verified(&self) {
+        if let Some(caches) = self.caches.upgrade() {
+            let mut caches = caches.lock().unwrap();
+            caches.unverified.pop(&self.id);
+            caches.verified.insert(self.id, self.clone());
+        }
+    }
+
+    /// Decides the block once, or fails if it was decided the other way.
+    async fn decide(&mut self, status: Status) -> Result<()> {
+        let mut blk = self.inner.lock().await;
+        match blk.status().await {
+            Status::Processing => {}
+            current if current == status => return Ok(()),
+            current => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("block {} is already {current}", self.id),
+                ))
+            }
+        }
+        if status == Status::Accepted {
+            blk.accept().await?;
+        } else {
+            blk.reject().await?;
+        }
+
+        if let Some(caches) = self.caches.upgrade() {
+            let mut caches = caches.lock().unwrap();
+            caches.verified.remove(&self.id);
+            caches.unverified.pop(&self.id);
+            caches.decided.put(self.id, self.clone());
+            if status == Status::Accepted {
+                caches.last_accepted = self.clone();
+            }
+        }
+        Ok(())
+    }
+}
+
+#[tonic::async_trait]
+impl<B: Block> Decidable for BlockWrapper<B> {
+    async fn id(&self) -> Id {
+        self.id
+    }
+
+    async fn status(&self) -> Status {
+        self.inner.lock().await.status().await
+    }
+
+    /// Accepts the block, which is a no-op if it is already accepted.
+    async fn accept(&mut self) -> Result<()> {
+        self.decide(Status::Accepted).await
+    }
+
+    /// Rejects the block, which is a no-op if it is already rejected.
+    async fn reject(&mut self) -> Result<()> {
+        self.decide(Status::Rejected).await
+    }
+}
+
+#[tonic::async_trait]
+impl<B: Block> Block for BlockWrapper<B> {
+    async fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    async fn height(&self) -> u64 {
+        self.height
+    }
+
+    async fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    async fn parent(&self) -> Id {
+        self.parent
+    }
+
+    async fn verify(&mut self) -> Result<()> {
+        self.inner.lock().await.verify().await?;
+        self.verified();
+        Ok(())
+    }
+
+    fn with_verify_context(&self) -> Option<&dyn WithVerifyContext> {
+        if self.with_verify_context {
+            Some(self)
+        } else {
+            None
+        }
+    }
+}
+
+/// Forwards to the block of the VM, which is only exposed through
+/// [`Block::with_verify_context`] if the block of the VM supports it.
+#[tonic::async_trait]
+impl<B: Block> WithVerifyContext for BlockWrapper<B> {
+    async fn should_verify_with_context(&self) -> Result<bool> {
+        let blk = self.inner.lock().await;
+        match blk.with_verify_context() {
+            Some(blk) => blk.should_verify_with_context().await,
+            None => Ok(false),
+        }
+    }
+
+    async fn verify_with_context(&self, blk_context: &Context) -> Result<()> {
+        {
+            let blk = self.inner.lock().await;
+            let blk = blk.with_verify_context().ok_or_else(|| {
+                Error::new(
+                    ErrorKind::Unsupported,
+                    "block does not implement WithVerifyContext interface",
+                )
+            })?;
+            blk.verify_with_context(blk_context).await?;
+        }
+        self.verified();
+        Ok(())
+    }
+}
+
+/// Caches the blocks of the VM, so that consensus gets the same block
+/// for the same ID, and the decided blocks are not read from the database
+/// again.
+///
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/vms/components/chain#State>
+pub struct State<V: Backend> {
+    backend: V,
+    caches: Arc<Mutex<Caches<V::Block>>>,
+}
+
+impl<V: Backend> State<V> {
+    pub async fn new(backend: V, config: Config, last_accepted_block: V::Block) -> Self {
+        let mut last_accepted = BlockWrapper::new(last_accepted_block, Weak::new()).await;
+        let caches = Arc::new_cyclic(|caches| {
+            last_accepted.caches = Weak::clone(caches);
+            let mut decided = LruCache::new(cache_size(config.decided_cache_size));
+            decided.put(last_accepted.id, last_accepted.clone());
+            Mutex::new(Caches {
+                verified: HashMap::new(),
+                decided,
+                unverified: LruCache::new(cache_size(config.unverified_cache_size)),
+                missing: LruCache::new(cache_size(config.missing_cache_size)),
+                bytes_to_id: LruCache::new(cache_size(config.bytes_to_id_cache_size)),
+                last_accepted,
+            })
+        });
+        Self { backend, caches }
+    }
+
+    /// Returns the functions the state wraps.
+    pub fn backend(&self) -> &V {
+        &self.backend
+    }
+
+    pub fn last_accepted(&self) -> Id {
+        self.caches.lock().unwrap().last_accepted.id
+    }
+
+    pub fn last_accepted_block(&self) -> BlockWrapper<V::Block> {
+        self.caches.lock().unwrap().last_accepted.clone()
+    }
+
+    /// Returns true if the block is verified but not yet decided.
+    pub fn is_processing(&self, id: &Id) -> bool {
+        self.caches.lock().unwrap().verified.contains_key(id)
+    }
+
+    /// Returns the block, from the caches if possible.
+    pub async fn get_block(&self, id: Id) -> Result<BlockWrapper<V::Block>> {
+        {
+            let mut caches = self.caches.lock().unwrap();
+            if let Some(blk) = caches.get(&id) {
+                return Ok(blk);
+            }
+            if caches.missing.get(&id).is_some() {
+                return Err(errors::Error::NotFound.to_err());
+            }
+        }
+
+        let blk = match self.backend.get_block(id).await {
+            Ok(blk) => blk,
+            Err(e) => {
+                if e.kind() == ErrorKind::NotFound {
+                    self.caches.lock().unwrap().missing.put(id, ());
+                }
+                return Err(e);
+            }
+        };
+        Ok(self.add_block_outside_consensus(blk).await)
+    }
+
+    /// Parses the block, which is the same block as the block of the same
+    /// ID that is verified or cached.
+    pub async fn parse_block(&self, bytes: &[u8]) -> Result<BlockWrapper<V::Block>> {
+        {
+            let mut caches = self.caches.lock().unwrap();
+            if let Some(id) = caches.bytes_to_id.get(bytes).copied() {
+                if let Some(blk) = caches.get(&id) {
+                    return Ok(blk);
+                }
+            }
+        }
+
+        let blk = self.backend.parse_block(bytes).await?;
+        let id = blk.id().await;
+        {
+            let mut caches = self.caches.lock().unwrap();
+            caches.bytes_to_id.put(bytes.to_vec(), id);
+            // the block may be known once missing
+            caches.missing.pop(&id);
+            if let Some(blk) = caches.get(&id) {
+                return Ok(blk);
+            }
+        }
+        Ok(self.add_block_outside_consensus(blk).await)
+    }
+
+    pub async fn build_block(&self) -> Result<BlockWrapper<V::Block>> {
+        let blk = self.backend.build_block().await?;
+        Ok(self.add_built_block(blk).await)
+    }
+
+    /// Builds the block with the P-Chain height, or fails if the backend
+    /// does not expose [`Backend::block_builder_with_context`].
+    pub async fn build_block_with_context(
+        &self,
+        blk_context: &Context,
+    ) -> Result<BlockWrapper<V::Block>> {
+        let builder = self.backend.block_builder_with_context().ok_or_else(|| {
+            Error::new(
+                ErrorKind::Unsupported,
+                "vm does not implement BuildBlockWithContextChainVM interface",
+            )
+        })?;
+        let blk = builder.build_block_with_context(blk_context).await?;
+        Ok(self.add_built_block(blk).await)
+    }
+
+    /// Drops the cached blocks, for example after the VM state changes
+    /// outside consensus (e.g., state sync). The verified blocks and the
+    /// last accepted block are kept.
+    pub fn flush(&self) {
+        let mut caches = self.caches.lock().unwrap();
+        caches.decided.clear();
+        caches.unverified.clear();
+        caches.missing.clear();
+        caches.bytes_to_id.clear();
+    }
+
+    async fn add_built_block(&self, blk: V::Block) -> BlockWrapper<V::Block> {
+        let blk = BlockWrapper::new(blk, Arc::downgrade(&self.caches)).await;
+        let mut caches = self.caches.lock().unwrap();
+        caches.missing.pop(&blk.id);
+        caches.bytes_to_id.put(blk.bytes.clone(), blk.id);
+        caches.unverified.put(blk.id, blk.clone());
+        blk
+    }
+
+    /// Caches the block which is not yet known to consensus, as decided or
+    /// unverified by its status.
+    async fn add_block_outside_consensus(&self, blk: V::Block) -> BlockWrapper<V::Block> {
+        let decided = blk.status().await.decided();
+        let blk = BlockWrapper::new(blk, Arc::downgrade(&self.caches)).await;
+        let mut caches = self.caches.lock().unwrap();
+        if decided {
+            caches.decided.put(blk.id, blk.clone());
+        } else {
+            caches.unverified.put(blk.id, blk.clone());
+        }
+        blk
+    }
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features subnet -- subnet::rpc::snowman::chain::test_state --exact --show-output
+#[tokio::test]
+async fn test_state() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct TestBlock {
+        bytes: Vec<u8>,
+        status: Status,
+        accepts: Arc<AtomicUsize>,
+    }
+
+    impl TestBlock {
+        /// Encodes the block as the parent ID and the height.
+        fn new(parent: Id, height: u8, status: Status, accepts: &Arc<AtomicUsize>) -> Self {
+            let mut bytes = parent.to_vec();
+            bytes.push(height);
+            Self {
+                bytes,
+                status,
+                accepts: Arc::clone(accepts),
+            }
+        }
+    }
+
+    #[tonic::async_trait]
+    impl Decidable for TestBlock {
+        async fn id(&self) -> Id {
+            Id::sha256(&self.bytes)
+        }
+        async fn status(&self) -> Status {
+            self.status.clone()
+        }
+        async fn accept(&mut self) -> Result<()> {
+            self.accepts.fetch_add(1, Ordering::SeqCst);
+            self.status = Status::Accepted;
+            Ok(())
+        }
+        async fn reject(&mut self) -> Result<()> {
+            self.status = Status::Rejected;
+            Ok(())
+        }
+    }
+
+    #[tonic::async_trait]
+    impl Block for TestBlock {
+        async fn bytes(&self) -> &[u8] {
+            &self.bytes
+        }
+        async fn height(&self) -> u64 {
+            self.bytes[32] as u64
+        }
+        async fn timestamp(&self) -> u64 {
+            0
+        }
+        async fn parent(&self) -> Id {
+            Id::from_slice(&self.bytes[..32])
+        }
+        async fn verify(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    struct TestBackend {
+        genesis: Id,
+        gets: Arc<AtomicUsize>,
+        accepts: Arc<AtomicUsize>,
+    }
+
+    #[tonic::async_trait]
+    impl Backend for TestBackend {
+        type Block = TestBlock;
+
+        async fn get_block(&self, id: Id) -> Result<TestBlock> {
+            self.gets.fetch_add(1, Ordering::SeqCst);
+            let genesis = TestBlock::new(Id::empty(), 0, Status::Accepted, &self.accepts);
+            if id == self.genesis {
+                return Ok(genesis);
+            }
+            Err(errors::Error::NotFound.to_err())
+        }
+        async fn parse_block(&self, bytes: &[u8]) -> Result<TestBlock> {
+            Ok(TestBlock::new(
+                Id::from_slice(&bytes[..32]),
+                bytes[32],
+                Status::Processing,
+                &self.accepts,
+            ))
+        }
+        async fn build_block(&self) -> Result<TestBlock> {
+            Ok(TestBlock::new(
+                self.genesis,
+                1,
+                Status::Processing,
+                &self.accepts,
+            ))
+        }
+    }
+
+    let accepts = Arc::new(AtomicUsize::new(0));
+    let gets = Arc::new(AtomicUsize::new(0));
+    let genesis = TestBlock::new(Id::empty(), 0, Status::Accepted, &accepts);
+    let genesis_id = genesis.id().await;
+    let backend = TestBackend {
+        genesis: genesis_id,
+        gets: Arc::clone(&gets),
+        accepts: Arc::clone(&accepts),
+    };
+    let state = State::new(backend, Config::default(), genesis).await;
+    assert_eq!(state.last_accepted(), genesis_id);
+
+    // the last accepted and missing blocks are cached
+    state.get_block(genesis_id).await.unwrap();
+    let missing = Id::sha256("missing");
+    for _ in 0..2 {
+        let err = state.get_block(missing).await.err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+    }
+    assert_eq!(gets.load(Ordering::SeqCst), 1);
+
+    // the built block is deduplicated by its bytes and ID
+    let mut blk1 = state.build_block().await.unwrap();
+    let blk1_id = blk1.id().await;
+    let parsed = state.parse_block(blk1.bytes().await).await.unwrap();
+    assert!(Arc::ptr_eq(&parsed.inner(), &blk1.inner()));
+    assert!(!state.is_processing(&blk1_id));
+    blk1.verify().await.unwrap();
+    assert!(state.is_processing(&blk1_id));
+
+    // the conflicting block is rejected
+    let mut bytes = genesis_id.to_vec();
+    bytes.push(2);
+    let mut blk2 = state.parse_block(&bytes).await.unwrap();
+    blk2.verify().await.unwrap();
+    blk2.reject().await.unwrap();
+    blk2.reject().await.unwrap();
+    let err = blk2.accept().await.err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    // accepting twice is a no-op
+    blk1.accept().await.unwrap();
+    state
+        .get_block(blk1_id)
+        .await
+        .unwrap()
+        .accept()
+        .await
+        .unwrap();
+    assert_eq!(accepts.load(Ordering::SeqCst), 1);
+    assert!(!state.is_processing(&blk1_id));
+    assert_eq!(state.last_accepted(), blk1_id);
+    assert_eq!(state.last_accepted_block().height().await, 1);
+    assert_eq!(
+        state.get_block(blk1_id).await.unwrap().status().await,
+        Status::Accepted
+    );
+    assert_eq!(gets.load(Ordering::SeqCst), 1);
+
+    // the flushed blocks are read from the database again
+    state.flush();
+    state.get_block(genesis_id).await.unwrap();
+    assert_eq!(gets.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,137 @@
+//! Height index of the accepted blocks, to serve the
+//! [`HeightIndexedChainVm`](super::block::HeightIndexedChainVm) queries.
+use std::io::Result;
+
+use crate::{
+    ids::Id,
+    subnet::rpc::{database::BoxedDatabase, errors, snowman::block::HeightIndexedChainVm},
+};
+
+/// Prefix of the keys that map the height to the accepted block ID.
+const HEIGHT_PREFIX: u8 = 0x00;
+/// Key of the block ID from which the index is being repaired.
+const CHECKPOINT_KEY: &[u8] = &[0x01];
+
+/// Maps the heights to the IDs of the accepted blocks, on top of a
+/// [`BoxedDatabase`]. While the index is being (re)built, the VM sets a
+/// checkpoint so that the index is reported incomplete and the building can
+/// resume after a restart.
+///
+/// The index serves the height index queries as is, so that the VM can
+/// return it from [`ChainVm::height_indexed`](super::block::ChainVm::height_indexed).
+///
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/vms/proposervm/state#HeightIndex>
+#[derive(Clone)]
+pub struct HeightIndex {
+    db: BoxedDatabase,
+}
+
+impl HeightIndex {
+    pub fn new(db: BoxedDatabase) -> Self {
+        Self { db }
+    }
+
+    /// Returns the ID of the accepted block at the height, or the not found
+    /// error if the height is not indexed.
+    pub async fn get(&self, height: u64) -> Result<Id> {
+        let blk_id = self.db.get(&height_key(height)).await?;
+        Ok(Id::from_slice(&blk_id))
+    }
+
+    /// Indexes the accepted block at the height.
+    pub async fn put(&mut self, height: u64, blk_id: &Id) -> Result<()> {
+        self.db.put(&height_key(height), &blk_id.to_vec()).await
+    }
+
+    /// Removes the height from the index (e.g., to prune the old blocks).
+    pub async fn delete(&mut self, height: u64) -> Result<()> {
+        self.db.delete(&height_key(height)).await
+    }
+
+    /// Returns the block ID from which the index is being repaired, or the
+    /// not found error if the index is not being repaired.
+    pub async fn get_checkpoint(&self) -> Result<Id> {
+        let blk_id = self.db.get(CHECKPOINT_KEY).await?;
+        Ok(Id::from_slice(&blk_id))
+    }
+
+    /// Records the block ID from which the index is being repaired.
+    pub async fn set_checkpoint(&mut self, blk_id: &Id) -> Result<()> {
+        self.db.put(CHECKPOINT_KEY, &blk_id.to_vec()).await
+    }
+
+    /// Marks the index complete, once the repair is done.
+    pub async fn delete_checkpoint(&mut self) -> Result<()> {
+        self.db.delete(CHECKPOINT_KEY).await
+    }
+
+    /// Returns the index incomplete error if the index is being repaired.
+    pub async fn verify(&self) -> Result<()> {
+        if self.db.has(CHECKPOINT_KEY).await? {
+            return Err(errors::Error::IndexIncomplete.to_err());
+        }
+        Ok(())
+    }
+}
+
+#[tonic::async_trait]
+impl HeightIndexedChainVm for HeightIndex {
+    async fn verify_height_index(&self) -> Result<()> {
+        self.verify().await
+    }
+
+    /// Fails with the index incomplete error while the index is being
+    /// repaired, since the missing heights are not known to be missing.
+    async fn get_block_id_at_height(&self, height: u64) -> Result<Id> {
+        self.verify().await?;
+        self.get(height).await
+    }
+}
+
+fn height_key(height: u64) -> Vec<u8> {
+    let mut key = Vec::with_capacity(1 + 8);
+    key.push(HEIGHT_PREFIX);
+    key.extend_from_slice(&height.to_be_bytes());
+    key
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features subnet -- subnet::rpc::snowman::height_index::test_height_index --exact --show-output
+#[tokio::test]
+async fn test_height_index() {
+    use crate::subnet::rpc::database::memdb;
+
+    let mut index = HeightIndex::new(memdb::Database::new());
+    index.verify().await.unwrap();
+
+    let blk0 = Id::sha256("blk0");
+    let blk1 = Id::sha256("blk1");
+    index.put(0, &blk0).await.unwrap();
+    index.put(1, &blk1).await.unwrap();
+    assert_eq!(index.get(0).await.unwrap(), blk0);
+    assert_eq!(index.get(1).await.unwrap(), blk1);
+
+    let err = index.get(2).await.unwrap_err();
+    assert!(errors::is_not_found(&err));
+
+    index.delete(0).await.unwrap();
+    assert!(errors::is_not_found(&index.get(0).await.unwrap_err()));
+
+    // repairing
+    assert!(errors::is_not_found(
+        &index.get_checkpoint().await.unwrap_err()
+    ));
+    index.set_checkpoint(&blk1).await.unwrap();
+    assert_eq!(index.get_checkpoint().await.unwrap(), blk1);
+    let err = index.verify().await.unwrap_err();
+    assert_eq!(
+        errors::to_i32(&err),
+        Some(errors::Error::IndexIncomplete.to_i32())
+    );
+
+    index.delete_checkpoint().await.unwrap();
+    index.verify().await.unwrap();
+
+    // shares the underlying database
+    let cloned = index.clone();
+    assert_eq!(cloned.get(1).await.unwrap(), blk1);
+}
@@ -1 +1,3 @@
 pub mod block;
+pub mod chain;
+pub mod height_index;
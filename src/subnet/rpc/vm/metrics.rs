@@ -0,0 +1,99 @@
+//! Metrics of the VM RPCs, gathered along with the metrics the VM registers.
+//! ref. <https://pkg.go.dev/github.com/luxfi/node/vms/metervm>
+use prometheus::{
+    histogram_opts, opts, HistogramTimer, HistogramVec, IntCounterVec, Registry, Result,
+};
+
+/// Namespace of the metrics, to not collide with the ones the VM registers.
+const NAMESPACE: &str = "rpcchainvm";
+
+pub const BUILD_BLOCK: &str = "build_block";
+pub const PARSE_BLOCK: &str = "parse_block";
+pub const GET_BLOCK: &str = "get_block";
+pub const VERIFY: &str = "verify";
+pub const ACCEPT: &str = "accept";
+pub const REJECT: &str = "reject";
+
+pub const BUILT: &str = "built";
+pub const VERIFIED: &str = "verified";
+pub const ACCEPTED: &str = "accepted";
+pub const REJECTED: &str = "rejected";
+
+/// Latencies of the block RPCs, and the number of blocks by their outcome.
+#[derive(Clone, Debug)]
+pub struct Metrics {
+    /// Labeled by the RPC (e.g., [`BUILD_BLOCK`]).
+    latency: HistogramVec,
+    /// Labeled by the outcome (e.g., [`ACCEPTED`]).
+    blocks: IntCounterVec,
+}
+
+impl Metrics {
+    /// Creates the metrics and registers them to the registry.
+    pub fn new(registry: &Registry) -> Result<Self> {
+        let latency = HistogramVec::new(
+            histogram_opts!("block_rpc_duration_seconds", "Latency of the block RPCs")
+                .namespace(NAMESPACE),
+            &["method"],
+        )?;
+        let blocks = IntCounterVec::new(
+            opts!("blocks_total", "Number of blocks by their outcome").namespace(NAMESPACE),
+            &["status"],
+        )?;
+        registry.register(Box::new(latency.clone()))?;
+        registry.register(Box::new(blocks.clone()))?;
+
+        Ok(Self { latency, blocks })
+    }
+
+    /// Returns the timer which observes the latency of the RPC when dropped.
+    pub fn start_timer(&self, method: &str) -> HistogramTimer {
+        self.latency.with_label_values(&[method]).start_timer()
+    }
+
+    /// Counts the block with the outcome.
+    pub fn inc_blocks(&self, status: &str) {
+        self.blocks.with_label_values(&[status]).inc();
+    }
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features subnet_metrics -- subnet::rpc::vm::metrics::test_metrics --exact --show-output
+#[test]
+fn test_metrics() {
+    let registry = Registry::new();
+    let metrics = Metrics::new(&registry).unwrap();
+    // already registered
+    assert!(Metrics::new(&registry).is_err());
+
+    {
+        let _timer = metrics.start_timer(BUILD_BLOCK);
+        metrics.inc_blocks(BUILT);
+    }
+    metrics.inc_blocks(ACCEPTED);
+    metrics.inc_blocks(ACCEPTED);
+
+    let families = registry.gather();
+    let latency = families
+        .iter()
+        .find(|mf| mf.get_name() == "rpcchainvm_block_rpc_duration_seconds")
+        .unwrap();
+    assert_eq!(
+        latency.get_metric()[0].get_histogram().get_sample_count(),
+        1
+    );
+
+    let blocks = families
+        .iter()
+        .find(|mf| mf.get_name() == "rpcchainvm_blocks_total")
+        .unwrap();
+    let count = |status: &str| {
+        blocks
+            .get_metric()
+            .iter()
+            .find(|m| m.get_label()[0].get_value() == status)
+            .map(|m| m.get_counter().get_value())
+    };
+    assert_eq!(count(ACCEPTED), Some(2.0));
+    assert_eq!(count(BUILT), Some(1.0));
+    assert_eq!(count(REJECTED), None);
+}
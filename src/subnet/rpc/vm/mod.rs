@@ -1,6 +1,9 @@
 //! RPC Chain VM implementation.
 pub mod server;
 
+#[cfg(any(doc, feature = "subnet_metrics"))]
+pub mod metrics;
+
 use std::{
     env,
     io::{Error, ErrorKind, Result},
@@ -9,14 +9,13 @@ use crate::{
     packer::U32_LEN,
     proto::pb::{
         self,
-        aliasreader::alias_reader_client::AliasReaderClient,
         google::protobuf::Empty,
-        keystore::keystore_client::KeystoreClient,
         messenger::{messenger_client::MessengerClient, NotifyRequest},
-        sharedmemory::shared_memory_client::SharedMemoryClient,
         vm,
     },
     subnet::rpc::{
+        aliasreader::client::AliasReaderClient,
+        atomic::client::SharedMemoryClient,
         consensus::snowman::{Block, Decidable},
         context::Context,
         database::rpcdb::{client::DatabaseClient, error_to_error_code},
@@ -26,16 +25,18 @@ use crate::{
         },
         errors,
         http::server::Server as HttpServer,
+        keystore::client::KeystoreClient,
         snow::{
             engine::common::{appsender::client::AppSenderClient, message::Message},
             validators::client::ValidatorStateClient,
             State,
         },
-        snowman::block::ChainVm,
+        snowman::block::{self, ChainVm, HeightIndexedChainVm, StateSyncableVm},
         utils::{
             self,
             grpc::{self, timestamp_from_time},
         },
+        warp::client::WarpSignerClient,
     },
 };
 use chrono::{TimeZone, Utc};
@@ -45,6 +46,9 @@ use semver::Version;
 use tokio::sync::{broadcast, mpsc, RwLock};
 use tonic::{transport::Endpoint, Request, Response};
 
+#[cfg(feature = "subnet_metrics")]
+use super::metrics;
+
 pub struct Server<V> {
     /// Underlying Vm implementation.
     pub vm: Arc<RwLock<V>>,
@@ -54,17 +58,37 @@ pub struct Server<V> {
     /// Subnet Prometheus process metrics.
     pub process_metrics: Arc<RwLock<prometheus::Registry>>,
 
+    #[cfg(feature = "subnet_metrics")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subnet_metrics")))]
+    /// Registry of the metrics the VM registers through its context, along
+    /// with the metrics of the VM RPCs.
+    pub vm_metrics: prometheus::Registry,
+
+    #[cfg(feature = "subnet_metrics")]
+    rpc_metrics: metrics::Metrics,
+
     /// Stop channel broadcast producer.
     pub stop_ch: broadcast::Sender<()>,
 }
 
 impl<V: ChainVm> Server<V> {
     pub fn new(vm: V, stop_ch: broadcast::Sender<()>) -> Self {
+        #[cfg(feature = "subnet_metrics")]
+        let vm_metrics = prometheus::Registry::new();
+        #[cfg(feature = "subnet_metrics")]
+        let rpc_metrics =
+            metrics::Metrics::new(&vm_metrics).expect("failed to register the VM RPC metrics");
+
         Self {
             vm: Arc::new(RwLock::new(vm)),
             #[cfg(feature = "subnet_metrics")]
             #[cfg_attr(docsrs, doc(cfg(feature = "subnet_metrics")))]
             process_metrics: Arc::new(RwLock::new(prometheus::default_registry().to_owned())),
+            #[cfg(feature = "subnet_metrics")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "subnet_metrics")))]
+            vm_metrics,
+            #[cfg(feature = "subnet_metrics")]
+            rpc_metrics,
             stop_ch,
         }
     }
@@ -120,9 +144,9 @@ where
         // Multiplexing in tonic is done by cloning the client which is very cheap.
         // ref. https://docs.rs/tonic/latest/tonic/transport/struct.Channel.html#multiplexing-requests
         let mut message = MessengerClient::new(client_conn.clone());
-        let keystore = KeystoreClient::new(client_conn.clone());
-        let shared_memory = SharedMemoryClient::new(client_conn.clone());
-        let bc_lookup = AliasReaderClient::new(client_conn.clone());
+        let keystore = Arc::new(KeystoreClient::new(client_conn.clone()));
+        let shared_memory = Arc::new(SharedMemoryClient::new(client_conn.clone()));
+        let bc_lookup = Arc::new(AliasReaderClient::new(client_conn.clone()));
 
         let ctx: Option<Context<ValidatorStateClient>> = Some(Context {
             network_id: req.network_id,
@@ -137,6 +161,9 @@ where
             bc_lookup,
             chain_data_dir: req.chain_data_dir,
             validator_state: ValidatorStateClient::new(client_conn.clone()),
+            warp_signer: Arc::new(WarpSignerClient::new(client_conn.clone())),
+            #[cfg(feature = "subnet_metrics")]
+            metrics: self.vm_metrics.clone(),
         });
 
         let mut versioned_dbs = Vec::with_capacity(req.db_servers.len());
@@ -333,29 +360,31 @@ where
         }))
     }
 
+    /// Builds the block with the P-chain height if the request carries it
+    /// and the VM exposes [`ChainVm::block_builder_with_context`], and with
+    /// [`ChainVm::build_block`] otherwise.
     async fn build_block(
         &self,
-        _req: Request<vm::BuildBlockRequest>,
+        req: Request<vm::BuildBlockRequest>,
     ) -> std::result::Result<Response<vm::BuildBlockResponse>, tonic::Status> {
         log::debug!("build_block called");
+        #[cfg(feature = "subnet_metrics")]
+        let _timer = self.rpc_metrics.start_timer(metrics::BUILD_BLOCK);
 
+        let req = req.into_inner();
         let inner_vm = self.vm.write().await;
-        let block = inner_vm
-            .build_block()
-            .await
-            .map_err(|e| tonic::Status::unknown(e.to_string()))?;
-
-        Ok(Response::new(vm::BuildBlockResponse {
-            id: Bytes::from(block.id().await.to_vec()),
-            parent_id: Bytes::from(block.parent().await.to_vec()),
-            bytes: Bytes::from(block.bytes().await.to_vec()),
-            height: block.height().await,
-            timestamp: Some(timestamp_from_time(
-                &Utc.timestamp_opt(block.timestamp().await as i64, 0)
-                    .unwrap(),
-            )),
-            verify_with_context: false,
-        }))
+        let block = match (req.p_chain_height, inner_vm.block_builder_with_context()) {
+            (Some(p_chain_height), Some(builder)) => {
+                builder
+                    .build_block_with_context(&block::Context { p_chain_height })
+                    .await
+            }
+            _ => inner_vm.build_block().await,
+        }
+        .map_err(|e| tonic::Status::unknown(e.to_string()))?;
+        #[cfg(feature = "subnet_metrics")]
+        self.rpc_metrics.inc_blocks(metrics::BUILT);
+        Ok(Response::new(build_block_response(&block).await?))
     }
 
     async fn parse_block(
@@ -363,6 +392,8 @@ where
         req: Request<vm::ParseBlockRequest>,
     ) -> std::result::Result<Response<vm::ParseBlockResponse>, tonic::Status> {
         log::debug!("parse_block called");
+        #[cfg(feature = "subnet_metrics")]
+        let _timer = self.rpc_metrics.start_timer(metrics::PARSE_BLOCK);
 
         let req = req.into_inner();
         let inner_vm = self.vm.write().await;
@@ -371,6 +402,8 @@ where
             .await
             .map_err(|e| tonic::Status::unknown(e.to_string()))?;
 
+        let verify_with_context = should_verify_with_context(&block).await?;
+
         Ok(Response::new(vm::ParseBlockResponse {
             id: Bytes::from(block.id().await.to_vec()),
             parent_id: Bytes::from(block.parent().await.to_vec()),
@@ -380,7 +413,7 @@ where
                 &Utc.timestamp_opt(block.timestamp().await as i64, 0)
                     .unwrap(),
             )),
-            verify_with_context: false,
+            verify_with_context,
         }))
     }
 
@@ -401,6 +434,8 @@ where
         req: Request<vm::GetBlockRequest>,
     ) -> std::result::Result<Response<vm::GetBlockResponse>, tonic::Status> {
         log::debug!("get_block called");
+        #[cfg(feature = "subnet_metrics")]
+        let _timer = self.rpc_metrics.start_timer(metrics::GET_BLOCK);
 
         let req = req.into_inner();
         let inner_vm = self.vm.read().await;
@@ -417,7 +452,7 @@ where
                         .unwrap(),
                 )),
                 err: 0, // return 0 indicating no error
-                verify_with_context: false,
+                verify_with_context: should_verify_with_context(&block).await?,
             })),
             // if an error was found, generate empty response with ErrNotFound code
             // ref: https://github.com/luxfi/node/blob/master/vms/
@@ -646,10 +681,31 @@ where
             .await
             .map_err(|e| tonic::Status::unknown(e.to_string()))?;
 
-        block
-            .verify()
-            .await
-            .map_err(|e| tonic::Status::unknown(e.to_string()))?;
+        #[cfg(feature = "subnet_metrics")]
+        let timer = self.rpc_metrics.start_timer(metrics::VERIFY);
+        // the node only sends the P-chain height if the block asked for it
+        // with "verify_with_context"
+        match req.p_chain_height {
+            Some(p_chain_height) => match block.with_verify_context() {
+                Some(block) => {
+                    block
+                        .verify_with_context(&block::Context { p_chain_height })
+                        .await
+                }
+                // ref. "vms/rpcchainvm.errExpectedBlockWithVerifyContext"
+                None => Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "expected block.WithVerifyContext",
+                )),
+            },
+            None => block.verify().await,
+        }
+        .map_err(|e| tonic::Status::unknown(e.to_string()))?;
+        #[cfg(feature = "subnet_metrics")]
+        {
+            timer.observe_duration();
+            self.rpc_metrics.inc_blocks(metrics::VERIFIED);
+        }
 
         Ok(Response::new(vm::BlockVerifyResponse {
             timestamp: Some(timestamp_from_time(
@@ -674,10 +730,17 @@ where
             .await
             .map_err(|e| tonic::Status::unknown(e.to_string()))?;
 
+        #[cfg(feature = "subnet_metrics")]
+        let timer = self.rpc_metrics.start_timer(metrics::ACCEPT);
         block
             .accept()
             .await
             .map_err(|e| tonic::Status::unknown(e.to_string()))?;
+        #[cfg(feature = "subnet_metrics")]
+        {
+            timer.observe_duration();
+            self.rpc_metrics.inc_blocks(metrics::ACCEPTED);
+        }
 
         Ok(Response::new(Empty {}))
     }
@@ -696,10 +759,17 @@ where
             .await
             .map_err(|e| tonic::Status::unknown(e.to_string()))?;
 
+        #[cfg(feature = "subnet_metrics")]
+        let timer = self.rpc_metrics.start_timer(metrics::REJECT);
         block
             .reject()
             .await
             .map_err(|e| tonic::Status::unknown(e.to_string()))?;
+        #[cfg(feature = "subnet_metrics")]
+        {
+            timer.observe_duration();
+            self.rpc_metrics.inc_blocks(metrics::REJECTED);
+        }
 
         Ok(Response::new(Empty {}))
     }
@@ -851,10 +921,9 @@ where
         log::debug!("gather called");
 
         // ref. <https://prometheus.io/docs/instrumenting/writing_clientlibs/#process-metrics>
-        let metric_families = crate::subnet::rpc::metrics::MetricsFamilies::from(
-            &self.process_metrics.read().await.gather(),
-        )
-        .mfs;
+        let mut families = self.process_metrics.read().await.gather();
+        families.extend(self.vm_metrics.gather());
+        let metric_families = crate::subnet::rpc::metrics::MetricsFamilies::from(&families).mfs;
 
         Ok(Response::new(vm::GatherResponse { metric_families }))
     }
@@ -919,7 +988,22 @@ where
     ) -> std::result::Result<Response<vm::StateSyncEnabledResponse>, tonic::Status> {
         log::debug!("state_sync_enabled called");
 
-        Err(tonic::Status::unimplemented("state_sync_enabled"))
+        let inner_vm = self.vm.read().await;
+        let result = match inner_vm.state_syncable() {
+            Some(vm) => vm.state_sync_enabled().await,
+            // ref. "vms/rpcchainvm.VMServer.StateSyncEnabled"
+            None => Ok(false),
+        };
+        match result {
+            Ok(enabled) => Ok(Response::new(vm::StateSyncEnabledResponse {
+                enabled,
+                err: 0,
+            })),
+            Err(e) => Ok(Response::new(vm::StateSyncEnabledResponse {
+                enabled: false,
+                err: error_to_code(e).map_err(|e| tonic::Status::unknown(e.to_string()))?,
+            })),
+        }
     }
 
     async fn get_ongoing_sync_state_summary(
@@ -928,27 +1012,73 @@ where
     ) -> std::result::Result<Response<vm::GetOngoingSyncStateSummaryResponse>, tonic::Status> {
         log::debug!("get_ongoing_sync_state_summary called");
 
-        Err(tonic::Status::unimplemented(
-            "get_ongoing_sync_state_summary",
-        ))
+        let inner_vm = self.vm.read().await;
+        let result = match state_syncable(&*inner_vm) {
+            Ok(vm) => vm.get_ongoing_sync_state_summary().await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(summary) => Ok(Response::new(vm::GetOngoingSyncStateSummaryResponse {
+                id: Bytes::from(summary.id().await.to_vec()),
+                height: summary.height().await,
+                bytes: Bytes::from(summary.bytes().await.to_vec()),
+                err: 0,
+            })),
+            Err(e) => Ok(Response::new(vm::GetOngoingSyncStateSummaryResponse {
+                err: error_to_code(e).map_err(|e| tonic::Status::unknown(e.to_string()))?,
+                ..Default::default()
+            })),
+        }
     }
 
     async fn parse_state_summary(
         &self,
-        _req: Request<vm::ParseStateSummaryRequest>,
+        req: Request<vm::ParseStateSummaryRequest>,
     ) -> std::result::Result<tonic::Response<vm::ParseStateSummaryResponse>, tonic::Status> {
         log::debug!("parse_state_summary called");
 
-        Err(tonic::Status::unimplemented("parse_state_summary"))
+        let req = req.into_inner();
+        let inner_vm = self.vm.read().await;
+        let result = match state_syncable(&*inner_vm) {
+            Ok(vm) => vm.parse_state_summary(req.bytes.as_ref()).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(summary) => Ok(Response::new(vm::ParseStateSummaryResponse {
+                id: Bytes::from(summary.id().await.to_vec()),
+                height: summary.height().await,
+                err: 0,
+            })),
+            Err(e) => Ok(Response::new(vm::ParseStateSummaryResponse {
+                err: error_to_code(e).map_err(|e| tonic::Status::unknown(e.to_string()))?,
+                ..Default::default()
+            })),
+        }
     }
 
     async fn get_state_summary(
         &self,
-        _req: Request<vm::GetStateSummaryRequest>,
+        req: Request<vm::GetStateSummaryRequest>,
     ) -> std::result::Result<Response<vm::GetStateSummaryResponse>, tonic::Status> {
         log::debug!("get_state_summary called");
 
-        Err(tonic::Status::unimplemented("get_state_summary"))
+        let req = req.into_inner();
+        let inner_vm = self.vm.read().await;
+        let result = match state_syncable(&*inner_vm) {
+            Ok(vm) => vm.get_state_summary(req.height).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(summary) => Ok(Response::new(vm::GetStateSummaryResponse {
+                id: Bytes::from(summary.id().await.to_vec()),
+                bytes: Bytes::from(summary.bytes().await.to_vec()),
+                err: 0,
+            })),
+            Err(e) => Ok(Response::new(vm::GetStateSummaryResponse {
+                err: error_to_code(e).map_err(|e| tonic::Status::unknown(e.to_string()))?,
+                ..Default::default()
+            })),
+        }
     }
 
     async fn get_last_state_summary(
@@ -957,16 +1087,54 @@ where
     ) -> std::result::Result<Response<vm::GetLastStateSummaryResponse>, tonic::Status> {
         log::debug!("get_last_state_summary called");
 
-        Err(tonic::Status::unimplemented("get_last_state_summary"))
+        let inner_vm = self.vm.read().await;
+        let result = match state_syncable(&*inner_vm) {
+            Ok(vm) => vm.get_last_state_summary().await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(summary) => Ok(Response::new(vm::GetLastStateSummaryResponse {
+                id: Bytes::from(summary.id().await.to_vec()),
+                height: summary.height().await,
+                bytes: Bytes::from(summary.bytes().await.to_vec()),
+                err: 0,
+            })),
+            Err(e) => Ok(Response::new(vm::GetLastStateSummaryResponse {
+                err: error_to_code(e).map_err(|e| tonic::Status::unknown(e.to_string()))?,
+                ..Default::default()
+            })),
+        }
     }
 
+    /// Parses the summary and accepts it, so that the VM starts syncing
+    /// its state to the summary.
+    ///
+    /// ref. <https://pkg.go.dev/github.com/luxfi/node/snow/engine/snowman/block#StateSummary>
     async fn state_summary_accept(
         &self,
-        _req: Request<vm::StateSummaryAcceptRequest>,
+        req: Request<vm::StateSummaryAcceptRequest>,
     ) -> std::result::Result<tonic::Response<vm::StateSummaryAcceptResponse>, tonic::Status> {
         log::debug!("state_summary_accept called");
 
-        Err(tonic::Status::unimplemented("state_summary_accept"))
+        let req = req.into_inner();
+        let inner_vm = self.vm.write().await;
+        let result = match state_syncable(&*inner_vm) {
+            Ok(vm) => match vm.parse_state_summary(req.bytes.as_ref()).await {
+                Ok(mut summary) => summary.accept().await,
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(mode) => Ok(Response::new(vm::StateSummaryAcceptResponse {
+                mode: mode.to_i32(),
+                err: 0,
+            })),
+            Err(e) => Ok(Response::new(vm::StateSummaryAcceptResponse {
+                mode: 0,
+                err: error_to_code(e).map_err(|e| tonic::Status::unknown(e.to_string()))?,
+            })),
+        }
     }
 
     async fn verify_height_index(
@@ -974,15 +1142,167 @@ where
         _req: Request<Empty>,
     ) -> std::result::Result<Response<vm::VerifyHeightIndexResponse>, tonic::Status> {
         log::debug!("verify_height_index called");
-        Err(tonic::Status::unimplemented("verify_height_index"))
+
+        let inner_vm = self.vm.read().await;
+        let result = match height_indexed(&*inner_vm) {
+            Ok(vm) => vm.verify_height_index().await,
+            Err(e) => Err(e),
+        };
+        let err = match result {
+            Ok(()) => 0,
+            Err(e) => error_to_code(e).map_err(|e| tonic::Status::unknown(e.to_string()))?,
+        };
+        Ok(Response::new(vm::VerifyHeightIndexResponse { err }))
     }
 
     async fn get_block_id_at_height(
         &self,
-        _req: Request<vm::GetBlockIdAtHeightRequest>,
+        req: Request<vm::GetBlockIdAtHeightRequest>,
     ) -> std::result::Result<Response<vm::GetBlockIdAtHeightResponse>, tonic::Status> {
         log::debug!("get_block_id_at_height called");
 
-        Err(tonic::Status::unimplemented("get_block_id_at_height"))
+        let req = req.into_inner();
+        let inner_vm = self.vm.read().await;
+        let result = match height_indexed(&*inner_vm) {
+            Ok(vm) => vm.get_block_id_at_height(req.height).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(blk_id) => Ok(Response::new(vm::GetBlockIdAtHeightResponse {
+                blk_id: Bytes::from(blk_id.to_vec()),
+                err: 0,
+            })),
+            Err(e) => Ok(Response::new(vm::GetBlockIdAtHeightResponse {
+                blk_id: Bytes::new(),
+                err: error_to_code(e).map_err(|e| tonic::Status::unknown(e.to_string()))?,
+            })),
+        }
+    }
+}
+
+/// Returns the response of the newly built block.
+async fn build_block_response<B: Block>(
+    block: &B,
+) -> std::result::Result<vm::BuildBlockResponse, tonic::Status> {
+    Ok(vm::BuildBlockResponse {
+        id: Bytes::from(block.id().await.to_vec()),
+        parent_id: Bytes::from(block.parent().await.to_vec()),
+        bytes: Bytes::from(block.bytes().await.to_vec()),
+        height: block.height().await,
+        timestamp: Some(timestamp_from_time(
+            &Utc.timestamp_opt(block.timestamp().await as i64, 0)
+                .unwrap(),
+        )),
+        verify_with_context: should_verify_with_context(block).await?,
+    })
+}
+
+/// Returns true if the block asks to be verified with the P-chain height.
+async fn should_verify_with_context<B: Block>(
+    block: &B,
+) -> std::result::Result<bool, tonic::Status> {
+    match block.with_verify_context() {
+        Some(block) => block
+            .should_verify_with_context()
+            .await
+            .map_err(|e| tonic::Status::unknown(e.to_string())),
+        None => Ok(false),
     }
 }
+
+/// Returns the height index capability of the VM, or the not implemented
+/// error if the VM does not index the blocks by height.
+fn height_indexed<V: ChainVm>(vm: &V) -> std::io::Result<&dyn HeightIndexedChainVm> {
+    vm.height_indexed()
+        .ok_or_else(|| errors::Error::HeightIndexedVMNotImplemented.to_err())
+}
+
+/// Returns the state sync capability of the VM, or the not implemented error
+/// if the VM does not support the state sync.
+fn state_syncable<V: ChainVm>(vm: &V) -> std::io::Result<&dyn StateSyncableVm> {
+    vm.state_syncable()
+        .ok_or_else(|| errors::Error::StateSyncableVMNotImplemented.to_err())
+}
+
+/// Returns the error code of the common VM errors (e.g., not found) to be
+/// set in the response, or the error itself to be returned as the gRPC error
+/// status.
+///
+/// ref. "vms/rpcchainvm.errorToRPCError"
+fn error_to_code(e: std::io::Error) -> std::io::Result<i32> {
+    errors::to_i32(&e).ok_or(e)
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features subnet -- subnet::rpc::vm::server::test_height_index_rpcs --exact --show-output
+#[tokio::test]
+async fn test_height_index_rpcs() {
+    use crate::subnet::rpc::{
+        database::memdb, harness::testvm, snowman::height_index::HeightIndex,
+    };
+
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Debug)
+        .is_test(true)
+        .try_init();
+
+    let (stop_ch, _stop_rx) = broadcast::channel(1);
+    let height_request = |height| Request::new(vm::GetBlockIdAtHeightRequest { height });
+
+    // the VM does not index the blocks by height
+    let server = Server::new(testvm::Vm::new(), stop_ch.clone());
+    let not_implemented = errors::Error::HeightIndexedVMNotImplemented.to_i32();
+    let resp = server
+        .verify_height_index(Request::new(Empty {}))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(resp.err, not_implemented);
+    let resp = server
+        .get_block_id_at_height(height_request(0))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(resp.err, not_implemented);
+    assert!(resp.blk_id.is_empty());
+
+    let mut index = HeightIndex::new(memdb::Database::new());
+    let blk_id = ids::Id::sha256("blk");
+    index.put(5, &blk_id).await.unwrap();
+    let server = Server::new(testvm::Vm::with_height_index(index.clone()), stop_ch);
+
+    let resp = server
+        .verify_height_index(Request::new(Empty {}))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(resp.err, 0);
+    let resp = server
+        .get_block_id_at_height(height_request(5))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(resp.err, 0);
+    assert_eq!(ids::Id::from_slice(&resp.blk_id), blk_id);
+    let resp = server
+        .get_block_id_at_height(height_request(6))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(resp.err, errors::Error::NotFound.to_i32());
+
+    // the index is being repaired
+    index.set_checkpoint(&blk_id).await.unwrap();
+    let index_incomplete = errors::Error::IndexIncomplete.to_i32();
+    let resp = server
+        .verify_height_index(Request::new(Empty {}))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(resp.err, index_incomplete);
+    let resp = server
+        .get_block_id_at_height(height_request(5))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(resp.err, index_incomplete);
+}
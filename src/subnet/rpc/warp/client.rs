@@ -0,0 +1,40 @@
+use std::io::{Error, ErrorKind, Result};
+
+use crate::{
+    platformvm::warp::UnsignedMessage,
+    proto::pb::warp::{signer_client, SignRequest},
+};
+use prost::bytes::Bytes;
+use tonic::transport::Channel;
+
+/// A gRPC client which signs the Warp messages with the node's BLS key.
+#[derive(Clone, Debug)]
+pub struct WarpSignerClient {
+    inner: signer_client::SignerClient<Channel>,
+}
+
+impl WarpSignerClient {
+    pub fn new(client_conn: Channel) -> Self {
+        Self {
+            inner: signer_client::SignerClient::new(client_conn),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl super::Signer for WarpSignerClient {
+    async fn sign(&self, msg: &UnsignedMessage) -> Result<Vec<u8>> {
+        let mut client = self.inner.clone();
+        let resp = client
+            .sign(SignRequest {
+                network_id: msg.network_id,
+                source_chain_id: Bytes::from(msg.source_chain_id.to_vec()),
+                payload: Bytes::from(msg.payload.clone()),
+            })
+            .await
+            .map_err(|e| Error::new(ErrorKind::Other, format!("sign failed: {e}")))?
+            .into_inner();
+
+        Ok(resp.signature.to_vec())
+    }
+}
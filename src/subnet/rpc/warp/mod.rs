@@ -0,0 +1,124 @@
+//! Warp message signing for VMs.
+//! ref. <https://pkg.go.dev/github.com/luxfi/node/vms/platformvm/warp/gwarp>
+pub mod client;
+pub mod server;
+
+use std::{
+    fmt::Debug,
+    io::{Error, ErrorKind, Result},
+};
+
+use crate::{ids, key::bls, platformvm::warp::UnsignedMessage};
+
+/// Signs the Warp unsigned messages with the BLS key of the node.
+///
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/vms/platformvm/warp#Signer>
+#[tonic::async_trait]
+pub trait Signer: Debug + Send + Sync {
+    /// Returns the compressed BLS signature over the message bytes.
+    /// Fails if the message does not originate from this chain.
+    async fn sign(&self, msg: &UnsignedMessage) -> Result<Vec<u8>>;
+}
+
+/// Signer backed by a local BLS private key, which only signs the messages
+/// whose network and source chain IDs match its own.
+///
+/// ref. <https://pkg.go.dev/github.com/luxfi/node/vms/platformvm/warp#NewSigner>
+#[derive(Debug, Clone)]
+pub struct LocalSigner {
+    sk: bls::private_key::Key,
+    network_id: u32,
+    chain_id: ids::Id,
+}
+
+impl LocalSigner {
+    pub fn new(sk: bls::private_key::Key, network_id: u32, chain_id: ids::Id) -> Self {
+        Self {
+            sk,
+            network_id,
+            chain_id,
+        }
+    }
+
+    pub fn to_public_key(&self) -> bls::public_key::Key {
+        self.sk.to_public_key()
+    }
+}
+
+#[tonic::async_trait]
+impl Signer for LocalSigner {
+    async fn sign(&self, msg: &UnsignedMessage) -> Result<Vec<u8>> {
+        if msg.network_id != self.network_id {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "wrong network ID {} (expected {})",
+                    msg.network_id, self.network_id
+                ),
+            ));
+        }
+        if msg.source_chain_id != self.chain_id {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "wrong source chain ID {} (expected {})",
+                    msg.source_chain_id, self.chain_id
+                ),
+            ));
+        }
+
+        let msg_bytes = msg
+            .to_bytes()
+            .map_err(|e| Error::new(ErrorKind::InvalidInput, e.message()))?;
+        Ok(self.sk.sign(&msg_bytes).to_compressed_bytes().to_vec())
+    }
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib --features subnet -- subnet::rpc::warp::test_signer --exact --show-output
+#[tokio::test]
+async fn test_signer() {
+    use crate::proto::pb::warp::signer_server::SignerServer;
+    use tokio::{net::TcpListener, sync::oneshot};
+    use tokio_stream::wrappers::TcpListenerStream;
+
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .is_test(true)
+        .try_init();
+
+    let chain_id = ids::Id::sha256("chain");
+    let local = LocalSigner::new(bls::private_key::Key::generate().unwrap(), 5, chain_id);
+    let pubkey = local.to_public_key();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (stop_tx, stop_rx) = oneshot::channel::<()>();
+    let server = tokio::spawn(
+        tonic::transport::Server::builder()
+            .add_service(SignerServer::new(server::Server::new(local)))
+            .serve_with_incoming_shutdown(TcpListenerStream::new(listener), async {
+                stop_rx.await.ok();
+            }),
+    );
+
+    let client_conn = tonic::transport::Endpoint::from_shared(format!("http://{}", addr))
+        .unwrap()
+        .connect()
+        .await
+        .unwrap();
+    let client = client::WarpSignerClient::new(client_conn);
+
+    let msg = UnsignedMessage::new(5, chain_id, b"hello");
+    let sig = client.sign(&msg).await.unwrap();
+    let sig = bls::signature::Sig::from_bytes(&sig).unwrap();
+    assert!(sig.verify(&msg.to_bytes().unwrap(), &pubkey));
+
+    // not from this chain
+    let msg = UnsignedMessage::new(5, ids::Id::sha256("other"), b"hello");
+    assert!(client.sign(&msg).await.is_err());
+    let msg = UnsignedMessage::new(1, chain_id, b"hello");
+    assert!(client.sign(&msg).await.is_err());
+
+    stop_tx.send(()).unwrap();
+    server.await.unwrap().unwrap();
+}
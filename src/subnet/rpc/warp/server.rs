@@ -0,0 +1,64 @@
+use std::{
+    io::{Error, ErrorKind},
+    sync::Arc,
+};
+
+use crate::{
+    ids,
+    platformvm::warp::UnsignedMessage,
+    proto::pb::{
+        self,
+        warp::{SignRequest, SignResponse},
+    },
+};
+use prost::bytes::Bytes;
+use tonic::{Request, Response, Status};
+
+/// A gRPC server which wraps a subnet::rpc::warp::Signer impl allowing client control over RPC.
+#[derive(Clone)]
+pub struct Server<S> {
+    inner: Arc<S>,
+}
+
+impl<S> Server<S>
+where
+    S: super::Signer + 'static,
+{
+    pub fn new(signer: S) -> Self {
+        Self {
+            inner: Arc::new(signer),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl<S> pb::warp::signer_server::Signer for Server<S>
+where
+    S: super::Signer + 'static,
+{
+    async fn sign(&self, request: Request<SignRequest>) -> Result<Response<SignResponse>, Status> {
+        let req = request.into_inner();
+        if req.source_chain_id.len() != ids::LEN {
+            return Err(Status::invalid_argument(format!(
+                "invalid source chain ID length {} (expected {})",
+                req.source_chain_id.len(),
+                ids::LEN
+            )));
+        }
+
+        let msg = UnsignedMessage::new(
+            req.network_id,
+            ids::Id::from_slice(&req.source_chain_id),
+            &req.payload,
+        );
+        let signature = self
+            .inner
+            .sign(&msg)
+            .await
+            .map_err(|e| Error::new(ErrorKind::Other, format!("sign failed: {:?}", e)))?;
+
+        Ok(Response::new(SignResponse {
+            signature: Bytes::from(signature),
+        }))
+    }
+}
@@ -0,0 +1,412 @@
+//! Verifies the credentials of the signed txs against the spent UTXOs,
+//! independent of the node.
+use crate::{
+    errors::{Error, Result},
+    hash,
+    ids::short,
+    key,
+    txs::{self, transferable, utxo},
+};
+
+/// Signed tx whose credentials authorize the spends of its inputs.
+/// The credentials are ordered as the spent inputs, followed by the
+/// subnet authorization credential if any.
+/// ref. "vms/platformvm/txs/executor.verifyInputs"
+/// ref. "vms/avm/txs/executor.SemanticVerifier"
+pub trait Signed {
+    fn base_tx(&self) -> &txs::Tx;
+
+    fn credentials(&self) -> Vec<&key::secp256k1::txs::Credential>;
+
+    /// Returns the inputs in the order of the credentials.
+    fn spent_inputs(&self) -> Vec<&transferable::Input> {
+        self.base_tx()
+            .transferable_inputs
+            .as_ref()
+            .map(|ins| ins.iter().collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the subnet authorization of the subnet owner, if any.
+    fn subnet_auth(&self) -> Option<&key::secp256k1::txs::Input> {
+        None
+    }
+
+    /// Returns "true" if the tx stakes its inputs, so it may spend the
+    /// stakeable UTXOs that are still locked.
+    fn is_staking(&self) -> bool {
+        false
+    }
+
+    /// Verifies each credential against the owners of the spent UTXO,
+    /// and the subnet authorization against "subnet_owners".
+    /// Returns the recovered signers per credential, in the credential order.
+    /// "now" is the unix timestamp in seconds, to check the locktimes.
+    fn verify_credentials(
+        &self,
+        utxos: &[utxo::Utxo],
+        subnet_owners: Option<&key::secp256k1::txs::OutputOwners>,
+        now: u64,
+    ) -> Result<Vec<Vec<short::Id>>> {
+        let metadata = self
+            .base_tx()
+            .metadata
+            .as_ref()
+            .ok_or_else(|| Error::Other {
+                message: "tx has no metadata (not signed)".to_string(),
+                retryable: false,
+            })?;
+        let tx_hash = hash::sha256(&metadata.tx_bytes_with_no_signature);
+
+        let inputs = self.spent_inputs();
+        let creds = self.credentials();
+        let subnet_auth = self.subnet_auth();
+        let expected_creds = inputs.len() + usize::from(subnet_auth.is_some());
+        if creds.len() != expected_creds {
+            return Err(Error::Other {
+                message: format!(
+                    "wrong number of credentials {} (expected {})",
+                    creds.len(),
+                    expected_creds
+                ),
+                retryable: false,
+            });
+        }
+
+        let mut signers = Vec::with_capacity(creds.len());
+        for (i, input) in inputs.iter().enumerate() {
+            let utxo = utxos
+                .iter()
+                .find(|u| u.utxo_id == input.utxo_id)
+                .ok_or_else(|| Error::Other {
+                    message: format!(
+                        "missing UTXO {}:{} for input {}",
+                        input.utxo_id.tx_id, input.utxo_id.output_index, i
+                    ),
+                    retryable: false,
+                })?;
+            let (sig_indices, owners) = spend(i, input, utxo, self.is_staking(), now)?;
+
+            let s = creds[i]
+                .verify_spend(&tx_hash, sig_indices, owners, now)
+                .map_err(|e| Error::Other {
+                    message: format!("failed to verify credential {} '{}'", i, e.message()),
+                    retryable: false,
+                })?;
+            signers.push(s);
+        }
+
+        if let Some(subnet_auth) = subnet_auth {
+            let owners = subnet_owners.ok_or_else(|| Error::Other {
+                message: "tx has subnet authorization but no subnet owners given".to_string(),
+                retryable: false,
+            })?;
+            let s = creds[inputs.len()]
+                .verify_spend(&tx_hash, &subnet_auth.sig_indices, owners, now)
+                .map_err(|e| Error::Other {
+                    message: format!("failed to verify subnet authorization '{}'", e.message()),
+                    retryable: false,
+                })?;
+            signers.push(s);
+        }
+
+        Ok(signers)
+    }
+}
+
+/// Returns the signature indices of the input and the owners of the spent
+/// UTXO, after checking that the input spends the UTXO as it is.
+/// The stakeable UTXO still locked at "now" must be spent by the locked
+/// input of the same locktime, and only by a staking tx.
+/// ref. "vms/secp256k1fx.Fx.VerifyTransfer"
+/// ref. "vms/platformvm/utxo.handler.VerifySpendUTXOs"
+fn spend<'a>(
+    i: usize,
+    input: &'a transferable::Input,
+    utxo: &'a utxo::Utxo,
+    is_staking: bool,
+    now: u64,
+) -> Result<(&'a [u32], &'a key::secp256k1::txs::OutputOwners)> {
+    if input.asset_id != utxo.asset_id {
+        return Err(Error::Other {
+            message: format!(
+                "input {} asset ID {} != UTXO asset ID {}",
+                i, input.asset_id, utxo.asset_id
+            ),
+            retryable: false,
+        });
+    }
+
+    let transfer_input = if let Some(transfer_input) = &input.transfer_input {
+        transfer_input
+    } else if let Some(stakeable_lock_in) = &input.stakeable_lock_in {
+        &stakeable_lock_in.transfer_input
+    } else {
+        return Err(Error::Other {
+            message: format!("input {} has no transfer input", i),
+            retryable: false,
+        });
+    };
+
+    let transfer_output = if let Some(transfer_output) = &utxo.transfer_output {
+        transfer_output
+    } else if let Some(stakeable_lock_out) = &utxo.stakeable_lock_out {
+        &stakeable_lock_out.transfer_output
+    } else {
+        return Err(Error::Other {
+            message: format!("UTXO of input {} has no transfer output", i),
+            retryable: false,
+        });
+    };
+
+    // the expired lock no longer restricts the spend
+    let utxo_locktime = match &utxo.stakeable_lock_out {
+        Some(stakeable_lock_out) if stakeable_lock_out.locktime > now => {
+            stakeable_lock_out.locktime
+        }
+        _ => 0,
+    };
+    let input_locktime = input
+        .stakeable_lock_in
+        .as_ref()
+        .map(|stakeable_lock_in| stakeable_lock_in.locktime)
+        .unwrap_or_default();
+    if input_locktime != utxo_locktime {
+        return Err(Error::Other {
+            message: format!(
+                "input {} locktime {} != UTXO locktime {}", // ref. "errLockedFundsNotMarkedAsLocked"
+                i, input_locktime, utxo_locktime
+            ),
+            retryable: false,
+        });
+    }
+    if utxo_locktime != 0 && !is_staking {
+        return Err(Error::Other {
+            message: format!(
+                "input {} spends the UTXO locked until {} in a non-staking tx",
+                i, utxo_locktime
+            ),
+            retryable: false,
+        });
+    }
+
+    if transfer_input.amount != transfer_output.amount {
+        return Err(Error::Other {
+            message: format!(
+                "input {} amount {} != UTXO amount {}", // ref. "errMismatchedAmounts"
+                i, transfer_input.amount, transfer_output.amount
+            ),
+            retryable: false,
+        });
+    }
+    Ok((&transfer_input.sig_indices, &transfer_output.output_owners))
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib -- txs::credentials::test_verify_credentials --exact --show-output
+#[test]
+fn test_verify_credentials() {
+    use crate::{ids, platformvm};
+
+    macro_rules! ab {
+        ($e:expr) => {
+            tokio_test::block_on($e)
+        };
+    }
+
+    let k1 = key::secp256k1::private_key::Key::generate().unwrap();
+    let k2 = key::secp256k1::private_key::Key::generate().unwrap();
+    let k3 = key::secp256k1::private_key::Key::generate().unwrap();
+    let addr = |k: &key::secp256k1::private_key::Key| k.to_public_key().to_short_id().unwrap();
+
+    // 2-of-3 multisig UTXO, and the single-owner UTXO locked until 100
+    let mut multisig_addrs = vec![addr(&k1), addr(&k2), addr(&k3)];
+    multisig_addrs.sort();
+    let asset_id = ids::Id::sha256("asset");
+    let utxos = vec![
+        utxo::Utxo {
+            utxo_id: utxo::Id::new(&ids::Id::sha256("tx1").to_vec(), 0, false).unwrap(),
+            asset_id,
+            transfer_output: Some(key::secp256k1::txs::transfer::Output::new(
+                1000,
+                key::secp256k1::txs::OutputOwners::new(0, 2, &multisig_addrs),
+            )),
+            ..utxo::Utxo::default()
+        },
+        utxo::Utxo {
+            utxo_id: utxo::Id::new(&ids::Id::sha256("tx2").to_vec(), 1, false).unwrap(),
+            asset_id,
+            transfer_output: Some(key::secp256k1::txs::transfer::Output::new(
+                500,
+                key::secp256k1::txs::OutputOwners::new(100, 1, &[addr(&k3)]),
+            )),
+            ..utxo::Utxo::default()
+        },
+    ];
+
+    // sign the multisig input with the owners at the sig indices
+    let signers_of = |idx: &[u32]| {
+        idx.iter()
+            .map(|i| {
+                [&k1, &k2, &k3]
+                    .into_iter()
+                    .find(|k| addr(k) == multisig_addrs[*i as usize])
+                    .unwrap()
+                    .clone()
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let subnet_owners = key::secp256k1::txs::OutputOwners::new(0, 1, &[addr(&k2)]);
+    let build = |sig_indices: Vec<u32>| platformvm::txs::add_subnet_validator::Tx {
+        base_tx: txs::Tx {
+            network_id: 1,
+            transferable_inputs: Some(vec![
+                transferable::Input {
+                    utxo_id: utxos[0].utxo_id.clone(),
+                    asset_id,
+                    transfer_input: Some(key::secp256k1::txs::transfer::Input::new(
+                        1000,
+                        sig_indices,
+                    )),
+                    ..transferable::Input::default()
+                },
+                transferable::Input {
+                    utxo_id: utxos[1].utxo_id.clone(),
+                    asset_id,
+                    transfer_input: Some(key::secp256k1::txs::transfer::Input::new(500, vec![0])),
+                    ..transferable::Input::default()
+                },
+            ]),
+            ..txs::Tx::default()
+        },
+        subnet_auth: key::secp256k1::txs::Input::new(vec![0]),
+        ..platformvm::txs::add_subnet_validator::Tx::default()
+    };
+
+    let mut tx = build(vec![0, 2]);
+    ab!(tx.sign(vec![
+        signers_of(&[0, 2]),
+        vec![k3.clone()],
+        vec![k2.clone()]
+    ]))
+    .unwrap();
+
+    let signers = tx
+        .verify_credentials(&utxos, Some(&subnet_owners), 100)
+        .unwrap();
+    assert_eq!(
+        signers,
+        vec![
+            vec![multisig_addrs[0].clone(), multisig_addrs[2].clone()],
+            vec![addr(&k3)],
+            vec![addr(&k2)],
+        ]
+    );
+
+    // locked
+    assert!(tx
+        .verify_credentials(&utxos, Some(&subnet_owners), 99)
+        .is_err());
+    // missing subnet owners, or the subnet is owned by someone else
+    assert!(tx.verify_credentials(&utxos, None, 100).is_err());
+    let other_owners = key::secp256k1::txs::OutputOwners::new(0, 1, &[addr(&k1)]);
+    assert!(tx
+        .verify_credentials(&utxos, Some(&other_owners), 100)
+        .is_err());
+    // missing UTXO
+    assert!(tx
+        .verify_credentials(&utxos[..1], Some(&subnet_owners), 100)
+        .is_err());
+
+    // signed by the owners other than the claimed indices
+    let mut tx = build(vec![0, 2]);
+    ab!(tx.sign(vec![
+        signers_of(&[0, 1]),
+        vec![k3.clone()],
+        vec![k2.clone()]
+    ]))
+    .unwrap();
+    let err = tx
+        .verify_credentials(&utxos, Some(&subnet_owners), 100)
+        .unwrap_err();
+    assert!(err.contains("wrong signature"));
+
+    // below the threshold
+    let mut tx = build(vec![1]);
+    ab!(tx.sign(vec![signers_of(&[1]), vec![k3.clone()], vec![k2.clone()]])).unwrap();
+    let err = tx
+        .verify_credentials(&utxos, Some(&subnet_owners), 100)
+        .unwrap_err();
+    assert!(err.contains("too few signers"));
+
+    // missing credential
+    let mut tx = build(vec![0, 2]);
+    ab!(tx.sign(vec![signers_of(&[0, 2]), vec![k3.clone()]])).unwrap();
+    assert!(tx
+        .verify_credentials(&utxos, Some(&subnet_owners), 100)
+        .is_err());
+
+    // not signed
+    let tx = build(vec![0, 2]);
+    assert!(tx
+        .verify_credentials(&utxos, Some(&subnet_owners), 100)
+        .is_err());
+
+    // stakeable UTXO locked until 200, spendable only by the staking tx
+    // with the locked input of the same locktime
+    let stakeable_utxos = vec![utxo::Utxo {
+        utxo_id: utxo::Id::new(&ids::Id::sha256("tx3").to_vec(), 0, false).unwrap(),
+        asset_id,
+        stakeable_lock_out: Some(platformvm::txs::StakeableLockOut {
+            locktime: 200,
+            transfer_output: key::secp256k1::txs::transfer::Output::new(
+                2000,
+                key::secp256k1::txs::OutputOwners::new(0, 1, &[addr(&k1)]),
+            ),
+        }),
+        ..utxo::Utxo::default()
+    }];
+    let stakeable_base_tx = |locktime: u64| txs::Tx {
+        network_id: 1,
+        transferable_inputs: Some(vec![transferable::Input {
+            utxo_id: stakeable_utxos[0].utxo_id.clone(),
+            asset_id,
+            stakeable_lock_in: Some(platformvm::txs::StakeableLockIn {
+                locktime,
+                transfer_input: key::secp256k1::txs::transfer::Input::new(2000, vec![0]),
+            }),
+            ..transferable::Input::default()
+        }]),
+        ..txs::Tx::default()
+    };
+
+    let mut tx = platformvm::txs::add_validator::Tx::new(stakeable_base_tx(200));
+    ab!(tx.sign(vec![vec![k1.clone()]])).unwrap();
+    let signers = tx.verify_credentials(&stakeable_utxos, None, 100).unwrap();
+    assert_eq!(signers, vec![vec![addr(&k1)]]);
+    // the expired lock must be spent as unlocked
+    let err = tx
+        .verify_credentials(&stakeable_utxos, None, 200)
+        .unwrap_err();
+    assert!(err.contains("input 0 locktime 200 != UTXO locktime 0"));
+
+    // locktime mismatch
+    let mut tx = platformvm::txs::add_validator::Tx::new(stakeable_base_tx(150));
+    ab!(tx.sign(vec![vec![k1.clone()]])).unwrap();
+    let err = tx
+        .verify_credentials(&stakeable_utxos, None, 100)
+        .unwrap_err();
+    assert!(err.contains("input 0 locktime 150 != UTXO locktime 200"));
+
+    // still locked, but not staked
+    let mut tx = platformvm::txs::add_subnet_validator::Tx {
+        base_tx: stakeable_base_tx(200),
+        subnet_auth: key::secp256k1::txs::Input::new(vec![0]),
+        ..platformvm::txs::add_subnet_validator::Tx::default()
+    };
+    ab!(tx.sign(vec![vec![k1.clone()], vec![k2.clone()]])).unwrap();
+    let err = tx
+        .verify_credentials(&stakeable_utxos, Some(&subnet_owners), 100)
+        .unwrap_err();
+    assert!(err.contains("non-staking tx"));
+}
@@ -1,3 +1,4 @@
+pub mod credentials;
 pub mod raw;
 pub mod transferable;
 pub mod utxo;
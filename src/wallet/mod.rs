@@ -1,4 +1,5 @@
 pub mod p;
+pub mod waiter;
 pub mod x;
 
 #[cfg(feature = "wallet_evm")]
@@ -4,8 +4,9 @@ use crate::{
     errors::{Error, Result},
     formatting,
     ids::{self, node},
-    jsonrpc::client::p as client_p,
-    key, platformvm, txs, units,
+    jsonrpc::client::{info as client_info, p as client_p},
+    key, platformvm, staking, txs, units,
+    wallet::waiter::{Chain, TxWaiter},
 };
 use chrono::{DateTime, NaiveDateTime, Utc};
 use tokio::time::{sleep, Duration, Instant};
@@ -36,6 +37,10 @@ where
 
     pub proof_of_possession: key::bls::ProofOfPossession,
 
+    /// HTTP endpoint of the validator node, to check its node ID and
+    /// proof of possession via "info.getNodeID" before staking.
+    pub node_http_rpc: Option<String>,
+
     /// Validate reward fee in percent.
     pub reward_fee_percent: u32,
 
@@ -83,6 +88,7 @@ where
             end_time,
             reward_fee_percent: 2,
             proof_of_possession: key::bls::ProofOfPossession::default(),
+            node_http_rpc: None,
             check_acceptance: false,
             poll_initial_wait: Duration::from_secs(62), // enough to elapse validate start time
             poll_interval: Duration::from_secs(1),
@@ -162,6 +168,22 @@ where
         self
     }
 
+    /// Sets the validator node Id and proof of possession from the node identity.
+    #[must_use]
+    pub fn identity(mut self, identity: &staking::identity::Identity) -> Self {
+        self.node_id = identity.node_id;
+        self.proof_of_possession = identity.proof_of_possession.clone();
+        self
+    }
+
+    /// Sets the HTTP endpoint of the validator node, to check that the node
+    /// runs with the same node Id and BLS key before staking.
+    #[must_use]
+    pub fn node_http_rpc(mut self, node_http_rpc: String) -> Self {
+        self.node_http_rpc = Some(node_http_rpc);
+        self
+    }
+
     /// Sets the check acceptance boolean flag.
     #[must_use]
     pub fn check_acceptance(mut self, check_acceptance: bool) -> Self {
@@ -225,6 +247,30 @@ where
             return Ok((ids::Id::empty(), false));
         }
 
+        // subnet signer should be empty, thus only the primary network
+        // validator requires the proof of possession
+        let pop = if self.subnet_id.is_empty() {
+            if !self
+                .proof_of_possession
+                .verify()
+                .map_err(|e| Error::Other {
+                    message: format!("failed to verify proof of possession '{}'", e),
+                    retryable: false,
+                })?
+            {
+                return Err(Error::Other {
+                    message: "invalid proof of possession".to_string(),
+                    retryable: false,
+                });
+            }
+            Some(&self.proof_of_possession)
+        } else {
+            None
+        };
+        if let Some(node_http_rpc) = &self.node_http_rpc {
+            self.verify_node(node_http_rpc, pop).await?;
+        }
+
         let cur_balance_p = self.inner.balance().await?;
         if cur_balance_p < self.stake_amount + self.inner.inner.add_primary_network_validator_fee {
             return Err(Error::Other {
@@ -246,13 +292,7 @@ where
             )
             .await?;
 
-        let signer = if self.subnet_id.is_empty() {
-            // primary network
-            Some(self.proof_of_possession.clone())
-        } else {
-            // subnet signer should be empty
-            None
-        };
+        let signer = pop.cloned();
 
         let mut tx = platformvm::txs::add_permissionless_validator::Tx {
             base_tx: txs::Tx {
@@ -336,45 +376,25 @@ where
             return Ok((tx_id, true));
         }
 
-        // enough time for txs processing
-        log::info!("initial waiting {:?}", self.poll_initial_wait);
-        sleep(self.poll_initial_wait).await;
-
         log::info!("polling to confirm add validator transaction");
-        let (start, mut success) = (Instant::now(), false);
-        loop {
-            let elapsed = start.elapsed();
-            if elapsed.gt(&self.poll_timeout) {
-                break;
-            }
-
-            let resp = client_p::get_tx_status(&picked_http_rpc.1, &tx_id.to_string()).await?;
-
-            let status = resp.result.unwrap().status;
-            if status == platformvm::txs::status::Status::Committed {
-                log::info!("{} successfully committed", tx_id);
-                success = true;
-                break;
-            }
-
-            log::warn!(
-                "{} {} (not accepted yet in {}, elapsed {:?})",
-                tx_id,
-                status,
-                picked_http_rpc.1,
-                elapsed
-            );
-            sleep(self.poll_interval).await;
-        }
-        if !success {
+        let tx_status = TxWaiter::new(&picked_http_rpc.1, Chain::P)
+            .initial_wait(self.poll_initial_wait)
+            .initial_interval(self.poll_interval)
+            .timeout(self.poll_timeout)
+            .wait(&tx_id)
+            .await?;
+        if !tx_status.accepted() {
             return Err(Error::API {
-                message: "failed to check acceptance in time".to_string(),
-                retryable: true,
+                message: format!(
+                    "{} was {} (reason {:?})",
+                    tx_id, tx_status.chain_status, tx_status.reason
+                ),
+                retryable: false,
             });
         }
 
         log::info!("polling to confirm validator");
-        success = false;
+        let (start, mut success) = (Instant::now(), false);
         loop {
             let elapsed = start.elapsed();
             if elapsed.gt(&self.poll_timeout) {
@@ -411,4 +431,40 @@ where
 
         Ok((tx_id, true))
     }
+
+    /// Checks that the node serving "info.getNodeID" at the endpoint runs
+    /// with the validator node Id and the BLS key of the proof of possession.
+    async fn verify_node(
+        &self,
+        node_http_rpc: &str,
+        pop: Option<&key::bls::ProofOfPossession>,
+    ) -> Result<()> {
+        let resp = client_info::get_node_id(node_http_rpc).await?;
+        if let Some(e) = resp.error {
+            return Err(Error::API {
+                message: format!("failed info.getNodeID from {} {:?}", node_http_rpc, e),
+                retryable: false,
+            });
+        }
+        let result = resp.result.ok_or_else(|| Error::API {
+            message: format!("unexpected None GetNodeIdResult from {}", node_http_rpc),
+            retryable: true,
+        })?;
+
+        staking::identity::verify_node_id_result(&self.node_id, pop, &result).map_err(|e| {
+            Error::Other {
+                message: format!(
+                    "node {} does not match the validator '{}'",
+                    node_http_rpc, e
+                ),
+                retryable: false,
+            }
+        })?;
+        log::info!(
+            "verified node Id '{}' and its proof of possession via {}",
+            self.node_id,
+            node_http_rpc
+        );
+        Ok(())
+    }
 }
@@ -3,8 +3,9 @@ use crate::{
     formatting, ids,
     jsonrpc::client::p as client_p,
     key, platformvm, txs,
+    wallet::waiter::{Chain, TxWaiter},
 };
-use tokio::time::{sleep, Duration, Instant};
+use tokio::time::Duration;
 
 /// Represents P-chain "CreateChain" transaction.
 /// ref. <https://github.com/luxfi/node/blob/v1.9.4/wallet/chain/p/builder.go#L459-L498> "NewCreateChainTx"
@@ -174,40 +175,20 @@ where
             return Ok(tx_id);
         }
 
-        // enough time for txs processing
-        log::info!("initial waiting {:?}", self.poll_initial_wait);
-        sleep(self.poll_initial_wait).await;
-
         log::info!("polling to confirm create chain transaction");
-        let (start, mut success) = (Instant::now(), false);
-        loop {
-            let elapsed = start.elapsed();
-            if elapsed.gt(&self.poll_timeout) {
-                break;
-            }
-
-            let resp = client_p::get_tx_status(&picked_http_rpc.1, &tx_id.to_string()).await?;
-
-            let status = resp.result.unwrap().status;
-            if status == platformvm::txs::status::Status::Committed {
-                log::info!("{} successfully committed", tx_id);
-                success = true;
-                break;
-            }
-
-            log::warn!(
-                "{} {} (not accepted yet in {}, elapsed {:?})",
-                tx_id,
-                status,
-                picked_http_rpc.1,
-                elapsed
-            );
-            sleep(self.poll_interval).await;
-        }
-        if !success {
+        let tx_status = TxWaiter::new(&picked_http_rpc.1, Chain::P)
+            .initial_wait(self.poll_initial_wait)
+            .initial_interval(self.poll_interval)
+            .timeout(self.poll_timeout)
+            .wait(&tx_id)
+            .await?;
+        if !tx_status.accepted() {
             return Err(Error::API {
-                message: "failed to check acceptance in time".to_string(),
-                retryable: true,
+                message: format!(
+                    "{} was {} (reason {:?})",
+                    tx_id, tx_status.chain_status, tx_status.reason
+                ),
+                retryable: false,
             });
         }
 
@@ -0,0 +1,371 @@
+use std::fmt;
+
+use crate::{
+    choices::status::Status,
+    errors::{Error, Result},
+    ids,
+    jsonrpc::client::{evm as client_evm, p as client_p, x as client_x},
+    platformvm,
+};
+use tokio::time::{sleep, Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+/// Defines the chain that the transaction was issued to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Chain {
+    /// Polls "avm.getTxStatus".
+    X,
+    /// Polls "platform.getTxStatus".
+    P,
+    /// Polls "avax.getAtomicTxStatus" for C-chain atomic (import/export) transactions.
+    C,
+}
+
+impl fmt::Display for Chain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Chain::X => write!(f, "X"),
+            Chain::P => write!(f, "P"),
+            Chain::C => write!(f, "C"),
+        }
+    }
+}
+
+/// Final status of a waited transaction.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TxStatus {
+    pub tx_id: ids::Id,
+    /// Either "Accepted" or "Rejected".
+    /// P-chain "Committed" is reported as "Accepted", and "Aborted"
+    /// and "Dropped" as "Rejected".
+    pub status: Status,
+    /// Status as reported by the chain (e.g., "Committed", "Aborted" or
+    /// "Dropped" on the P-chain), to tell the rejections apart.
+    pub chain_status: String,
+    /// Reason why the transaction was rejected or dropped, if the chain
+    /// reports one.
+    pub reason: Option<String>,
+}
+
+impl TxStatus {
+    pub fn accepted(&self) -> bool {
+        self.status == Status::Accepted
+    }
+}
+
+/// Waits for issued transactions to be decided, by polling the chain's
+/// transaction status API with exponential backoff.
+#[derive(Clone, Debug)]
+pub struct TxWaiter {
+    pub http_rpc: String,
+    pub chain: Chain,
+
+    /// Initial wait duration before the first poll.
+    pub initial_wait: Duration,
+    /// Wait duration after the first poll, multiplied by "multiplier" after each poll.
+    pub initial_interval: Duration,
+    /// Upper bound of the wait duration between polls.
+    pub max_interval: Duration,
+    /// Factor to grow the wait duration between polls.
+    pub multiplier: f64,
+    /// Maximum duration for polling, including the initial wait.
+    pub timeout: Duration,
+
+    /// Cancels all in-flight waits when triggered.
+    pub cancel: CancellationToken,
+}
+
+impl TxWaiter {
+    pub fn new(http_rpc: &str, chain: Chain) -> Self {
+        Self {
+            http_rpc: http_rpc.to_string(),
+            chain,
+            initial_wait: Duration::from_millis(500),
+            initial_interval: Duration::from_millis(500),
+            max_interval: Duration::from_secs(5),
+            multiplier: 1.5,
+            timeout: Duration::from_secs(300),
+            cancel: CancellationToken::new(),
+        }
+    }
+
+    /// Sets the initial wait time before the first poll.
+    #[must_use]
+    pub fn initial_wait(mut self, initial_wait: Duration) -> Self {
+        self.initial_wait = initial_wait;
+        self
+    }
+
+    /// Sets the wait time after the first poll.
+    #[must_use]
+    pub fn initial_interval(mut self, initial_interval: Duration) -> Self {
+        self.initial_interval = initial_interval;
+        self
+    }
+
+    /// Sets the maximum wait time between polls.
+    #[must_use]
+    pub fn max_interval(mut self, max_interval: Duration) -> Self {
+        self.max_interval = max_interval;
+        self
+    }
+
+    /// Sets the backoff multiplier.
+    #[must_use]
+    pub fn multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Sets the poll timeout.
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets the cancellation token.
+    /// Use a child token (e.g., "CancellationToken::child_token") to cancel
+    /// the waits together with other tasks.
+    #[must_use]
+    pub fn cancel(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Waits until the transaction is accepted or rejected.
+    /// Returns an error if the wait times out (retryable) or is cancelled.
+    /// Polls that fail transiently (e.g., connection errors) are logged and
+    /// retried until the timeout, while the other errors (e.g., the API
+    /// rejecting the request) are returned immediately.
+    pub async fn wait(&self, tx_id: &ids::Id) -> Result<TxStatus> {
+        let start = Instant::now();
+        let mut interval = self.initial_interval;
+
+        log::info!(
+            "waiting for {} on {}-chain (initial wait {:?})",
+            tx_id,
+            self.chain,
+            self.initial_wait
+        );
+        self.sleep_or_cancel(tx_id, self.initial_wait).await?;
+
+        loop {
+            match self.poll(tx_id).await {
+                Ok(Some(status)) => {
+                    log::info!(
+                        "{} {} (elapsed {:?})",
+                        tx_id,
+                        status.status,
+                        start.elapsed()
+                    );
+                    return Ok(status);
+                }
+                Ok(None) => log::debug!(
+                    "{} not decided yet in {} (elapsed {:?})",
+                    tx_id,
+                    self.http_rpc,
+                    start.elapsed()
+                ),
+                Err(e) if e.retryable() => {
+                    log::warn!("failed to poll {} status ({}), retrying", tx_id, e)
+                }
+                Err(e) => return Err(e),
+            }
+
+            let elapsed = start.elapsed();
+            if elapsed >= self.timeout {
+                return Err(Error::API {
+                    message: format!("failed to check acceptance of {} in time", tx_id),
+                    retryable: true,
+                });
+            }
+
+            self.sleep_or_cancel(tx_id, interval.min(self.timeout - elapsed))
+                .await?;
+            interval = self.next_interval(interval);
+        }
+    }
+
+    /// Waits for all transactions concurrently, and returns the results
+    /// in the same order as "tx_ids".
+    pub async fn wait_all(&self, tx_ids: &[ids::Id]) -> Vec<Result<TxStatus>> {
+        futures::future::join_all(tx_ids.iter().map(|tx_id| self.wait(tx_id))).await
+    }
+
+    fn next_interval(&self, interval: Duration) -> Duration {
+        let max_interval = self.max_interval.max(self.initial_interval);
+        interval.mul_f64(self.multiplier.max(1.0)).min(max_interval)
+    }
+
+    async fn sleep_or_cancel(&self, tx_id: &ids::Id, d: Duration) -> Result<()> {
+        tokio::select! {
+            _ = self.cancel.cancelled() => Err(Error::Other {
+                message: format!("cancelled waiting for {}", tx_id),
+                retryable: false,
+            }),
+            _ = sleep(d) => Ok(()),
+        }
+    }
+
+    /// Returns the final status, or None if the transaction is not decided yet.
+    async fn poll(&self, tx_id: &ids::Id) -> Result<Option<TxStatus>> {
+        let decided = |status: Status, chain_status: &str, reason: Option<String>| {
+            Some(TxStatus {
+                tx_id: *tx_id,
+                status,
+                chain_status: chain_status.to_string(),
+                reason,
+            })
+        };
+
+        match self.chain {
+            Chain::X => {
+                let resp = client_x::get_tx_status(&self.http_rpc, &tx_id.to_string()).await?;
+                let result = resp.result.ok_or_else(|| Error::API {
+                    message: format!("failed avm.getTxStatus {:?}", resp.error),
+                    retryable: false,
+                })?;
+                match result.status {
+                    // the X-chain reports no rejection reason
+                    Status::Accepted | Status::Rejected => {
+                        Ok(decided(result.status.clone(), result.status.as_str(), None))
+                    }
+                    _ => Ok(None),
+                }
+            }
+            Chain::P => {
+                let resp = client_p::get_tx_status(&self.http_rpc, &tx_id.to_string()).await?;
+                let result = resp.result.ok_or_else(|| Error::API {
+                    message: format!("failed platform.getTxStatus {:?}", resp.error),
+                    retryable: false,
+                })?;
+                let status = match result.status {
+                    platformvm::txs::status::Status::Committed => Status::Accepted,
+                    platformvm::txs::status::Status::Aborted
+                    | platformvm::txs::status::Status::Dropped => Status::Rejected,
+                    _ => return Ok(None),
+                };
+                Ok(decided(status, result.status.as_str(), result.reason))
+            }
+            Chain::C => {
+                let resp = client_evm::get_atomic_tx_status(&self.http_rpc, tx_id).await?;
+                let result = resp.result.ok_or_else(|| Error::API {
+                    message: format!("failed avax.getAtomicTxStatus {:?}", resp.error),
+                    retryable: false,
+                })?;
+                match result.status.as_str() {
+                    "Accepted" => Ok(decided(Status::Accepted, &result.status, None)),
+                    "Dropped" => Ok(decided(Status::Rejected, &result.status, None)),
+                    _ => Ok(None),
+                }
+            }
+        }
+    }
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib -- wallet::waiter::test_next_interval --exact --show-output
+#[test]
+fn test_next_interval() {
+    let w = TxWaiter::new("http://127.0.0.1:9650", Chain::X)
+        .initial_interval(Duration::from_secs(1))
+        .max_interval(Duration::from_secs(3))
+        .multiplier(2.0);
+    assert_eq!(
+        w.next_interval(Duration::from_secs(1)),
+        Duration::from_secs(2)
+    );
+    assert_eq!(
+        w.next_interval(Duration::from_secs(2)),
+        Duration::from_secs(3)
+    );
+    assert_eq!(
+        w.next_interval(Duration::from_secs(3)),
+        Duration::from_secs(3)
+    );
+
+    // never shorter than the initial interval
+    let w = w.max_interval(Duration::from_millis(100));
+    assert_eq!(
+        w.next_interval(Duration::from_secs(1)),
+        Duration::from_secs(1)
+    );
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib -- wallet::waiter::test_cancel --exact --show-output
+#[tokio::test]
+async fn test_cancel() {
+    let cancel = CancellationToken::new();
+    let w = TxWaiter::new("http://127.0.0.1:1", Chain::P)
+        .initial_wait(Duration::from_secs(60))
+        .cancel(cancel.clone());
+
+    cancel.cancel();
+    let results = w.wait_all(&[ids::Id::empty(), ids::Id::empty()]).await;
+    assert_eq!(results.len(), 2);
+    for res in results {
+        let err = res.unwrap_err();
+        assert!(!err.retryable());
+        assert!(err.contains("cancelled"));
+    }
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib -- wallet::waiter::test_retry_connection_errors --exact --show-output
+#[tokio::test]
+async fn test_retry_connection_errors() {
+    let w = TxWaiter::new("http://127.0.0.1:1", Chain::X)
+        .initial_wait(Duration::from_millis(10))
+        .initial_interval(Duration::from_millis(10))
+        .timeout(Duration::from_millis(200));
+
+    // connection errors are retried until the timeout
+    let err = w.wait(&ids::Id::empty()).await.unwrap_err();
+    assert!(err.retryable());
+    assert!(err.contains("in time"));
+}
+
+/// RUST_LOG=debug cargo test --package lux-types --lib -- wallet::waiter::test_p_chain_status --exact --show-output
+#[tokio::test]
+async fn test_p_chain_status() {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    // responds to each poll with the next body
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let http_rpc = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        for body in [
+            r#"{"jsonrpc":"2.0","id":1,"result":{"status":"Dropped","reason":"failed to verify tx: missing UTXO"}}"#,
+            r#"{"jsonrpc":"2.0","id":1,"result":{"status":"Aborted"}}"#,
+        ] {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0; 4096];
+            let _ = stream.read(&mut buf).await.unwrap();
+            let resp = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(resp.as_bytes()).await.unwrap();
+        }
+    });
+
+    let w = TxWaiter::new(&http_rpc, Chain::P)
+        .initial_wait(Duration::from_millis(10))
+        .timeout(Duration::from_secs(5));
+    let dropped = w.wait(&ids::Id::empty()).await.unwrap();
+    assert_eq!(dropped.status, Status::Rejected);
+    assert_eq!(dropped.chain_status, "Dropped");
+    assert_eq!(
+        dropped.reason.as_deref(),
+        Some("failed to verify tx: missing UTXO")
+    );
+
+    // no placeholder reason if the chain reports none
+    let aborted = w.wait(&ids::Id::empty()).await.unwrap();
+    assert_eq!(aborted.status, Status::Rejected);
+    assert_eq!(aborted.chain_status, "Aborted");
+    assert_eq!(aborted.reason, None);
+}
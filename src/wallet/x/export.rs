@@ -2,13 +2,13 @@ use std::{cmp, time::SystemTime};
 
 use crate::{
     avm,
-    choices::status::Status,
     errors::{Error, Result},
     formatting, ids,
     jsonrpc::client::x as client_x,
     key, txs,
+    wallet::waiter::{Chain, TxWaiter},
 };
-use tokio::time::{sleep, Duration, Instant};
+use tokio::time::Duration;
 
 /// Represents X-chain "Export" transaction.
 /// ref. <https://github.com/luxfi/node/blob/v1.9.4/wallet/chain/x/builder.go> "NewExportTx".
@@ -260,40 +260,20 @@ where
             return Ok(tx_id);
         }
 
-        // enough time for txs processing
-        log::info!("initial waiting {:?}", self.poll_initial_wait);
-        sleep(self.poll_initial_wait).await;
-
         log::info!("polling to confirm base transaction");
-        let (start, mut success) = (Instant::now(), false);
-        loop {
-            let elapsed = start.elapsed();
-            if elapsed.gt(&self.poll_timeout) {
-                break;
-            }
-
-            let resp = client_x::get_tx_status(&picked_http_rpc.1, &tx_id.to_string()).await?;
-
-            let status = resp.result.unwrap().status;
-            if status == Status::Accepted {
-                log::info!("{} successfully accepted", tx_id);
-                success = true;
-                break;
-            }
-
-            log::warn!(
-                "{} {} (not accepted yet in {}, elapsed {:?})",
-                tx_id,
-                status,
-                picked_http_rpc.1,
-                elapsed
-            );
-            sleep(self.poll_interval).await;
-        }
-        if !success {
+        let tx_status = TxWaiter::new(&picked_http_rpc.1, Chain::X)
+            .initial_wait(self.poll_initial_wait)
+            .initial_interval(self.poll_interval)
+            .timeout(self.poll_timeout)
+            .wait(&tx_id)
+            .await?;
+        if !tx_status.accepted() {
             return Err(Error::API {
-                message: "failed to check acceptance in time".to_string(),
-                retryable: true,
+                message: format!(
+                    "{} was {} (reason {:?})",
+                    tx_id, tx_status.chain_status, tx_status.reason
+                ),
+                retryable: false,
             });
         }
 